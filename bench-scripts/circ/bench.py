@@ -65,7 +65,7 @@ for ds in dss:
                 continue
             for t in ts_map:
                 for kr in key_ranges(ds):
-                    cmd = [os.path.join(BIN_PATH, mm), '-i', str(i), '-d', ds, '-g', str(g), '-t', t, '-r', str(kr), '-o', os.path.join(RESULTS_PATH, f'{ds}.csv')]
+                    cmd = [os.path.join(BIN_PATH, "smr-bench"), '-m', mm, '-i', str(i), '-d', ds, '-g', str(g), '-t', t, '-r', str(kr), '-o', os.path.join(RESULTS_PATH, f'{ds}.csv')]
                     cmds.append(cmd)
                     estimated_time += i * (1.1 if int(kr) <= 100000 else 1.5)
 
@@ -296,7 +296,7 @@ if __name__ == '__main__':
     for ds in dss_all:
         data = pd.read_csv(f'{RESULTS_PATH}/' + ds + '.csv')
 
-        data = data.drop(['bag_size'], axis=1, errors='ignore')
+        data = data.drop(['bag_size', 'key_dist', 'affinity', 'prefill_strategy'], axis=1, errors='ignore')
         data.throughput = data.throughput.map(lambda x: x / 1_000_000)
         data.peak_mem = data.peak_mem.map(lambda x: x / (2 ** 20))
         data.avg_mem = data.avg_mem.map(lambda x: x / (2 ** 20))
@@ -304,14 +304,14 @@ if __name__ == '__main__':
         raw_data[ds] = data.copy()
 
         # take average of each runs
-        avg = data.groupby(['ds', 'mm', 'threads', 'non_coop', 'get_rate', 'key_range']).mean().reset_index()
+        avg = data.groupby(['ds', 'mm', 'threads', 'non_coop', 'get', 'insert', 'remove', 'key_range']).mean().reset_index()
 
         # sort by SMR_I
         avg[SMR_ONLY] = pd.Categorical(avg.mm.map(str), SMR_ONLYs)
         avg[SMR_I] = pd.Categorical(avg.mm.map(str) + avg.non_coop.map(n_map), SMR_Is)
         avg.sort_values(by=SMR_I, inplace=True)
-        for i, bench in enumerate([WRITE, HALF, READ]):
-            avg_data[bench][ds] = avg[avg.get_rate == i]
+        for get, bench in zip([0, 50, 90, 100], [WRITE, HALF, READ]):
+            avg_data[bench][ds] = avg[avg['get'] == get]
 
     tasks = []
 
@@ -41,7 +41,8 @@ def make_cmd(mm, i, ds, g, t, kr):
         mm = mm[:len(mm)-len("-large")]
         bag = "large"
 
-    return [os.path.join(BIN_PATH, mm),
+    return [os.path.join(BIN_PATH, "smr-bench"),
+            '-m', mm,
             '-i', str(i),
             '-d', str(ds),
             '-g', str(g),
@@ -56,8 +57,6 @@ def invalid(mm, ds, g):
         is_invalid |= g == 0  # HHSList is just HList with faster get()
     if mm == 'hp':
         is_invalid |= ds in ["h-list", "hhs-list", "nm-tree"]
-    if mm == 'nbr':
-        is_invalid |= ds in ["hm-list", "skip-list"]
     return is_invalid
 
 cmds = []
@@ -171,18 +171,18 @@ for ds in dss_all:
     data.peak_garb = data.peak_garb.map(lambda x: x / 10000)
     data.avg_garb = data.avg_garb.map(lambda x: x / 10000)
     data.mm = list(map(lambda tup: tup[0] if tup[1] == "small" else tup[0] + "-large", zip(data.mm, data.bag_size)))
-    data = data.drop("bag_size", axis=1)
+    data = data.drop(["bag_size", "key_dist", "affinity", "prefill_strategy"], axis=1, errors='ignore')
     data = data[data.mm.isin(SMRs)]
 
     raw_data[ds] = data.copy()
 
     # take average of each runs
-    avg = data.groupby(['ds', 'mm', 'threads', 'non_coop', 'get_rate', 'key_range']).mean().reset_index()
+    avg = data.groupby(['ds', 'mm', 'threads', 'non_coop', 'get', 'insert', 'remove', 'key_range']).mean().reset_index()
 
     avg[SMR_ONLY] = pd.Categorical(avg.mm.map(str), SMRs)
     avg.sort_values(by=SMR_ONLY, inplace=True)
-    for i, bench in enumerate([WRITE, HALF, READ, READ_ONLY]):
-        avg_data[bench][ds] = avg[avg.get_rate == i]
+    for get, bench in zip([0, 50, 90, 100], [WRITE, HALF, READ, READ_ONLY]):
+        avg_data[bench][ds] = avg[avg['get'] == get]
 
 # 1. throughput graphs, 3 lines (SMR_ONLY) each.
 for ds in dss_write:
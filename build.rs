@@ -0,0 +1,34 @@
+//! Records the build metadata reported in the JSON Lines output of the benchmarks.
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    // `PROFILE` is either `debug` or `release` even for custom profiles (e.g.,
+    // `release-with-debug`), so take the name of the target directory instead:
+    // `<target>/<profile>/build/<pkg>-<hash>/out`.
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let profile = Path::new(&out_dir)
+        .ancestors()
+        .nth(3)
+        .and_then(|p| p.file_name())
+        .and_then(|p| p.to_str())
+        .map(String::from)
+        .unwrap_or_else(|| env::var("PROFILE").unwrap());
+    println!("cargo:rustc-env=SMR_BENCH_PROFILE={profile}");
+
+    let commit = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=SMR_BENCH_COMMIT={commit}");
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+}
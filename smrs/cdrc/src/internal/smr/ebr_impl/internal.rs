@@ -44,6 +44,7 @@ use core::{fmt, ptr};
 
 use crossbeam_utils::CachePadded;
 use memoffset::offset_of;
+use smr_stats::{Counters, Stats};
 
 use super::atomic::{Owned, Shared};
 use super::collector::{Collector, LocalHandle};
@@ -55,6 +56,12 @@ use super::sync::queue::Queue;
 
 #[allow(missing_docs)]
 pub static GLOBAL_GARBAGE_COUNT: AtomicUsize = AtomicUsize::new(0);
+static COUNTERS: Counters = Counters::new();
+
+/// Returns the reclamation statistics of all collectors.
+pub fn stats() -> Stats {
+    COUNTERS.snapshot()
+}
 
 /// Maximum number of objects a bag can contain.
 #[cfg(not(any(crossbeam_sanitize, miri)))]
@@ -192,6 +199,7 @@ impl Global {
     /// Pushes the bag into the global queue and replaces the bag with a new empty bag.
     pub(crate) fn push_bag(&self, bag: &mut Bag, guard: &Guard) {
         GLOBAL_GARBAGE_COUNT.fetch_add(bag.0.len(), Ordering::AcqRel);
+        COUNTERS.retire(bag.0.len());
         let bag = mem::replace(bag, Bag::new());
 
         atomic::fence(Ordering::SeqCst);
@@ -226,6 +234,7 @@ impl Global {
         }
         collecting.set(true);
 
+        let mut reclaimed = 0;
         for _ in 0..Self::COLLECTS_MIN_TRIALS {
             match self.queue.try_pop_if(
                 &|sealed_bag: &SealedBag| sealed_bag.is_expired(global_epoch),
@@ -234,10 +243,12 @@ impl Global {
                 None => break,
                 Some(sealed_bag) => {
                     GLOBAL_GARBAGE_COUNT.fetch_sub(sealed_bag.bag.0.len(), Ordering::AcqRel);
+                    reclaimed += sealed_bag.bag.0.len();
                     drop(sealed_bag);
                 }
             }
         }
+        COUNTERS.scan(reclaimed);
         collecting.set(false);
     }
 
@@ -287,6 +298,7 @@ impl Global {
         // advanced two steps ahead of it.
         let new_epoch = global_epoch.successor();
         self.epoch.store(new_epoch, Ordering::Release);
+        COUNTERS.advance_epoch();
         new_epoch
     }
 
@@ -105,4 +105,4 @@ pub use self::atomic::{CompareAndSetError, CompareAndSetOrdering};
 mod default;
 pub use self::default::{default_collector, is_pinned, pin};
 
-pub use self::internal::GLOBAL_GARBAGE_COUNT;
+pub use self::internal::{stats, GLOBAL_GARBAGE_COUNT};
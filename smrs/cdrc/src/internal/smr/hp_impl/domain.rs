@@ -2,6 +2,7 @@ use core::sync::atomic::{AtomicUsize, Ordering};
 
 use crossbeam_utils::CachePadded;
 use rustc_hash::FxHashSet;
+use smr_stats::{Counters, Stats};
 
 use super::hazard::ThreadRecords;
 use super::retire::RetiredList;
@@ -12,6 +13,7 @@ pub struct Domain {
     pub(crate) threads: CachePadded<ThreadRecords>,
     pub(crate) retireds: CachePadded<RetiredList>,
     pub(crate) num_garbages: CachePadded<AtomicUsize>,
+    pub(crate) stats: CachePadded<Counters>,
 }
 
 impl Domain {
@@ -20,6 +22,7 @@ impl Domain {
             threads: CachePadded::new(ThreadRecords::new()),
             retireds: CachePadded::new(RetiredList::new()),
             num_garbages: CachePadded::new(AtomicUsize::new(0)),
+            stats: CachePadded::new(Counters::new()),
         }
     }
 
@@ -33,6 +36,10 @@ impl Domain {
     pub fn num_garbages(&self) -> usize {
         self.num_garbages.load(Ordering::Acquire)
     }
+
+    pub fn stats(&self) -> Stats {
+        self.stats.snapshot()
+    }
 }
 
 impl Drop for Domain {
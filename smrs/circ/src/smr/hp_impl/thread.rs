@@ -56,6 +56,7 @@ impl Thread {
         self.domain()
             .num_garbages
             .fetch_add(self.retired.borrow().len(), Ordering::AcqRel);
+        self.domain().stats.retire(self.retired.borrow().len());
         self.domain().retireds.push(self.retired.take())
     }
 
@@ -130,6 +131,7 @@ impl Thread {
         self.domain()
             .num_garbages
             .fetch_sub(retireds_len - not_freed.len(), Ordering::AcqRel);
+        self.domain().stats.scan(retireds_len - not_freed.len());
         self.domain().retireds.push(not_freed);
     }
 }
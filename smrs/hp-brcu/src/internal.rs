@@ -1,6 +1,7 @@
 use crossbeam_utils::CachePadded;
 use nix::errno::Errno;
 use nix::sys::pthread::{pthread_self, Pthread};
+use smr_stats::{Counters, Stats};
 use static_assertions::const_assert;
 use std::cell::{Cell, UnsafeCell};
 use std::marker::PhantomData;
@@ -17,6 +18,10 @@ use crate::rollback;
 
 pub(crate) static mut USE_ROLLBACK: bool = true;
 
+/// The reclamation statistics of the [`Global`], which is a singleton. It is a static so that the
+/// signal handler can count the restarts.
+pub(crate) static COUNTERS: Counters = Counters::new();
+
 /// Turn on or off the functionality to rollback. By default, HP-BRCU will use rollbacks.
 ///
 /// # Safety
@@ -65,9 +70,15 @@ impl Global {
         self.garbage_count.load(Ordering::Acquire)
     }
 
+    #[inline]
+    pub fn stats(&self) -> Stats {
+        COUNTERS.snapshot()
+    }
+
     #[inline]
     pub(crate) fn push_bag(&self, bag: &mut Bag) {
         self.garbage_count.fetch_add(bag.len(), Ordering::SeqCst);
+        COUNTERS.retire(bag.len());
         let bag = take(bag);
 
         fence(Ordering::SeqCst);
@@ -132,12 +143,18 @@ impl Global {
         //
         // Note that advancing here may fail if other thread already have advanced the epoch.
         let new_epoch = global_epoch.successor();
-        let _ = self.epoch.compare_exchange(
-            global_epoch,
-            new_epoch,
-            Ordering::Release,
-            Ordering::Relaxed,
-        );
+        if self
+            .epoch
+            .compare_exchange(
+                global_epoch,
+                new_epoch,
+                Ordering::Release,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+        {
+            COUNTERS.advance_epoch();
+        }
         Ok(new_epoch)
     }
 
@@ -171,8 +188,9 @@ impl Global {
                     advancer.unpin_inner();
                 }
                 match unsafe { rollback::send_signal(owner) } {
+                    Ok(_) => COUNTERS.signal(1),
                     // `ESRCH` indicates that the given pthread is already exited.
-                    Ok(_) | Err(Errno::ESRCH) => {}
+                    Err(Errno::ESRCH) => {}
                     Err(err) => panic!("Failed to restart the thread: {}", err),
                 }
             }
@@ -184,12 +202,18 @@ impl Global {
         //
         // Note that advancing here may fail if other thread already have advanced the epoch.
         let new_epoch = global_epoch.successor();
-        let _ = self.epoch.compare_exchange(
-            global_epoch,
-            new_epoch,
-            Ordering::Release,
-            Ordering::Relaxed,
-        );
+        if self
+            .epoch
+            .compare_exchange(
+                global_epoch,
+                new_epoch,
+                Ordering::Release,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+        {
+            COUNTERS.advance_epoch();
+        }
         new_epoch
     }
 
@@ -459,6 +483,7 @@ impl Local {
     #[inline]
     pub(crate) fn decr_garb_stat(&self, val: usize) {
         self.global().garbage_count.fetch_sub(val, Ordering::AcqRel);
+        COUNTERS.scan(val);
     }
 
     #[inline]
@@ -488,6 +513,7 @@ impl Local {
 
         // Must defer the destruction of the old array.
         self.global().garbage_count.fetch_add(1, Ordering::AcqRel);
+        COUNTERS.retire(1);
         self.hp_bag
             .push(Deferred::new(array_ptr as _, free::<Vec<AtomicPtr<u8>>>));
     }
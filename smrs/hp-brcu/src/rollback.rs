@@ -7,6 +7,8 @@ use setjmp::{sigjmp_buf, siglongjmp};
 use std::mem::MaybeUninit;
 use std::sync::atomic::{compiler_fence, AtomicU8, Ordering};
 
+use crate::internal::COUNTERS;
+
 /// Represents a thread-local status.
 ///
 /// A thread entering a critical section sets its `STATUS`.
@@ -104,6 +106,7 @@ impl Rollbacker {
     /// Returns to the checkpoint manually.
     #[inline]
     pub fn restart(&self) -> ! {
+        COUNTERS.restart();
         compiler_fence(Ordering::SeqCst);
         unsafe { siglongjmp(CHKPT.as_mut_ptr(), 1) }
     }
@@ -148,6 +151,8 @@ extern "C" fn handle_signal(_: i32, _: *mut siginfo_t, _: *mut c_void) {
 
     // if we have made a checkpoint and are not in crash-atomic section, it is good to `longjmp`.
     if current == Status::InCs {
+        // An atomic increment is async-signal-safe.
+        COUNTERS.restart();
         unsafe { siglongjmp(CHKPT.as_mut_ptr(), 1) }
     }
 
@@ -2,6 +2,7 @@ use core::sync::atomic::{AtomicUsize, Ordering};
 
 use crossbeam_utils::CachePadded;
 use rustc_hash::FxHashSet;
+use smr_stats::{Counters, Stats};
 
 use crate::hazard::ThreadRecords;
 use crate::retire::RetiredList;
@@ -13,6 +14,7 @@ pub struct Domain {
     pub(crate) barrier: CachePadded<EpochBarrier>,
     pub(crate) retireds: CachePadded<RetiredList>,
     pub(crate) num_garbages: CachePadded<AtomicUsize>,
+    pub(crate) stats: CachePadded<Counters>,
 }
 
 impl Domain {
@@ -22,6 +24,7 @@ impl Domain {
             barrier: CachePadded::new(EpochBarrier(AtomicUsize::new(0))),
             retireds: CachePadded::new(RetiredList::new()),
             num_garbages: CachePadded::new(AtomicUsize::new(0)),
+            stats: CachePadded::new(Counters::new()),
         }
     }
 
@@ -38,6 +41,14 @@ impl Domain {
     pub fn num_garbages(&self) -> usize {
         self.num_garbages.load(Ordering::Relaxed)
     }
+
+    /// The reclamation statistics. The epoch advances are the ones of the barrier of HP++.
+    pub fn stats(&self) -> Stats {
+        Stats {
+            epoch_advances: self.barrier.0.load(Ordering::Relaxed),
+            ..self.stats.snapshot()
+        }
+    }
 }
 
 impl Drop for Domain {
@@ -71,6 +71,7 @@ impl<'domain> Thread<'domain> {
         self.domain
             .num_garbages
             .fetch_add(self.retired.len(), Ordering::AcqRel);
+        self.domain.stats.retire(self.retired.len());
         self.domain.retireds.push(mem::replace(
             &mut self.retired,
             Vec::with_capacity(counts_between_flush()),
@@ -187,6 +188,7 @@ impl<'domain> Thread<'domain> {
         self.domain
             .num_garbages
             .fetch_sub(retireds_len - not_freed.len(), Ordering::AcqRel);
+        self.domain.stats.scan(retireds_len - not_freed.len());
         self.domain.retireds.push(not_freed);
     }
 }
@@ -218,6 +218,7 @@ impl Collector {
             }
             let pthread = self.registered_map[other_tid].load(Ordering::Acquire);
             recovery::send_signal(pthread)?;
+            stats::COUNTERS.signal(1);
         }
         Ok(())
     }
@@ -254,6 +255,9 @@ impl Collector {
         );
         self.registered_map[tid].store(pthread_self(), Ordering::Release);
         let guard = Guard::register(self, tid);
+        // The slot may be registered again after `reset_registrations`, and the new handle
+        // acquires its shields from the start.
+        guard.thread_mut().using_hazptrs = 0;
 
         // Wait until all threads are ready.
         self.barrier.wait();
@@ -5,7 +5,7 @@ pub mod recovery;
 mod stats;
 
 pub use collector::{unprotected, Collector, Guard, Shield, ThreadId};
-pub use stats::count_garbages;
+pub use stats::{count_garbages, stats};
 
 pub use nix::sys::signal;
 pub use setjmp;
@@ -6,6 +6,8 @@ use setjmp::{jmp_buf, sigjmp_buf, siglongjmp};
 use std::mem::{transmute, MaybeUninit};
 use std::sync::atomic::{compiler_fence, AtomicBool, Ordering};
 
+use crate::stats;
+
 static mut NEUTRALIZE_SIGNAL: Signal = Signal::SIGUSR1;
 static mut SIG_ACTION: MaybeUninit<SigAction> = MaybeUninit::uninit();
 
@@ -115,6 +117,8 @@ extern "C" fn handle_signal(_: i32, _: *mut siginfo_t, _: *mut c_void) {
         Err(_) => return,
     };
     rest.store(false, Ordering::Relaxed);
+    // An atomic increment is async-signal-safe.
+    stats::COUNTERS.restart();
     compiler_fence(Ordering::SeqCst);
 
     unsafe { siglongjmp(buf, 1) };
@@ -1,16 +1,26 @@
 use std::sync::atomic::{AtomicUsize, Ordering};
 
+use smr_stats::{Counters, Stats};
+
 pub(crate) static GLOBAL_GARBAGE_COUNT: AtomicUsize = AtomicUsize::new(0);
+pub(crate) static COUNTERS: Counters = Counters::new();
 
 pub(crate) fn incr_garb(count: usize) {
     GLOBAL_GARBAGE_COUNT.fetch_add(count, Ordering::Relaxed);
+    COUNTERS.retire(count);
 }
 
 pub(crate) fn decr_garb(count: usize) {
     GLOBAL_GARBAGE_COUNT.fetch_sub(count, Ordering::Relaxed);
+    COUNTERS.scan(count);
 }
 
 /// Get current count of unreclaimed pointers.
 pub fn count_garbages() -> usize {
     GLOBAL_GARBAGE_COUNT.load(Ordering::Relaxed)
 }
+
+/// Get the reclamation statistics of all collectors.
+pub fn stats() -> Stats {
+    COUNTERS.snapshot()
+}
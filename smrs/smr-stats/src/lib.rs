@@ -0,0 +1,158 @@
+//! The reclamation statistics shared by the schemes.
+//!
+//! Each scheme updates a [`Counters`] at its reclamation events and exposes a [`Stats`] snapshot
+//! of it, so that the benchmark reports the same statistics for every scheme. The counters that
+//! do not apply to a scheme (e.g., the signals of the schemes without neutralization) stay 0.
+
+use std::ops::Sub;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A snapshot of the cumulative reclamation events of a scheme.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// The retired nodes, counted when a scheme starts to track them for reclamation.
+    pub retired: usize,
+    /// The retired nodes that were reclaimed (or returned to a pool, for the schemes reusing
+    /// them).
+    pub reclaimed: usize,
+    /// The attempts to reclaim a batch of retired nodes.
+    pub scans: usize,
+    /// The advances of the global epoch.
+    pub epoch_advances: usize,
+    /// The signals sent to neutralize or roll back other threads.
+    pub signals: usize,
+    /// The operations restarted by a signal.
+    pub restarts: usize,
+}
+
+impl Stats {
+    /// The retired but not yet reclaimed nodes.
+    pub fn garbages(&self) -> usize {
+        self.retired.saturating_sub(self.reclaimed)
+    }
+
+    /// The average number of nodes reclaimed by a scan, or 0 if there was no scan.
+    pub fn avg_batch(&self) -> f64 {
+        if self.scans == 0 {
+            0.0
+        } else {
+            self.reclaimed as f64 / self.scans as f64
+        }
+    }
+}
+
+/// The events between two snapshots.
+impl Sub for Stats {
+    type Output = Stats;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            retired: self.retired - rhs.retired,
+            reclaimed: self.reclaimed - rhs.reclaimed,
+            scans: self.scans - rhs.scans,
+            epoch_advances: self.epoch_advances - rhs.epoch_advances,
+            signals: self.signals - rhs.signals,
+            restarts: self.restarts - rhs.restarts,
+        }
+    }
+}
+
+/// A snapshot of the pool of a scheme that reuses the reclaimed nodes (e.g., VBR).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Pool {
+    /// The available nodes, including those held by the threads.
+    pub pooled: usize,
+    /// The bags of nodes allocated so far.
+    pub bags: usize,
+}
+
+/// The counters of the reclamation events, updated with relaxed atomics.
+#[derive(Debug, Default)]
+pub struct Counters {
+    retired: AtomicUsize,
+    reclaimed: AtomicUsize,
+    scans: AtomicUsize,
+    epoch_advances: AtomicUsize,
+    signals: AtomicUsize,
+    restarts: AtomicUsize,
+}
+
+impl Counters {
+    pub const fn new() -> Self {
+        Self {
+            retired: AtomicUsize::new(0),
+            reclaimed: AtomicUsize::new(0),
+            scans: AtomicUsize::new(0),
+            epoch_advances: AtomicUsize::new(0),
+            signals: AtomicUsize::new(0),
+            restarts: AtomicUsize::new(0),
+        }
+    }
+
+    #[inline]
+    pub fn retire(&self, count: usize) {
+        self.retired.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Records a scan which reclaimed `count` nodes.
+    #[inline]
+    pub fn scan(&self, count: usize) {
+        self.scans.fetch_add(1, Ordering::Relaxed);
+        self.reclaimed.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Records `count` nodes reclaimed outside of a scan (e.g., when a thread exits).
+    #[inline]
+    pub fn reclaim(&self, count: usize) {
+        self.reclaimed.fetch_add(count, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn advance_epoch(&self) {
+        self.epoch_advances.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn signal(&self, count: usize) {
+        self.signals.fetch_add(count, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn restart(&self) {
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> Stats {
+        Stats {
+            retired: self.retired.load(Ordering::Relaxed),
+            reclaimed: self.reclaimed.load(Ordering::Relaxed),
+            scans: self.scans.load(Ordering::Relaxed),
+            epoch_advances: self.epoch_advances.load(Ordering::Relaxed),
+            signals: self.signals.load(Ordering::Relaxed),
+            restarts: self.restarts.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Counters;
+
+    #[test]
+    fn snapshot() {
+        let counters = Counters::new();
+        counters.retire(10);
+        let before = counters.snapshot();
+        counters.retire(6);
+        counters.scan(12);
+        counters.scan(0);
+        counters.advance_epoch();
+        let stats = counters.snapshot();
+        assert_eq!(stats.garbages(), 4);
+        let delta = stats - before;
+        assert_eq!(delta.retired, 6);
+        assert_eq!(delta.scans, 2);
+        assert_eq!(delta.avg_batch(), 6.0);
+        assert_eq!(delta.epoch_advances, 1);
+    }
+}
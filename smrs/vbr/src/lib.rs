@@ -5,13 +5,14 @@ use std::{
     marker::PhantomData,
     mem::{align_of, zeroed},
     ptr::null_mut,
-    sync::atomic::AtomicU64,
+    sync::atomic::{AtomicU64, AtomicUsize},
 };
 
 use arrayvec::ArrayVec;
 use atomic::{Atomic, Ordering};
 use crossbeam_utils::CachePadded;
 use portable_atomic::{compiler_fence, AtomicU128};
+use smr_stats::{Counters, Pool, Stats};
 
 pub const ENTRIES_PER_BAG: usize = 128;
 pub const INIT_BAGS_PER_LOCAL: usize = 32;
@@ -26,6 +27,13 @@ pub struct Inner<T> {
 pub struct Global<T> {
     epoch: CachePadded<AtomicU64>,
     avail: BagStack<Inner<T>>,
+    /// The retired entries are reclaimed when their bags are returned to `avail`, from which they
+    /// are reused.
+    stats: CachePadded<Counters>,
+    /// The number of available entries, in the bags of `avail` and in those held by the locals.
+    pooled: CachePadded<AtomicUsize>,
+    /// The number of bags allocated so far.
+    bags: CachePadded<AtomicUsize>,
 }
 
 unsafe impl<T> Sync for Global<T> {}
@@ -33,17 +41,44 @@ unsafe impl<T> Send for Global<T> {}
 
 impl<T> Global<T> {
     pub fn new(capacity: usize) -> Self {
-        let avail = BagStack::new();
+        let global = Self {
+            epoch: CachePadded::new(AtomicU64::new(1)),
+            avail: BagStack::new(),
+            stats: CachePadded::new(Counters::new()),
+            pooled: CachePadded::new(AtomicUsize::new(0)),
+            bags: CachePadded::new(AtomicUsize::new(0)),
+        };
         let count = capacity / ENTRIES_PER_BAG + if capacity % ENTRIES_PER_BAG > 0 { 1 } else { 0 };
         for _ in 0..count {
-            avail.push(Box::into_raw(Box::new(Bag::new_with_alloc())));
+            global.push_avail(global.alloc_bag(Bag::new_with_alloc()));
         }
-        Self {
-            epoch: CachePadded::new(AtomicU64::new(1)),
-            avail,
+        global
+    }
+
+    /// The reclamation statistics, where the garbages are the retired entries that are not yet
+    /// returned to the global pool.
+    pub fn stats(&self) -> Stats {
+        self.stats.snapshot()
+    }
+
+    pub fn pool(&self) -> Pool {
+        Pool {
+            pooled: self.pooled.load(Ordering::Relaxed),
+            bags: self.bags.load(Ordering::Relaxed),
         }
     }
 
+    fn alloc_bag(&self, bag: Bag<Inner<T>>) -> *mut Bag<Inner<T>> {
+        self.bags.fetch_add(1, Ordering::Relaxed);
+        Box::into_raw(Box::new(bag))
+    }
+
+    fn push_avail(&self, bag: *mut Bag<Inner<T>>) {
+        self.pooled
+            .fetch_add(unsafe { &*bag }.entries.len(), Ordering::Relaxed);
+        self.avail.push(bag);
+    }
+
     pub fn epoch(&self) -> u64 {
         self.epoch.load(Ordering::SeqCst)
     }
@@ -55,24 +90,28 @@ impl<T> Global<T> {
             Ordering::SeqCst,
             Ordering::SeqCst,
         ) {
-            Ok(_) => Ok(expected + 1),
+            Ok(_) => {
+                self.stats.advance_epoch();
+                Ok(expected + 1)
+            }
             Err(_) => Err(expected),
         }
     }
 
-    pub fn acquire(&self) -> *mut Bag<Inner<T>> {
+    /// Acquires an available bag. Its entries stay counted as pooled until they are popped.
+    fn acquire(&self) -> *mut Bag<Inner<T>> {
         loop {
             if let Some(bag) = self.avail.pop() {
                 return bag;
             } else {
-                self.avail
-                    .push(Box::into_raw(Box::new(Bag::new_with_alloc())));
+                self.push_avail(self.alloc_bag(Bag::new_with_alloc()));
             }
         }
     }
 
-    pub fn retire(&self, bag: *mut Bag<Inner<T>>) {
-        self.avail.push(bag);
+    fn retire(&self, bag: *mut Bag<Inner<T>>) {
+        self.stats.scan(unsafe { &*bag }.entries.len());
+        self.push_avail(bag);
     }
 }
 
@@ -199,7 +238,7 @@ impl<T> Local<T> {
         let mut avail = VecDeque::with_capacity(INIT_BAGS_PER_LOCAL);
         avail.resize_with(INIT_BAGS_PER_LOCAL, || global.acquire());
         let mut retired = VecDeque::new();
-        retired.push_back(Box::into_raw(Box::new(Bag::new())));
+        retired.push_back(global.alloc_bag(Bag::new()));
         Self {
             global,
             avail: RefCell::new(avail),
@@ -217,6 +256,7 @@ impl<T> Local<T> {
                 };
                 let bag_ref = unsafe { &mut *bag };
                 if let Some(item) = bag_ref.pop() {
+                    self.global().pooled.fetch_sub(1, Ordering::Relaxed);
                     return item;
                 } else {
                     self.avail.borrow_mut().pop_front();
@@ -235,9 +275,11 @@ impl<T> Local<T> {
         let bag = *self.avail.borrow().front().unwrap();
         let bag_ref = unsafe { &mut *bag };
         bag_ref.push(inner);
+        self.global().pooled.fetch_add(1, Ordering::Relaxed);
     }
 
     fn push_retired(&self, inner: *mut Inner<T>) {
+        self.global().stats.retire(1);
         // Try find an available slot from a thread-local bag.
         loop {
             let bag = match self.retired.borrow().front() {
@@ -254,9 +296,11 @@ impl<T> Local<T> {
         }
 
         // Create a fresh bag to store a node.
-        let mut bag = Box::new(Bag::new());
+        let mut bag = Bag::new();
         bag.push(inner);
-        self.retired.borrow_mut().push_back(Box::into_raw(bag));
+        self.retired
+            .borrow_mut()
+            .push_back(self.global().alloc_bag(bag));
     }
 
     pub fn guard(&self) -> Guard<T> {
@@ -267,6 +311,21 @@ impl<T> Local<T> {
     }
 }
 
+impl<T> Drop for Local<T> {
+    /// Returns the bags of this local to the global pool, so that they are not leaked.
+    fn drop(&mut self) {
+        let global = unsafe { &*self.global };
+        // The entries of the available bags are already counted as pooled.
+        for bag in self.avail.get_mut().drain(..) {
+            global.avail.push(bag);
+        }
+        for bag in self.retired.get_mut().drain(..) {
+            global.stats.reclaim(unsafe { &*bag }.entries.len());
+            global.push_avail(bag);
+        }
+    }
+}
+
 pub struct Guard<T> {
     local: *const Local<T>,
     epoch: u64,
@@ -0,0 +1,157 @@
+//! Placement policies that pin the benchmark threads to CPUs (`--affinity`).
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+
+use core_affinity::CoreId;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Affinity {
+    /// Leaves the placement to the OS scheduler.
+    None,
+    /// Fills the hardware threads of a core, then the cores of a socket, then the next socket.
+    Compact,
+    /// Spreads the threads across the sockets first, then across the cores of each socket, and
+    /// uses the sibling hardware threads only after every core has a thread.
+    Scatter,
+    /// Pins the threads to the given CPUs in order.
+    List(Vec<usize>),
+}
+
+impl Affinity {
+    /// Parses `none`, `compact`, `scatter`, or a CPU list such as `0,2,4-7`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec {
+            "none" => return Ok(Affinity::None),
+            "compact" => return Ok(Affinity::Compact),
+            "scatter" => return Ok(Affinity::Scatter),
+            _ => {}
+        }
+        let invalid =
+            || format!("invalid affinity `{spec}`: expected none, compact, scatter or a CPU list");
+        let mut cpus = Vec::new();
+        for range in spec.split(',') {
+            let (lo, hi) = range.split_once('-').unwrap_or((range, range));
+            let lo = lo.trim().parse::<usize>().map_err(|_| invalid())?;
+            let hi = hi.trim().parse::<usize>().map_err(|_| invalid())?;
+            if lo > hi {
+                return Err(invalid());
+            }
+            cpus.extend(lo..=hi);
+        }
+        Ok(Affinity::List(cpus))
+    }
+
+    /// The CPUs in the order in which the threads are pinned, or `None` if not pinned. The `i`-th
+    /// thread is pinned to the `i % len`-th CPU, so the threads more than the CPUs share them.
+    pub fn cpus(&self) -> Option<Vec<usize>> {
+        match self {
+            Affinity::None => None,
+            Affinity::List(cpus) => Some(cpus.clone()),
+            Affinity::Compact | Affinity::Scatter => {
+                let mut cpus = topology();
+                if *self == Affinity::Compact {
+                    cpus.sort_by_key(|cpu| (cpu.package, cpu.core, cpu.id));
+                } else {
+                    cpus.sort_by_key(|cpu| (cpu.smt_rank, cpu.core_rank, cpu.package, cpu.id));
+                }
+                Some(cpus.into_iter().map(|cpu| cpu.id).collect())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Affinity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Affinity::None => write!(f, "none"),
+            Affinity::Compact => write!(f, "compact"),
+            Affinity::Scatter => write!(f, "scatter"),
+            Affinity::List(cpus) => {
+                let cpus = cpus.iter().map(|cpu| cpu.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", cpus.join(","))
+            }
+        }
+    }
+}
+
+/// Pins the current thread to `cpu`.
+pub fn pin(cpu: usize) {
+    assert!(
+        core_affinity::set_for_current(CoreId { id: cpu }),
+        "failed to pin a thread to CPU {cpu}"
+    );
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Cpu {
+    id: usize,
+    package: usize,
+    core: usize,
+    /// The index of this CPU among the hardware threads of its core.
+    smt_rank: usize,
+    /// The index of its core among the cores of its package.
+    core_rank: usize,
+}
+
+/// The CPUs available to this process with their sockets and cores, read from
+/// `/sys/devices/system/cpu`. If it is not available, each CPU is regarded as a core of a single
+/// socket.
+fn topology() -> Vec<Cpu> {
+    let ids = core_affinity::get_core_ids()
+        .expect("failed to get the available CPUs")
+        .into_iter()
+        .map(|core| core.id);
+    let read = |cpu: usize, name: &str| {
+        fs::read_to_string(format!("/sys/devices/system/cpu/cpu{cpu}/topology/{name}"))
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+    };
+    let mut cpus = ids
+        .map(|id| Cpu {
+            id,
+            package: read(id, "physical_package_id").unwrap_or(0),
+            core: read(id, "core_id").unwrap_or(id),
+            smt_rank: 0,
+            core_rank: 0,
+        })
+        .collect::<Vec<_>>();
+
+    let mut siblings = BTreeMap::<(usize, usize), Vec<usize>>::new();
+    for cpu in &cpus {
+        siblings
+            .entry((cpu.package, cpu.core))
+            .or_default()
+            .push(cpu.id);
+    }
+    let mut cores = BTreeMap::<usize, Vec<usize>>::new();
+    for &(package, core) in siblings.keys() {
+        cores.entry(package).or_default().push(core);
+    }
+    for cpu in &mut cpus {
+        let threads = &siblings[&(cpu.package, cpu.core)];
+        cpu.smt_rank = threads.iter().position(|&id| id == cpu.id).unwrap();
+        let package_cores = &cores[&cpu.package];
+        cpu.core_rank = package_cores.iter().position(|&c| c == cpu.core).unwrap();
+    }
+    cpus
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Affinity;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Affinity::parse("scatter"), Ok(Affinity::Scatter));
+        assert_eq!(
+            Affinity::parse("0,2,4-6"),
+            Ok(Affinity::List(vec![0, 2, 4, 5, 6]))
+        );
+        assert_eq!(Affinity::parse("0,2,4-6").unwrap().to_string(), "0,2,4,5,6");
+        for spec in ["", "1,", "3-1", "a-b"] {
+            assert!(Affinity::parse(spec).is_err(), "{spec}");
+        }
+    }
+}
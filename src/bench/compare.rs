@@ -0,0 +1,367 @@
+//! Comparison of two result files of `smr-bench` (see the `smr-compare` binary).
+//!
+//! The rows of both files are grouped by their configuration, i.e., all columns except the
+//! measurements, the trial and the seed, so the trials of a run and the repeated runs of the same
+//! configuration form a group of samples. The groups of the two files are then joined on the
+//! configuration columns they have in common.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+use super::stats::Summary;
+
+/// The columns that are not part of the configuration, in addition to the latency columns.
+const NON_CONFIG_COLUMNS: [&str; 18] = [
+    "throughput",
+    "peak_mem",
+    "avg_mem",
+    "peak_garb",
+    "avg_garb",
+    "replaced",
+    "drained_garb",
+    "retired",
+    "reclaimed",
+    "scans",
+    "avg_batch",
+    "epoch_advances",
+    "signals",
+    "restarts",
+    "pooled",
+    "bags",
+    "trial",
+    "seed",
+];
+
+/// The fields of the `config` object of a JSON record that are not part of the configuration.
+const NON_CONFIG_FIELDS: [&str; 7] = [
+    "trials",
+    "seed",
+    "latency",
+    "timeseries",
+    "record",
+    "record_times",
+    "validate",
+];
+
+/// The measurements of a trial.
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+    throughput: f64,
+    peak_mem: f64,
+    avg_mem: f64,
+}
+
+/// The trials of a result file, with their configurations.
+pub struct Results {
+    columns: BTreeSet<String>,
+    rows: Vec<(BTreeMap<String, String>, Sample)>,
+}
+
+fn is_latency_column(column: &str) -> bool {
+    ["get_", "insert_", "remove_"]
+        .iter()
+        .any(|op| column.starts_with(op))
+}
+
+impl Results {
+    /// Parses a result file, which is in JSON lines if it starts with `{`, and in CSV otherwise.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        if input.trim_start().starts_with('{') {
+            Self::parse_jsonl(input)
+        } else {
+            Self::parse_csv(input)
+        }
+    }
+
+    fn parse_csv(input: &str) -> Result<Self, String> {
+        let mut reader = csv::Reader::from_reader(input.as_bytes());
+        let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+        let column = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h == name)
+                .ok_or_else(|| format!("no `{name}` column"))
+        };
+        let (throughput, peak_mem, avg_mem) = (
+            column("throughput")?,
+            column("peak_mem")?,
+            column("avg_mem")?,
+        );
+        let config = headers
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| !NON_CONFIG_COLUMNS.contains(h) && !is_latency_column(h))
+            .collect::<Vec<_>>();
+
+        let mut rows = Vec::new();
+        for (line, record) in reader.records().enumerate() {
+            let record = record.map_err(|e| e.to_string())?;
+            let value = |i: usize| {
+                record[i]
+                    .parse::<f64>()
+                    .map_err(|e| format!("row {}: {e}", line + 1))
+            };
+            let sample = Sample {
+                throughput: value(throughput)?,
+                peak_mem: value(peak_mem)?,
+                avg_mem: value(avg_mem)?,
+            };
+            let key = config
+                .iter()
+                .map(|&(i, h)| (h.to_string(), record[i].to_string()))
+                .collect();
+            rows.push((key, sample));
+        }
+        Ok(Self {
+            columns: config.iter().map(|(_, h)| h.to_string()).collect(),
+            rows,
+        })
+    }
+
+    fn parse_jsonl(input: &str) -> Result<Self, String> {
+        let mut columns = BTreeSet::new();
+        let mut rows = Vec::new();
+        for (line, record) in input.lines().enumerate() {
+            if record.trim().is_empty() {
+                continue;
+            }
+            let err = |e: &dyn ToString| format!("line {}: {}", line + 1, e.to_string());
+            let record = serde_json::from_str::<Value>(record).map_err(|e| err(&e))?;
+            let Some(Value::Object(config)) = record.get("config") else {
+                return Err(err(&"no `config` object"));
+            };
+            // The nested objects (e.g., `op_mix`) are flattened with the names of their fields, as
+            // in the CSV format.
+            let mut key = BTreeMap::new();
+            for (name, value) in config {
+                match value {
+                    Value::Object(fields) => {
+                        for (name, value) in fields {
+                            key.insert(name.clone(), json_string(value));
+                        }
+                    }
+                    _ if NON_CONFIG_FIELDS.contains(&name.as_str()) => {}
+                    _ => {
+                        key.insert(name.clone(), json_string(value));
+                    }
+                }
+            }
+            columns.extend(key.keys().cloned());
+            let Some(Value::Array(perfs)) = record.get("perf") else {
+                return Err(err(&"no `perf` array"));
+            };
+            for perf in perfs {
+                let value = |name: &str| {
+                    perf.get(name)
+                        .and_then(Value::as_f64)
+                        .ok_or_else(|| err(&format!("no `{name}` in `perf`")))
+                };
+                let sample = Sample {
+                    throughput: value("throughput")?,
+                    peak_mem: value("peak_mem")?,
+                    avg_mem: value("avg_mem")?,
+                };
+                rows.push((key.clone(), sample));
+            }
+        }
+        Ok(Self { columns, rows })
+    }
+
+    /// Groups the samples by the values of `columns`.
+    fn group(&self, columns: &[String]) -> BTreeMap<Vec<String>, Vec<Sample>> {
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+        for (key, sample) in &self.rows {
+            let key = columns
+                .iter()
+                .map(|c| key.get(c).cloned().unwrap_or_default())
+                .collect();
+            groups.entry(key).or_default().push(*sample);
+        }
+        groups
+    }
+}
+
+fn json_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        v => v.to_string(),
+    }
+}
+
+/// A measurement of the same configuration in the baseline and in the new results.
+#[derive(Clone, Copy, Debug)]
+pub struct Delta {
+    pub base: Summary,
+    pub new: Summary,
+}
+
+impl Delta {
+    fn of(base: &[Sample], new: &[Sample], f: impl Fn(&Sample) -> f64) -> Self {
+        let summary = |samples: &[Sample]| Summary::of(&samples.iter().map(&f).collect::<Vec<_>>());
+        Self {
+            base: summary(base),
+            new: summary(new),
+        }
+    }
+
+    /// The relative change of the mean, or `None` if the baseline is 0 (e.g., memory that was not
+    /// sampled).
+    pub fn change(&self) -> Option<f64> {
+        (self.base.mean != 0.0).then(|| (self.new.mean - self.base.mean) / self.base.mean)
+    }
+
+    /// Whether the 95% confidence intervals of the means do not overlap. If both sides have a
+    /// single sample, there is no estimate of the noise and any change is significant.
+    pub fn significant(&self) -> bool {
+        (self.new.mean - self.base.mean).abs() > self.base.ci95 + self.new.ci95
+    }
+}
+
+/// The maximum relative changes that are not regressions.
+#[derive(Clone, Copy, Debug)]
+pub struct Thresholds {
+    /// The decrease of the throughput.
+    pub throughput: f64,
+    /// The increase of the peak and the average memory usage.
+    pub memory: f64,
+}
+
+/// The comparison of a configuration in both results.
+pub struct Comparison {
+    /// The values of the joined columns.
+    pub key: Vec<String>,
+    pub throughput: Delta,
+    pub peak_mem: Delta,
+    pub avg_mem: Delta,
+}
+
+impl Comparison {
+    /// The names of the measurements that regressed beyond the thresholds, only counting the
+    /// significant changes.
+    pub fn regressions(&self, thresholds: &Thresholds) -> Vec<&'static str> {
+        let exceeds = |delta: &Delta, threshold: f64, sign: f64| {
+            delta.significant() && delta.change().is_some_and(|c| c * sign > threshold)
+        };
+        let mut regressions = Vec::new();
+        if exceeds(&self.throughput, thresholds.throughput, -1.0) {
+            regressions.push("throughput");
+        }
+        if exceeds(&self.peak_mem, thresholds.memory, 1.0) {
+            regressions.push("peak_mem");
+        }
+        if exceeds(&self.avg_mem, thresholds.memory, 1.0) {
+            regressions.push("avg_mem");
+        }
+        regressions
+    }
+}
+
+/// The joined results.
+pub struct Report {
+    /// The columns joined on.
+    pub columns: Vec<String>,
+    /// The configuration columns present in only one of the results, which are ignored.
+    pub ignored: Vec<String>,
+    pub comparisons: Vec<Comparison>,
+    /// The numbers of the configurations only in the baseline and only in the new results.
+    pub unmatched: (usize, usize),
+}
+
+/// Joins the new results with the baseline on their common configuration columns.
+pub fn compare(base: &Results, new: &Results) -> Report {
+    let columns = base
+        .columns
+        .intersection(&new.columns)
+        .cloned()
+        .collect::<Vec<_>>();
+    let ignored = base
+        .columns
+        .symmetric_difference(&new.columns)
+        .cloned()
+        .collect();
+    let base = base.group(&columns);
+    let mut new = new.group(&columns);
+    let mut comparisons = Vec::new();
+    let mut only_base = 0;
+    for (key, base) in base {
+        let Some(new) = new.remove(&key) else {
+            only_base += 1;
+            continue;
+        };
+        comparisons.push(Comparison {
+            throughput: Delta::of(&base, &new, |s| s.throughput),
+            peak_mem: Delta::of(&base, &new, |s| s.peak_mem),
+            avg_mem: Delta::of(&base, &new, |s| s.avg_mem),
+            key,
+        });
+    }
+    Report {
+        columns,
+        ignored,
+        comparisons,
+        unmatched: (only_base, new.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, Results, Thresholds};
+
+    const HEADER: &str = "ds,mm,threads,get,throughput,peak_mem,avg_mem,get_p50,trial,seed\n";
+
+    #[test]
+    fn regressions() {
+        let base = Results::parse(&format!(
+            "{HEADER}\
+             h-list,ebr,1,0,1000,10,5,,0,1\n\
+             h-list,ebr,1,0,1010,10,5,,1,1\n\
+             h-list,ebr,1,0,990,10,5,,0,2\n\
+             h-list,hp,1,0,1000,10,5,,0,1\n\
+             h-list,nr,1,0,1000,10,5,,0,1\n"
+        ))
+        .unwrap();
+        let new = Results::parse(
+            r#"{"config":{"ds":"h-list","mm":"ebr","threads":1,"op_mix":{"get":0},"seed":3},"perf":[{"throughput":900,"peak_mem":10,"avg_mem":5},{"throughput":905,"peak_mem":10,"avg_mem":5}]}
+{"config":{"ds":"h-list","mm":"hp","threads":1,"op_mix":{"get":0},"seed":3},"perf":[{"throughput":980,"peak_mem":20,"avg_mem":5}]}
+{"config":{"ds":"h-list","mm":"pebr","threads":1,"op_mix":{"get":0},"seed":3},"perf":[{"throughput":980,"peak_mem":20,"avg_mem":5}]}
+"#,
+        )
+        .unwrap();
+        let report = compare(&base, &new);
+        assert_eq!(report.columns, ["ds", "get", "mm", "threads"]);
+        assert!(report.ignored.is_empty());
+        assert_eq!(report.unmatched, (1, 1));
+        let thresholds = Thresholds {
+            throughput: 0.05,
+            memory: 0.1,
+        };
+        let [ebr, hp] = &report.comparisons[..] else {
+            panic!("{} comparisons", report.comparisons.len());
+        };
+        assert_eq!(ebr.key, ["h-list", "0", "ebr", "1"]);
+        assert_eq!(ebr.throughput.base.mean, 1000.0);
+        assert_eq!(ebr.regressions(&thresholds), ["throughput"]);
+        assert_eq!(hp.regressions(&thresholds), ["peak_mem"]);
+    }
+
+    #[test]
+    fn noise() {
+        // The drop of the mean is within the noise of the trials.
+        let base = Results::parse(&format!(
+            "{HEADER}h-list,ebr,1,0,1000,0,0,,0,1\nh-list,ebr,1,0,600,0,0,,1,1\n"
+        ))
+        .unwrap();
+        let new = Results::parse(&format!(
+            "{HEADER}h-list,ebr,1,0,700,0,0,,0,1\nh-list,ebr,1,0,740,0,0,,1,1\n"
+        ))
+        .unwrap();
+        let report = compare(&base, &new);
+        let thresholds = Thresholds {
+            throughput: 0.05,
+            memory: 0.1,
+        };
+        assert!(report.comparisons[0].regressions(&thresholds).is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+//! A log-bucketed histogram for recording operation latencies.
+
+use std::cmp::{max, min};
+
+/// The number of bits of a value kept by its bucket, below the most significant bit.
+/// The relative error of a recorded value is at most `1 / 2^SUB_BITS`.
+const SUB_BITS: u32 = 4;
+const SUB_BUCKETS: usize = 1 << SUB_BITS;
+const BUCKETS: usize = (64 - SUB_BITS as usize + 1) * SUB_BUCKETS;
+
+/// A histogram of `u64` values (e.g., latencies in nanoseconds).
+///
+/// Values smaller than `2^SUB_BITS` are recorded exactly, and the larger ones are grouped into
+/// `2^SUB_BITS` buckets for each power of two. Histograms recorded by different threads can be
+/// merged into one.
+#[derive(Clone)]
+pub struct Histogram {
+    buckets: Box<[u64; BUCKETS]>,
+    count: u64,
+    max: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            buckets: Box::new([0; BUCKETS]),
+            count: 0,
+            max: 0,
+        }
+    }
+
+    fn index(value: u64) -> usize {
+        if value < SUB_BUCKETS as u64 {
+            return value as usize;
+        }
+        let exp = 63 - value.leading_zeros();
+        let sub = (value >> (exp - SUB_BITS)) as usize & (SUB_BUCKETS - 1);
+        (exp - SUB_BITS + 1) as usize * SUB_BUCKETS + sub
+    }
+
+    /// The largest value that falls into the bucket at `index`.
+    fn upper_bound(index: usize) -> u64 {
+        if index < SUB_BUCKETS {
+            return index as u64;
+        }
+        let shift = (index / SUB_BUCKETS) as u32 - 1;
+        let lower = ((SUB_BUCKETS + index % SUB_BUCKETS) as u64) << shift;
+        lower + ((1 << shift) - 1)
+    }
+
+    #[inline]
+    pub fn record(&mut self, value: u64) {
+        self.buckets[Self::index(value)] += 1;
+        self.count += 1;
+        self.max = max(self.max, value);
+    }
+
+    pub fn merge(&mut self, other: &Histogram) {
+        for (b, o) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            *b += o;
+        }
+        self.count += other.count;
+        self.max = max(self.max, other.max);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Returns the value at the given quantile in `[0, 1]`, or 0 if nothing is recorded.
+    pub fn quantile(&self, q: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let rank = max(1, (q * self.count as f64).ceil() as u64);
+        let mut seen = 0;
+        for (i, &n) in self.buckets.iter().enumerate() {
+            seen += n;
+            if seen >= rank {
+                return min(Self::upper_bound(i), self.max);
+            }
+        }
+        self.max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Histogram;
+
+    #[test]
+    fn quantiles() {
+        let mut hist = Histogram::new();
+        for v in 1..=10_000 {
+            hist.record(v);
+        }
+        let mut other = Histogram::new();
+        other.record(1_000_000);
+        hist.merge(&other);
+
+        assert_eq!(hist.count(), 10_001);
+        assert_eq!(hist.max(), 1_000_000);
+        assert_eq!(hist.quantile(1.0), 1_000_000);
+        for (q, expected) in [(0.5, 5_000), (0.9, 9_000), (0.99, 9_900)] {
+            let v = hist.quantile(q);
+            assert!(v >= expected && v <= expected + expected / 16, "q{q}: {v}");
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Linearizability checking of the histories of concurrent map operations.
+//!
+//! A history is checked against the sequential specification of a set, where `get` returns
+//! whether the key is present, and `insert` and `remove` return whether they changed the set.
+//! Since the operations on different keys are independent and linearizability is compositional,
+//! the history of each key is checked separately, by the search of Wing and Gong with the
+//! memoization of Lowe.
+
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+use crate::config::map::Op;
+
+/// A completed operation in a history, with the times of its invocation and response relative to
+/// a common start.
+#[derive(Clone, Copy, Debug)]
+pub struct Event {
+    pub thread: usize,
+    pub op: Op,
+    pub key: usize,
+    pub result: bool,
+    pub invoke: Duration,
+    pub response: Duration,
+}
+
+/// Applies `op` to a key in the state `present`, and returns the new state if `result` is what the
+/// sequential specification returns.
+fn apply(present: bool, op: Op, result: bool) -> Option<bool> {
+    match op {
+        Op::Get => (result == present).then_some(present),
+        Op::Insert => (result != present).then_some(true),
+        Op::Remove => (result == present).then_some(false),
+    }
+}
+
+/// Checks that the history, which starts from the empty map, is linearizable.
+pub fn check(history: &[Event]) -> Result<(), String> {
+    let mut keys = BTreeMap::<usize, Vec<Event>>::new();
+    for event in history {
+        keys.entry(event.key).or_default().push(*event);
+    }
+    for (key, mut events) in keys {
+        events.sort_by_key(|e| e.invoke);
+        if !Search::new(&events).run() {
+            let mut msg = format!("the history of key {key} is not linearizable:");
+            for e in &events {
+                msg += &format!(
+                    "\n  thread {} {} -> {} [{:?}, {:?}]",
+                    e.thread, e.op, e.result, e.invoke, e.response
+                );
+            }
+            return Err(msg);
+        }
+    }
+    Ok(())
+}
+
+/// The search for a linearization of the history of a single key, sorted by the invocations.
+struct Search<'h> {
+    events: &'h [Event],
+    /// Whether each event is linearized so far.
+    done: Vec<bool>,
+    /// The pairs of the linearized events and the state explored so far. Revisiting one of them
+    /// cannot lead to a linearization, as the search would have stopped otherwise.
+    visited: HashSet<(Vec<bool>, bool)>,
+}
+
+impl<'h> Search<'h> {
+    fn new(events: &'h [Event]) -> Self {
+        Self {
+            events,
+            done: vec![false; events.len()],
+            visited: HashSet::new(),
+        }
+    }
+
+    fn run(&mut self) -> bool {
+        // The linearized events in order, with the states before them.
+        let mut stack = Vec::<(usize, bool)>::new();
+        let mut present = false;
+        let mut next = 0;
+        loop {
+            let Some(first) = self.done.iter().position(|d| !d) else {
+                return true;
+            };
+            // The events that may take effect first are the ones invoked before any of the
+            // remaining events responded.
+            let deadline = (first..self.events.len())
+                .filter(|&i| !self.done[i])
+                .map(|i| self.events[i].response)
+                .min()
+                .unwrap();
+            let candidate = (next.max(first)..self.events.len()).find(|&i| {
+                let e = &self.events[i];
+                !self.done[i] && e.invoke <= deadline && apply(present, e.op, e.result).is_some()
+            });
+            match candidate {
+                Some(i) => {
+                    let e = &self.events[i];
+                    let state = apply(present, e.op, e.result).unwrap();
+                    self.done[i] = true;
+                    if self.visited.insert((self.done.clone(), state)) {
+                        stack.push((i, present));
+                        present = state;
+                        next = 0;
+                    } else {
+                        // Already explored from here.
+                        self.done[i] = false;
+                        next = i + 1;
+                    }
+                }
+                None => {
+                    // Backtracks to the next candidate of the previous step.
+                    let Some((i, prev)) = stack.pop() else {
+                        return false;
+                    };
+                    self.done[i] = false;
+                    present = prev;
+                    next = i + 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, Event};
+    use crate::config::map::Op;
+    use std::time::Duration;
+
+    fn event(thread: usize, op: Op, result: bool, invoke: u64, response: u64) -> Event {
+        Event {
+            thread,
+            op,
+            key: 0,
+            result,
+            invoke: Duration::from_nanos(invoke),
+            response: Duration::from_nanos(response),
+        }
+    }
+
+    #[test]
+    fn sequential() {
+        let history = [
+            event(0, Op::Get, false, 0, 1),
+            event(0, Op::Insert, true, 2, 3),
+            event(0, Op::Insert, false, 4, 5),
+            event(0, Op::Get, true, 6, 7),
+            event(0, Op::Remove, true, 8, 9),
+        ];
+        assert!(check(&history).is_ok());
+        assert!(check(&[event(0, Op::Get, true, 0, 1)]).is_err());
+        assert!(check(&[event(0, Op::Remove, true, 0, 1)]).is_err());
+    }
+
+    #[test]
+    fn concurrent() {
+        // The remove takes effect between the two inserts.
+        let history = [
+            event(0, Op::Insert, true, 0, 10),
+            event(1, Op::Remove, true, 1, 9),
+            event(2, Op::Insert, true, 2, 8),
+            event(0, Op::Get, true, 11, 12),
+        ];
+        assert!(check(&history).is_ok());
+        // Both inserts succeed without a remove in between.
+        let history = [
+            event(0, Op::Insert, true, 0, 10),
+            event(1, Op::Insert, true, 1, 9),
+        ];
+        assert!(check(&history).is_err());
+        // The get responded before the insert was invoked.
+        let history = [
+            event(0, Op::Get, true, 0, 1),
+            event(1, Op::Insert, true, 2, 3),
+        ];
+        assert!(check(&history).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use cdrc::{ebr_impl, hp_impl, Cs, CsEBR, CsHP};
+use smr_stats::Stats;
+use std::marker::PhantomData;
+use std::sync::atomic::Ordering;
+
+use super::{Driver, MapAdapter};
+use crate::config::map::{Config, DS};
+use crate::ds_impl::cdrc::{
+    BonsaiTreeMap, ConcurrentMap, EFRBTree, HHSList, HList, HMList, HashMap, NMTreeMap, SkipList,
+};
+
+/// The drivers of CDRC on top of the critical section `C`. If `FLUSH` is set, the local garbages are
+/// eagerly reclaimed at the end of each critical section.
+pub fn driver<C: CdrcCs, const FLUSH: bool>(ds: &DS) -> Option<Driver> {
+    Some(match ds {
+        DS::HList => Driver::of::<Cdrc<HList<usize, usize, C>, C, FLUSH>>(),
+        DS::HMList => Driver::of::<Cdrc<HMList<usize, usize, C>, C, FLUSH>>(),
+        DS::HHSList => Driver::of::<Cdrc<HHSList<usize, usize, C>, C, FLUSH>>(),
+        DS::HashMap => Driver::of::<Cdrc<HashMap<usize, usize, C>, C, FLUSH>>(),
+        DS::NMTree => Driver::of::<Cdrc<NMTreeMap<usize, usize, C>, C, FLUSH>>(),
+        DS::SkipList => Driver::of::<Cdrc<SkipList<usize, usize, C>, C, FLUSH>>(),
+        DS::BonsaiTree => Driver::of::<Cdrc<BonsaiTreeMap<usize, usize, C>, C, FLUSH>>(),
+        DS::EFRBTree => Driver::of::<Cdrc<EFRBTree<usize, usize, C>, C, FLUSH>>(),
+    })
+}
+
+/// The reclamation-specific knobs of a CDRC critical section.
+pub trait CdrcCs: Cs + 'static {
+    /// Whether `clear` ends a critical section, which can then span `-c` operations.
+    const CRITICAL_SECTION: bool;
+
+    fn set_counts_between_flush(counts: usize);
+    fn garbage_count() -> usize;
+    fn stats() -> Stats;
+}
+
+impl CdrcCs for CsEBR {
+    const CRITICAL_SECTION: bool = true;
+
+    fn set_counts_between_flush(counts: usize) {
+        cdrc::set_counts_between_flush_ebr(counts);
+    }
+
+    fn garbage_count() -> usize {
+        ebr_impl::GLOBAL_GARBAGE_COUNT.load(Ordering::Acquire)
+    }
+
+    fn stats() -> Stats {
+        ebr_impl::stats()
+    }
+}
+
+impl CdrcCs for CsHP {
+    const CRITICAL_SECTION: bool = false;
+
+    fn set_counts_between_flush(counts: usize) {
+        cdrc::set_counts_between_flush_hp(counts);
+    }
+
+    fn garbage_count() -> usize {
+        hp_impl::DEFAULT_DOMAIN.num_garbages()
+    }
+
+    fn stats() -> Stats {
+        hp_impl::DEFAULT_DOMAIN.stats()
+    }
+}
+
+pub struct Cdrc<M, C, const FLUSH: bool> {
+    map: M,
+    ops_per_cs: u64,
+    _marker: PhantomData<fn() -> C>,
+}
+
+pub struct CdrcLocal<O, C> {
+    output: O,
+    cs: C,
+}
+
+impl<M, C, const FLUSH: bool> MapAdapter for Cdrc<M, C, FLUSH>
+where
+    M: ConcurrentMap<usize, usize, C> + Sync,
+    C: CdrcCs,
+{
+    type Local<'l> = CdrcLocal<M::Output, C>;
+
+    const OPS_PER_CS: bool = C::CRITICAL_SECTION;
+
+    const VALIDATE_STRUCTURE: bool = true;
+
+    fn new(config: &Config) -> Self {
+        C::set_counts_between_flush(config.bag_size.size(64, 4096));
+        Self {
+            map: M::new(),
+            ops_per_cs: config.ops_per_cs,
+            _marker: PhantomData,
+        }
+    }
+
+    fn with_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        f(&mut CdrcLocal {
+            output: M::empty_output(),
+            cs: C::new(),
+        })
+    }
+
+    fn with_prefill_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        f(&mut CdrcLocal {
+            output: M::empty_output(),
+            cs: unsafe { C::unprotected() },
+        })
+    }
+
+    fn get(&self, local: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map.get(key, &mut local.output, &local.cs)
+    }
+
+    fn insert(&self, local: &mut Self::Local<'_>, key: usize, value: usize) -> bool {
+        self.map.insert(key, value, &mut local.output, &local.cs)
+    }
+
+    fn remove(&self, local: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map.remove(key, &mut local.output, &local.cs)
+    }
+
+    /// Ends the critical section every `-c` operations for the EBR flavors, and clears the shields
+    /// after every operation for the HP flavor, which has no critical section.
+    fn end_op(&self, local: &mut Self::Local<'_>, ops: u64) {
+        if !C::CRITICAL_SECTION || ops % self.ops_per_cs == 0 {
+            local.cs.clear();
+            if FLUSH {
+                local.cs.eager_reclaim();
+            }
+        }
+    }
+
+    fn garbage_count(&self) -> usize {
+        C::garbage_count()
+    }
+
+    fn stats(&self) -> Option<Stats> {
+        Some(C::stats())
+    }
+
+    fn validate(&self) -> Result<Vec<usize>, String> {
+        self.map
+            .validate()
+            .map(|keys| keys.into_iter().copied().collect())
+    }
+}
@@ -0,0 +1,84 @@
+use circ::{ebr_impl, set_counts_between_flush_ebr, Cs, CsEBR};
+use smr_stats::Stats;
+use std::sync::atomic::Ordering;
+
+use super::{Driver, MapAdapter};
+use crate::config::map::{Config, DS};
+use crate::ds_impl::circ_ebr::{
+    BonsaiTreeMap, ConcurrentMap, EFRBTree, HHSList, HList, HMList, HashMap, NMTreeMap, SkipList,
+};
+
+pub fn driver(ds: &DS) -> Option<Driver> {
+    Some(match ds {
+        DS::HList => Driver::of::<CircEbr<HList<usize, usize>>>(),
+        DS::HMList => Driver::of::<CircEbr<HMList<usize, usize>>>(),
+        DS::HHSList => Driver::of::<CircEbr<HHSList<usize, usize>>>(),
+        DS::HashMap => Driver::of::<CircEbr<HashMap<usize, usize>>>(),
+        DS::NMTree => Driver::of::<CircEbr<NMTreeMap<usize, usize>>>(),
+        DS::SkipList => Driver::of::<CircEbr<SkipList<usize, usize>>>(),
+        DS::BonsaiTree => Driver::of::<CircEbr<BonsaiTreeMap<usize, usize>>>(),
+        DS::EFRBTree => Driver::of::<CircEbr<EFRBTree<usize, usize>>>(),
+    })
+}
+
+/// Repins the critical section every `-c` operations.
+pub struct CircEbr<M> {
+    map: M,
+    ops_per_cs: u64,
+}
+
+impl<M: ConcurrentMap<usize, usize> + Sync> MapAdapter for CircEbr<M> {
+    type Local<'l> = CsEBR;
+
+    const OPS_PER_CS: bool = true;
+
+    const VALIDATE_STRUCTURE: bool = true;
+
+    fn new(config: &Config) -> Self {
+        set_counts_between_flush_ebr(config.bag_size.size(64, 4096));
+        Self {
+            map: M::new(),
+            ops_per_cs: config.ops_per_cs,
+        }
+    }
+
+    fn with_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        f(&mut CsEBR::new())
+    }
+
+    fn with_prefill_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        f(&mut unsafe { CsEBR::unprotected() })
+    }
+
+    fn get(&self, cs: &mut CsEBR, key: &usize) -> bool {
+        self.map.get(key, cs).is_some()
+    }
+
+    fn insert(&self, cs: &mut CsEBR, key: usize, value: usize) -> bool {
+        self.map.insert(key, value, cs)
+    }
+
+    fn remove(&self, cs: &mut CsEBR, key: &usize) -> bool {
+        self.map.remove(key, cs).is_some()
+    }
+
+    fn end_op(&self, cs: &mut CsEBR, ops: u64) {
+        if ops % self.ops_per_cs == 0 {
+            cs.clear();
+        }
+    }
+
+    fn garbage_count(&self) -> usize {
+        ebr_impl::GLOBAL_GARBAGE_COUNT.load(Ordering::Acquire)
+    }
+
+    fn stats(&self) -> Option<Stats> {
+        Some(ebr_impl::stats())
+    }
+
+    fn validate(&self) -> Result<Vec<usize>, String> {
+        self.map
+            .validate()
+            .map(|keys| keys.into_iter().copied().collect())
+    }
+}
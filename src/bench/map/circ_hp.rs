@@ -0,0 +1,85 @@
+use circ::{hp_impl, set_counts_between_flush_hp, Cs, CsHP};
+use smr_stats::Stats;
+
+use super::{Driver, MapAdapter};
+use crate::config::map::{Config, DS};
+use crate::ds_impl::circ_hp::{
+    BonsaiTreeMap, ConcurrentMap, EFRBTree, HHSList, HList, HMList, HashMap, NMTreeMap, SkipList,
+};
+
+pub fn driver(ds: &DS) -> Option<Driver> {
+    Some(match ds {
+        DS::HList => Driver::of::<CircHp<HList<usize, usize>>>(),
+        DS::HMList => Driver::of::<CircHp<HMList<usize, usize>>>(),
+        DS::HHSList => Driver::of::<CircHp<HHSList<usize, usize>>>(),
+        DS::HashMap => Driver::of::<CircHp<HashMap<usize, usize>>>(),
+        DS::NMTree => Driver::of::<CircHp<NMTreeMap<usize, usize>>>(),
+        DS::SkipList => Driver::of::<CircHp<SkipList<usize, usize>>>(),
+        DS::BonsaiTree => Driver::of::<CircHp<BonsaiTreeMap<usize, usize>>>(),
+        DS::EFRBTree => Driver::of::<CircHp<EFRBTree<usize, usize>>>(),
+    })
+}
+
+pub struct CircHp<M> {
+    map: M,
+}
+
+pub struct CircHpLocal<O> {
+    output: O,
+    cs: CsHP,
+}
+
+impl<M: ConcurrentMap<usize, usize> + Sync> MapAdapter for CircHp<M> {
+    type Local<'l> = CircHpLocal<M::Output>;
+
+    const VALIDATE_STRUCTURE: bool = true;
+
+    fn new(config: &Config) -> Self {
+        set_counts_between_flush_hp(config.bag_size.size(64, 4096));
+        Self { map: M::new() }
+    }
+
+    fn with_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        f(&mut CircHpLocal {
+            output: M::empty_output(),
+            cs: CsHP::new(),
+        })
+    }
+
+    fn with_prefill_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        f(&mut CircHpLocal {
+            output: M::empty_output(),
+            cs: unsafe { CsHP::unprotected() },
+        })
+    }
+
+    fn get(&self, local: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map.get(key, &mut local.output, &local.cs)
+    }
+
+    fn insert(&self, local: &mut Self::Local<'_>, key: usize, value: usize) -> bool {
+        self.map.insert(key, value, &mut local.output, &local.cs)
+    }
+
+    fn remove(&self, local: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map.remove(key, &mut local.output, &local.cs)
+    }
+
+    fn end_op(&self, local: &mut Self::Local<'_>, _ops: u64) {
+        local.cs.clear();
+    }
+
+    fn garbage_count(&self) -> usize {
+        hp_impl::DEFAULT_DOMAIN.num_garbages()
+    }
+
+    fn stats(&self) -> Option<Stats> {
+        Some(hp_impl::DEFAULT_DOMAIN.stats())
+    }
+
+    fn validate(&self) -> Result<Vec<usize>, String> {
+        self.map
+            .validate()
+            .map(|keys| keys.into_iter().copied().collect())
+    }
+}
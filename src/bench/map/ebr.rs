@@ -0,0 +1,78 @@
+use std::sync::atomic::Ordering;
+
+use super::{Driver, MapAdapter};
+use crate::config::map::{Config, DS};
+use crate::ds_impl::ebr::{
+    BonsaiTreeMap, ConcurrentMap, EFRBTree, HHSList, HList, HMList, HashMap, NMTreeMap, SkipList,
+};
+
+pub fn driver(ds: &DS) -> Option<Driver> {
+    Some(match ds {
+        DS::HList => Driver::of::<Ebr<HList<usize, usize>>>(),
+        DS::HMList => Driver::of::<Ebr<HMList<usize, usize>>>(),
+        DS::HHSList => Driver::of::<Ebr<HHSList<usize, usize>>>(),
+        DS::HashMap => Driver::of::<Ebr<HashMap<usize, usize>>>(),
+        DS::NMTree => Driver::of::<Ebr<NMTreeMap<usize, usize>>>(),
+        DS::BonsaiTree => Driver::of::<Ebr<BonsaiTreeMap<usize, usize>>>(),
+        DS::EFRBTree => Driver::of::<Ebr<EFRBTree<usize, usize>>>(),
+        DS::SkipList => Driver::of::<Ebr<SkipList<usize, usize>>>(),
+    })
+}
+
+/// Repins the guard every `-c` operations.
+pub struct Ebr<M> {
+    map: M,
+    collector: crossbeam_ebr::Collector,
+    ops_per_cs: u64,
+}
+
+impl<M: ConcurrentMap<usize, usize> + Sync> MapAdapter for Ebr<M> {
+    type Local<'l> = crossbeam_ebr::Guard;
+
+    const OPS_PER_CS: bool = true;
+
+    const VALIDATE_STRUCTURE: bool = true;
+
+    fn new(config: &Config) -> Self {
+        crossbeam_ebr::set_bag_capacity(config.bag_size.size(64, 4096));
+        Self {
+            map: M::new(),
+            collector: crossbeam_ebr::Collector::new(),
+            ops_per_cs: config.ops_per_cs,
+        }
+    }
+
+    fn with_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        let handle = self.collector.register();
+        let mut guard = handle.pin();
+        f(&mut guard)
+    }
+
+    fn get(&self, guard: &mut crossbeam_ebr::Guard, key: &usize) -> bool {
+        self.map.get(key, guard).is_some()
+    }
+
+    fn insert(&self, guard: &mut crossbeam_ebr::Guard, key: usize, value: usize) -> bool {
+        self.map.insert(key, value, guard)
+    }
+
+    fn remove(&self, guard: &mut crossbeam_ebr::Guard, key: &usize) -> bool {
+        self.map.remove(key, guard).is_some()
+    }
+
+    fn end_op(&self, guard: &mut crossbeam_ebr::Guard, ops: u64) {
+        if ops % self.ops_per_cs == 0 {
+            guard.repin();
+        }
+    }
+
+    fn garbage_count(&self) -> usize {
+        crossbeam_ebr::GLOBAL_GARBAGE_COUNT.load(Ordering::Acquire)
+    }
+
+    fn validate(&self) -> Result<Vec<usize>, String> {
+        self.map
+            .validate()
+            .map(|keys| keys.into_iter().copied().collect())
+    }
+}
@@ -0,0 +1,63 @@
+use hp_pp::{set_counts_between_flush, DEFAULT_DOMAIN};
+use smr_stats::Stats;
+
+use super::{Driver, MapAdapter};
+use crate::config::map::{Config, DS};
+use crate::ds_impl::hp::{BonsaiTreeMap, ConcurrentMap, EFRBTree, HMList, HashMap, SkipList};
+
+pub fn driver(ds: &DS) -> Option<Driver> {
+    Some(match ds {
+        DS::HMList => Driver::of::<Hp<HMList<usize, usize>>>(),
+        DS::HashMap => Driver::of::<Hp<HashMap<usize, usize>>>(),
+        DS::EFRBTree => Driver::of::<Hp<EFRBTree<usize, usize>>>(),
+        DS::SkipList => Driver::of::<Hp<SkipList<usize, usize>>>(),
+        DS::BonsaiTree => Driver::of::<Hp<BonsaiTreeMap<usize, usize>>>(),
+        _ => return None,
+    })
+}
+
+/// An adapter for the maps implementing [`ConcurrentMap`] of HP, which is shared by HP and HP++.
+pub struct Hp<M> {
+    map: M,
+}
+
+impl<M: ConcurrentMap<usize, usize> + Sync> MapAdapter for Hp<M> {
+    type Local<'l> = M::Handle<'static>;
+
+    const VALIDATE_STRUCTURE: bool = true;
+
+    fn new(config: &Config) -> Self {
+        set_counts_between_flush(config.bag_size.size(64, 4096));
+        Self { map: M::new() }
+    }
+
+    fn with_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        f(&mut M::handle())
+    }
+
+    fn get(&self, handle: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map.get(handle, key).is_some()
+    }
+
+    fn insert(&self, handle: &mut Self::Local<'_>, key: usize, value: usize) -> bool {
+        self.map.insert(handle, key, value)
+    }
+
+    fn remove(&self, handle: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map.remove(handle, key).is_some()
+    }
+
+    fn garbage_count(&self) -> usize {
+        DEFAULT_DOMAIN.num_garbages()
+    }
+
+    fn stats(&self) -> Option<Stats> {
+        Some(DEFAULT_DOMAIN.stats())
+    }
+
+    fn validate(&self) -> Result<Vec<usize>, String> {
+        self.map
+            .validate()
+            .map(|keys| keys.into_iter().copied().collect())
+    }
+}
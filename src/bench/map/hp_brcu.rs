@@ -0,0 +1,92 @@
+use hp_brcu::{global, Thread, THREAD};
+use smr_stats::Stats;
+
+use super::{Driver, MapAdapter};
+use crate::config::map::{BagSize, Config, DS};
+use crate::ds_impl::hp_brcu::{
+    BonsaiTreeMap, ConcurrentMap, EFRBTree, HHSList, HList, HMList, HashMap, NMTreeMap, SkipList,
+};
+
+/// The drivers of HP-BRCU, or HP-RCU if `ROLLBACK` is `false`.
+pub fn driver<const ROLLBACK: bool>(ds: &DS) -> Option<Driver> {
+    Some(match ds {
+        DS::HList => Driver::of::<HpBrcu<ROLLBACK, HList<usize, usize>>>(),
+        DS::HMList => Driver::of::<HpBrcu<ROLLBACK, HMList<usize, usize>>>(),
+        DS::HHSList => Driver::of::<HpBrcu<ROLLBACK, HHSList<usize, usize>>>(),
+        DS::HashMap => Driver::of::<HpBrcu<ROLLBACK, HashMap<usize, usize>>>(),
+        DS::NMTree => Driver::of::<HpBrcu<ROLLBACK, NMTreeMap<usize, usize>>>(),
+        DS::SkipList => Driver::of::<HpBrcu<ROLLBACK, SkipList<usize, usize>>>(),
+        DS::EFRBTree => Driver::of::<HpBrcu<ROLLBACK, EFRBTree<usize, usize>>>(),
+        DS::BonsaiTree => Driver::of::<HpBrcu<ROLLBACK, BonsaiTreeMap<usize, usize>>>(),
+    })
+}
+
+pub struct HpBrcu<const ROLLBACK: bool, M> {
+    map: M,
+}
+
+pub struct HpBrcuLocal<'l, O> {
+    thread: &'l mut Thread,
+    output: O,
+}
+
+impl<const ROLLBACK: bool, M: ConcurrentMap<usize, usize> + Sync> MapAdapter
+    for HpBrcu<ROLLBACK, M>
+{
+    type Local<'l> = HpBrcuLocal<'l, M::Output>;
+
+    const BAG_SIZE: bool = false;
+
+    const VALIDATE_STRUCTURE: bool = true;
+
+    fn new(config: &Config) -> Self {
+        unsafe { hp_brcu::set_rollback(ROLLBACK) };
+        if config.bag_size != BagSize::Small {
+            println!(
+                "Warning: Bag size {} is currently unavailable for HP-BRCU.",
+                config.bag_size
+            );
+        }
+        Self { map: M::new() }
+    }
+
+    fn with_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        THREAD.with(|th| {
+            let thread = &mut **th.borrow_mut();
+            let output = M::empty_output(thread);
+            f(&mut HpBrcuLocal { thread, output })
+        })
+    }
+
+    fn get(&self, local: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map.get(key, &mut local.output, local.thread)
+    }
+
+    fn insert(&self, local: &mut Self::Local<'_>, key: usize, value: usize) -> bool {
+        self.map.insert(key, value, &mut local.output, local.thread)
+    }
+
+    fn remove(&self, local: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map.remove(key, &mut local.output, local.thread)
+    }
+
+    /// Stalls in a critical section, which is rolled back and restarted if it blocks the
+    /// reclamation of the others for too long (HP-BRCU only).
+    fn stall(&self, local: &mut Self::Local<'_>, _key: usize, mut wait: impl FnMut()) {
+        unsafe { local.thread.critical_section(|_| wait()) };
+    }
+
+    fn garbage_count(&self) -> usize {
+        global().garbage_count()
+    }
+
+    fn stats(&self) -> Option<Stats> {
+        Some(global().stats())
+    }
+
+    fn validate(&self) -> Result<Vec<usize>, String> {
+        self.map
+            .validate()
+            .map(|keys| keys.into_iter().copied().collect())
+    }
+}
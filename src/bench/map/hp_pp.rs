@@ -0,0 +1,19 @@
+use super::hp::Hp;
+use super::Driver;
+use crate::config::map::DS;
+use crate::ds_impl::hp_pp::{
+    BonsaiTreeMap, EFRBTree, HHSList, HList, HMList, HashMap, NMTreeMap, SkipList,
+};
+
+pub fn driver(ds: &DS) -> Option<Driver> {
+    Some(match ds {
+        DS::HList => Driver::of::<Hp<HList<usize, usize>>>(),
+        DS::HMList => Driver::of::<Hp<HMList<usize, usize>>>(),
+        DS::HHSList => Driver::of::<Hp<HHSList<usize, usize>>>(),
+        DS::HashMap => Driver::of::<Hp<HashMap<usize, usize>>>(),
+        DS::NMTree => Driver::of::<Hp<NMTreeMap<usize, usize>>>(),
+        DS::EFRBTree => Driver::of::<Hp<EFRBTree<usize, usize>>>(),
+        DS::SkipList => Driver::of::<Hp<SkipList<usize, usize>>>(),
+        DS::BonsaiTree => Driver::of::<Hp<BonsaiTreeMap<usize, usize>>>(),
+    })
+}
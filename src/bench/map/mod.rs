@@ -0,0 +1,1091 @@
+//! The map benchmark driver and the registry of reclamation schemes.
+//!
+//! Each scheme provides a [`MapAdapter`] for its data structures and registers a [`Scheme`] in
+//! [`SCHEMES`]. The driver itself (prefill, worker threads and the auxiliary sampling thread) is
+//! shared by all schemes.
+
+pub mod cdrc;
+pub mod circ_ebr;
+pub mod circ_hp;
+pub mod ebr;
+pub mod hp;
+pub mod hp_brcu;
+pub mod hp_pp;
+pub mod nbr;
+pub mod nr;
+pub mod pebr;
+pub mod vbr;
+
+use clap::ValueEnum;
+use crossbeam_utils::thread::scope;
+use crossbeam_utils::CachePadded;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use smr_stats::{Pool, Stats};
+use std::cmp::{self, max, min};
+use std::fs::{create_dir_all, File};
+use std::hint::spin_loop;
+use std::io::{stdout, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Barrier, Mutex};
+use std::thread::available_parallelism;
+use std::time::{Duration, Instant};
+
+use super::affinity::pin;
+use super::histogram::Histogram;
+use super::linearizability::{self, Event};
+use super::trace::{Trace, TraceOp, TraceWriter};
+use super::validate::Tally;
+use crate::config::map::{Config, Op, Perf, PrefillStrategy, DS};
+use crate::utils::seed_thread_rng;
+
+/// A reclamation scheme that can be selected with `-m`.
+pub struct Scheme {
+    /// The name of the scheme, which is also written to the `mm` column of the output.
+    pub name: &'static str,
+    /// The driver of each data structure implemented with the scheme, which is also the source of
+    /// the capabilities reported by `--list-capabilities`.
+    pub driver: fn(&DS) -> Option<Driver>,
+}
+
+/// All reclamation schemes supported by the map benchmark.
+pub const SCHEMES: &[Scheme] = &[
+    Scheme {
+        name: "nr",
+        driver: nr::driver,
+    },
+    Scheme {
+        name: "ebr",
+        driver: ebr::driver,
+    },
+    Scheme {
+        name: "pebr",
+        driver: pebr::driver,
+    },
+    Scheme {
+        name: "hp",
+        driver: hp::driver,
+    },
+    Scheme {
+        name: "hp-pp",
+        driver: hp_pp::driver,
+    },
+    Scheme {
+        name: "nbr",
+        driver: nbr::driver,
+    },
+    Scheme {
+        name: "hp-brcu",
+        driver: hp_brcu::driver::<true>,
+    },
+    Scheme {
+        name: "hp-rcu",
+        driver: hp_brcu::driver::<false>,
+    },
+    Scheme {
+        name: "vbr",
+        driver: vbr::driver,
+    },
+    Scheme {
+        name: "cdrc-ebr",
+        driver: cdrc::driver::<::cdrc::CsEBR, false>,
+    },
+    Scheme {
+        name: "cdrc-ebr-flush",
+        driver: cdrc::driver::<::cdrc::CsEBR, true>,
+    },
+    Scheme {
+        name: "cdrc-hp",
+        driver: cdrc::driver::<::cdrc::CsHP, false>,
+    },
+    Scheme {
+        name: "circ-ebr",
+        driver: circ_ebr::driver,
+    },
+    Scheme {
+        name: "circ-hp",
+        driver: circ_hp::driver,
+    },
+];
+
+/// Finds a registered scheme by its name.
+pub fn scheme(name: &str) -> Option<&'static Scheme> {
+    SCHEMES.iter().find(|s| s.name == name)
+}
+
+impl Scheme {
+    pub fn supports(&self, ds: &DS) -> bool {
+        (self.driver)(ds).is_some()
+    }
+
+    /// The data structures implemented with the scheme.
+    pub fn data_structures(&self) -> Vec<DS> {
+        DS::value_variants()
+            .iter()
+            .filter(|ds| self.supports(ds))
+            .cloned()
+            .collect()
+    }
+
+    /// Runs the map benchmark with the given configuration and returns the result of each trial.
+    pub fn bench(&self, config: &Config) -> Vec<Perf> {
+        let driver = (self.driver)(&config.ds).unwrap_or_else(|| {
+            panic!(
+                "Unsupported(or unimplemented) data structure for {}",
+                self.name
+            )
+        });
+        (driver.bench)(config)
+    }
+}
+
+/// The benchmark of a data structure under a scheme, with the options it honors.
+#[derive(Clone, Copy)]
+pub struct Driver {
+    pub bench: fn(&Config) -> Vec<Perf>,
+    /// Whether the bag size (`-b`) takes effect.
+    pub bag_size: bool,
+    /// Whether the number of operations per critical section (`-c`) takes effect.
+    pub ops_per_cs: bool,
+    /// Whether the non-cooperative thread (`-n`) takes effect.
+    pub non_coop: bool,
+    /// Whether the worker threads can be replaced during a run (`--churn`).
+    pub churn: bool,
+    /// Whether `--validate` checks the structural invariants and all keys of the map, rather than
+    /// only the tallied keys through `get`.
+    pub validate_structure: bool,
+    /// Runs the given number of operations per worker on an empty map and checks the
+    /// linearizability of their history.
+    pub check: fn(&Config, usize) -> Result<(), String>,
+    /// Rejects the options that the scheme restricts further than the command-line parser.
+    pub check_config: fn(&Config) -> Result<(), String>,
+}
+
+impl Driver {
+    /// The driver running `M` with [`bench_map`].
+    pub fn of<M: MapAdapter>() -> Self {
+        Self {
+            bench: bench_map::<M>,
+            bag_size: M::BAG_SIZE,
+            ops_per_cs: M::OPS_PER_CS,
+            non_coop: M::NON_COOP,
+            churn: M::CHURN,
+            validate_structure: M::VALIDATE_STRUCTURE,
+            check: check_map::<M>,
+            check_config: M::check_config,
+        }
+    }
+}
+
+/// Drives a map data structure under a particular reclamation scheme.
+///
+/// The adapter owns the map together with any global state of the scheme, and hides the
+/// scheme-specific thread-local state (guards, handles, output holders, ...) behind `Local`.
+pub trait MapAdapter: Sync + Sized {
+    /// Thread-local state of a thread operating on the map.
+    type Local<'l>;
+
+    /// Whether a thread stalled in `stall` can interfere with the reclamation of the others
+    /// (`-n`).
+    const NON_COOP: bool = true;
+
+    /// Whether a critical section spans `config.ops_per_cs` operations, ended by `end_op`.
+    const OPS_PER_CS: bool = false;
+
+    /// Whether `new` configures the size of the retirement bags by `config.bag_size`.
+    const BAG_SIZE: bool = true;
+
+    /// Whether threads can exit and new threads can call `with_local` after `prepare` (`--churn`).
+    const CHURN: bool = true;
+
+    /// Whether `validate` checks the structural invariants of the data structure (`--validate`).
+    const VALIDATE_STRUCTURE: bool = false;
+
+    /// Rejects the options that the scheme restricts further than the command-line parser (e.g.,
+    /// the bag size of NBR must be a power of two). It is checked before `new`.
+    fn check_config(_config: &Config) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Creates an empty map and configures the scheme (e.g., the size of retirement bags).
+    fn new(config: &Config) -> Self;
+
+    /// Prepares the scheme for `threads` threads which are about to call `with_local`
+    /// concurrently.
+    fn prepare(&mut self, _threads: usize) {}
+
+    /// Registers the current thread and runs `f` with its thread-local state.
+    fn with_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R;
+
+    /// Like `with_local`, but for the prefilling threads, which never remove nodes. Schemes with
+    /// an unprotected mode may skip the protection here.
+    fn with_prefill_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        self.with_local(f)
+    }
+
+    fn get(&self, local: &mut Self::Local<'_>, key: &usize) -> bool;
+    fn insert(&self, local: &mut Self::Local<'_>, key: usize, value: usize) -> bool;
+    fn remove(&self, local: &mut Self::Local<'_>, key: &usize) -> bool;
+
+    /// Called after each operation of a worker with the number of operations done so far.
+    fn end_op(&self, _local: &mut Self::Local<'_>, _ops: u64) {}
+
+    /// Runs `wait` in the middle of an operation on `key`, holding what a thread stalled there
+    /// holds (`-n`). `wait` may be called again if the scheme restarts the operation.
+    ///
+    /// By default, it waits after a `get`, before `end_op`, so the thread keeps its critical
+    /// section (`0` ends it for any `-c`) or the hazard pointers protecting the nodes of `key`.
+    fn stall(&self, local: &mut Self::Local<'_>, key: usize, mut wait: impl FnMut()) {
+        self.get(local, &key);
+        wait();
+        self.end_op(local, 0);
+    }
+
+    /// The number of retired but not yet reclaimed nodes.
+    fn garbage_count(&self) -> usize {
+        0
+    }
+
+    /// The cumulative reclamation statistics of the scheme, or `None` if the scheme does not count
+    /// them.
+    fn stats(&self) -> Option<Stats> {
+        None
+    }
+
+    /// The pool from which the scheme reuses the retired nodes, or `None` if it frees them.
+    fn pool(&self) -> Option<Pool> {
+        None
+    }
+
+    /// Checks the structural invariants of the map after all threads stopped, and returns its
+    /// keys. It is called only if `VALIDATE_STRUCTURE` is set.
+    fn validate(&self) -> Result<Vec<usize>, String> {
+        unreachable!("no structural check of the map")
+    }
+}
+
+/// Inserts `config.prefill` keys into the map in the order of `config.prefill_strategy`, and adds
+/// them to `tally` if given.
+fn prefill<M: MapAdapter>(config: &Config, map: &mut M, tally: Option<&Mutex<Tally>>) {
+    let single = |map: &mut M, order: fn(&usize, &usize) -> cmp::Ordering| {
+        map.prepare(1);
+        map.with_prefill_local(|local| {
+            let rng = &mut thread_rng(config, 0, 0);
+            let key_sampler = &mut config.key_dist.sampler(0, 1);
+            let mut local_tally = tally.map(|_| Tally::default());
+            let mut keys = Vec::with_capacity(config.prefill);
+            for _ in 0..config.prefill {
+                keys.push(key_sampler.sample(rng));
+            }
+            keys.sort_by(order);
+            for key in keys.drain(..) {
+                let value = key;
+                if map.insert(local, key, value) {
+                    if let Some(local_tally) = &mut local_tally {
+                        local_tally.insert(key);
+                    }
+                }
+            }
+            if let (Some(tally), Some(local_tally)) = (tally, local_tally) {
+                tally.lock().unwrap().merge(local_tally);
+            }
+        });
+    };
+    match config.prefill_strategy {
+        PrefillStrategy::Random => {
+            let threads = available_parallelism().map(|v| v.get()).unwrap_or(1);
+            print!("prefilling with {threads} threads... ");
+            stdout().flush().unwrap();
+            map.prepare(threads);
+            let map = &*map;
+            scope(|s| {
+                for t in 0..threads {
+                    s.spawn(move |_| {
+                        map.with_prefill_local(|local| {
+                            let rng = &mut thread_rng(config, 0, t);
+                            let key_sampler = &mut config.key_dist.sampler(t, threads);
+                            let mut local_tally = tally.map(|_| Tally::default());
+                            let count = config.prefill / threads
+                                + if t < config.prefill % threads { 1 } else { 0 };
+                            for _ in 0..count {
+                                let key = key_sampler.sample(rng);
+                                let value = key;
+                                if map.insert(local, key, value) {
+                                    if let Some(local_tally) = &mut local_tally {
+                                        local_tally.insert(key);
+                                    }
+                                }
+                            }
+                            if let (Some(tally), Some(local_tally)) = (tally, local_tally) {
+                                tally.lock().unwrap().merge(local_tally);
+                            }
+                        });
+                    });
+                }
+            })
+            .unwrap();
+        }
+        PrefillStrategy::Decreasing => single(map, |a, b| b.cmp(a)),
+        PrefillStrategy::Increasing => single(map, |a, b| a.cmp(b)),
+        PrefillStrategy::None => return,
+    }
+    print!("prefilled... ");
+    stdout().flush().unwrap();
+}
+
+/// Runs the map benchmark on `M` and measures its performance in each trial (`--trials`).
+///
+/// All trials run on the same prefilled map, after the recording (`--record`) and the warm-up
+/// (`--warmup`) which are not measured.
+pub fn bench_map<M: MapAdapter>(config: &Config) -> Vec<Perf> {
+    assert!(
+        M::CHURN || config.churn.is_none(),
+        "Thread churn is currently unavailable for {}.",
+        config.mm
+    );
+    assert!(
+        M::NON_COOP || config.non_coop == 0,
+        "The non-cooperative thread is currently unavailable for {}.",
+        config.mm
+    );
+    let non_coop = config.non_coop > 0;
+    let threads = config.threads + if non_coop { 1 } else { 0 };
+    let map = &mut M::new(config);
+    let tally = config.validate.then(Mutex::<Tally>::default);
+    prefill(config, map, tally.as_ref());
+
+    if let Some(path) = &config.record {
+        // The operations are recorded in a run of their own right after the prefill, as writing
+        // them would slow down the workers of a measured trial. The run draws the same streams as
+        // the first trial.
+        print!("recording... ");
+        stdout().flush().unwrap();
+        map.prepare(threads);
+        run(
+            map,
+            config,
+            2,
+            config.duration,
+            None,
+            Some(path),
+            tally.as_ref(),
+        );
+        Trace::join_parts(path, config.threads);
+    }
+
+    if !config.warmup.is_zero() {
+        print!("warming up... ");
+        stdout().flush().unwrap();
+        map.prepare(threads);
+        run(map, config, 1, config.warmup, None, None, tally.as_ref());
+    }
+
+    let mut timeseries = config.timeseries.as_deref().map(Timeseries::create);
+    let perfs = (0..config.trials)
+        .map(|trial| {
+            if config.trials > 1 {
+                print!("trial {trial}... ");
+                stdout().flush().unwrap();
+            }
+            map.prepare(threads);
+            if let Some(timeseries) = &mut timeseries {
+                timeseries.trial = trial;
+            }
+            run(
+                map,
+                config,
+                2 + trial as u64,
+                config.duration,
+                timeseries.as_mut(),
+                None,
+                tally.as_ref(),
+            )
+        })
+        .collect();
+    if let Some(tally) = tally {
+        validate(map, tally.into_inner().unwrap());
+    }
+    println!("end");
+    perfs
+}
+
+/// Checks the keys of the map against the tally of the successful operations, and panics on a
+/// violation. The structure of the map and the absence of other keys are also checked if the
+/// adapter supports it (`VALIDATE_STRUCTURE`).
+fn validate<M: MapAdapter>(map: &mut M, tally: Tally) {
+    print!("validating... ");
+    stdout().flush().unwrap();
+    let keys = M::VALIDATE_STRUCTURE.then(|| {
+        map.validate()
+            .unwrap_or_else(|e| panic!("invalid structure: {e}"))
+    });
+    map.prepare(1);
+    let count = map
+        .with_local(|local| tally.check(|key| map.get(local, &key), keys))
+        .unwrap_or_else(|e| panic!("invalid keys: {e}"));
+    print!("{count} keys validated... ");
+}
+
+/// Runs `ops` operations sampled from the configuration on each of the `config.threads` workers
+/// on an empty map, records the history of their invocations and responses, and checks its
+/// linearizability. The key range should be small so that the workers contend on the same keys.
+pub fn check_map<M: MapAdapter>(config: &Config, ops: usize) -> Result<(), String> {
+    let mut map = M::new(config);
+    map.prepare(config.threads);
+    let map = &map;
+    let barrier = &Barrier::new(config.threads);
+    let start = Instant::now();
+    let history = scope(|s| {
+        let workers = (0..config.threads)
+            .map(|t| {
+                s.spawn(move |_| {
+                    map.with_local(|local| {
+                        let mut rng = &mut thread_rng(config, 2, t);
+                        let key_sampler = &mut config.key_dist.sampler(t, config.threads);
+                        let mut history = Vec::with_capacity(ops);
+                        barrier.wait();
+                        for i in 0..ops {
+                            let key = key_sampler.sample(rng);
+                            let op = Op::OPS[config.op_dist.sample(&mut rng)];
+                            let invoke = start.elapsed();
+                            let result = match op {
+                                Op::Get => map.get(local, &key),
+                                Op::Insert => map.insert(local, key, key),
+                                Op::Remove => map.remove(local, &key),
+                            };
+                            let response = start.elapsed();
+                            map.end_op(local, i as u64 + 1);
+                            history.push(Event {
+                                thread: t,
+                                op,
+                                key,
+                                result,
+                                invoke,
+                                response,
+                            });
+                        }
+                        history
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap())
+            .collect::<Vec<_>>()
+    })
+    .unwrap();
+    linearizability::check(&history)
+}
+
+/// Creates the random number generator of the `thread`-th thread in the `phase`-th phase (0 for
+/// the prefill, 1 for the warm-up, and then the trials) for sampling keys and operations, and seeds
+/// the thread-local one used inside the data structures. All of them are derived from `--seed`, so
+/// each thread draws the same streams in every run with the same seed.
+fn thread_rng(config: &Config, phase: u64, thread: usize) -> StdRng {
+    let stream = (phase << 32 | thread as u64) << 1;
+    let seed = |stream: u64| config.seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    seed_thread_rng(seed(stream | 1));
+    StdRng::seed_from_u64(seed(stream))
+}
+
+/// Runs the workers (and the auxiliary and non-cooperative threads) of the `phase`-th phase on the
+/// map for `duration`,
+/// or until the workers replay all operations of `--trace`. If `record` is set, the workers also
+/// write their operations to the parts of the trace file `record` (see `TraceWriter`). The
+/// successful inserts and removes are added to `tally` if given.
+fn run<M: MapAdapter>(
+    map: &M,
+    config: &Config,
+    phase: u64,
+    duration: Duration,
+    timeseries: Option<&mut Timeseries>,
+    record: Option<&str>,
+    tally: Option<&Mutex<Tally>>,
+) -> Perf {
+    let non_coop = config.non_coop > 0;
+    // The number of workers that finished, which stops the auxiliary thread.
+    let finished = &AtomicUsize::new(0);
+    let barrier = &Arc::new(Barrier::new(
+        config.threads + config.aux_thread + if non_coop { 1 } else { 0 },
+    ));
+    // The number of operations done so far by each worker, published only for the time series.
+    let progress = &(0..if timeseries.is_some() {
+        config.threads
+    } else {
+        0
+    })
+        .map(|_| CachePadded::new(AtomicU64::new(0)))
+        .collect::<Vec<_>>();
+    let (ops_sender, ops_receiver) = mpsc::channel();
+    let (mem_sender, mem_receiver) = mpsc::channel();
+    // The workers are pinned to the CPUs in order, followed by the auxiliary thread and the
+    // non-cooperative thread.
+    let cpus = &config.affinity.cpus();
+    let pin_slot = move |slot: usize| {
+        if let Some(cpus) = cpus {
+            pin(cpus[slot % cpus.len()]);
+        }
+    };
+    let stats = map.stats();
+
+    scope(|s| {
+        // sampling thread
+        if config.aux_thread > 0 {
+            let mem_sender = mem_sender.clone();
+            s.spawn(move |_| {
+                pin_slot(config.threads);
+                let result = sample(
+                    map, config, duration, barrier, progress, finished, timeseries,
+                );
+                mem_sender.send(result).unwrap();
+            });
+        } else {
+            mem_sender.send((0, 0, 0, 0)).unwrap();
+        }
+
+        // non-cooperative thread, which stalls in an operation for `non_coop_period` and then
+        // starts another
+        if non_coop {
+            s.spawn(move |_| {
+                pin_slot(config.threads + 1);
+                map.with_local(|local| {
+                    let rng = &mut thread_rng(config, phase, config.threads);
+                    let key_sampler = &mut config.key_dist.sampler(0, 1);
+                    barrier.clone().wait();
+                    let start = Instant::now();
+                    let stopped = || {
+                        start.elapsed() >= duration
+                            || finished.load(Ordering::Acquire) >= config.threads
+                    };
+                    while !stopped() {
+                        let until = start.elapsed() + config.non_coop_period;
+                        map.stall(local, key_sampler.sample(rng), || {
+                            while start.elapsed() < until && !stopped() {
+                                std::thread::sleep(config.aux_thread_period);
+                            }
+                        });
+                    }
+                });
+            });
+        }
+
+        for t in 0..config.threads {
+            let ops_sender = ops_sender.clone();
+            s.spawn(move |_| {
+                pin_slot(t);
+                let mut ops: u64 = 0;
+                let mut latency = config.latency.then(<[Histogram; 3]>::default);
+                let mut recorded = record.map(|path| TraceWriter::create(path, t));
+                let mut local_tally = tally.map(|_| Tally::default());
+                let mut trace = config.trace.as_ref().map(|trace| trace.threads[t].iter());
+                let mut rng = &mut thread_rng(config, phase, t);
+                let key_sampler = &mut config.key_dist.sampler(t, config.threads);
+
+                // Runs the operations until `until` after `start`, and returns whether the worker
+                // is done, i.e., the run is over or the trace is replayed.
+                let mut work = |local: &mut M::Local<'_>, start: Instant, until: Duration| loop {
+                    let now = start.elapsed();
+                    if now >= duration {
+                        return true;
+                    }
+                    if now >= until {
+                        return false;
+                    }
+                    let (op, key) = match &mut trace {
+                        Some(trace) => {
+                            let Some(entry) = trace.next() else {
+                                return true;
+                            };
+                            if let Some(time) = entry.time {
+                                if time >= duration {
+                                    return true;
+                                }
+                                while start.elapsed() < time {
+                                    spin_loop();
+                                }
+                            }
+                            (entry.op, entry.key)
+                        }
+                        None => {
+                            let key = key_sampler.sample(rng);
+                            (Op::OPS[config.op_dist.sample(&mut rng)], key)
+                        }
+                    };
+                    if let Some(recorded) = &mut recorded {
+                        recorded.write(TraceOp {
+                            op,
+                            key,
+                            time: config.record_times.then_some(now),
+                        });
+                    }
+                    let op_start = latency.as_ref().map(|_| Instant::now());
+                    match op {
+                        Op::Get => {
+                            map.get(local, &key);
+                        }
+                        Op::Insert => {
+                            let value = key;
+                            if map.insert(local, key, value) {
+                                if let Some(local_tally) = &mut local_tally {
+                                    local_tally.insert(key);
+                                }
+                            }
+                        }
+                        Op::Remove => {
+                            if map.remove(local, &key) {
+                                if let Some(local_tally) = &mut local_tally {
+                                    local_tally.remove(key);
+                                }
+                            }
+                        }
+                    }
+                    ops += 1;
+                    map.end_op(local, ops);
+                    if let Some(progress) = progress.get(t) {
+                        progress.store(ops, Ordering::Relaxed);
+                    }
+                    if let (Some(latency), Some(op_start)) = (&mut latency, op_start) {
+                        latency[op as usize].record(op_start.elapsed().as_nanos() as u64);
+                    }
+                };
+
+                let (start, mut done) = map.with_local(|local| {
+                    barrier.clone().wait();
+                    let start = Instant::now();
+                    (start, work(local, start, config.churn.unwrap_or(duration)))
+                });
+                // With `--churn`, the thread leaves and a new thread takes over its operations
+                // every churn period.
+                let mut replaced = 0;
+                while !done {
+                    let until = start.elapsed() + config.churn.unwrap();
+                    done = scope(|s| {
+                        s.spawn(|_| {
+                            pin_slot(t);
+                            map.with_local(|local| work(local, start, until))
+                        })
+                        .join()
+                        .unwrap()
+                    })
+                    .unwrap();
+                    replaced += 1;
+                }
+
+                let elapsed = min(start.elapsed(), duration);
+                finished.fetch_add(1, Ordering::Release);
+                if let (Some(tally), Some(local_tally)) = (tally, local_tally) {
+                    tally.lock().unwrap().merge(local_tally);
+                }
+                if let Some(recorded) = recorded {
+                    recorded.finish();
+                }
+                ops_sender.send((ops, elapsed, replaced, latency)).unwrap();
+            });
+        }
+    })
+    .unwrap();
+    let reclamation = map.stats().zip(stats).map(|(after, before)| after - before);
+
+    let mut ops = 0;
+    let mut elapsed = Duration::ZERO;
+    let mut replaced = 0;
+    let mut latency = config.latency.then(<[Histogram; 3]>::default);
+    for _ in 0..config.threads {
+        let (local_ops, local_elapsed, local_replaced, local_latency) =
+            ops_receiver.recv().unwrap();
+        ops += local_ops;
+        elapsed = max(elapsed, local_elapsed);
+        replaced += local_replaced;
+        if let (Some(latency), Some(local_latency)) = (&mut latency, local_latency) {
+            for (hist, local_hist) in latency.iter_mut().zip(&local_latency) {
+                hist.merge(local_hist);
+            }
+        }
+    }
+    let ops_per_sec = (ops as f64 / elapsed.as_secs_f64()) as u64;
+    let (peak_mem, avg_mem, peak_garb, avg_garb) = mem_receiver.recv().unwrap();
+    Perf {
+        ops_per_sec,
+        peak_mem,
+        avg_mem,
+        peak_garb,
+        avg_garb,
+        replaced,
+        drained_garb: config.churn.map(|churn| drain(map, config, phase, churn)),
+        reclamation,
+        pool: map.pool(),
+        latency,
+    }
+}
+
+/// Removes and reinserts random keys on a new thread for `duration` after the workers exited, and
+/// returns the number of garbages left. If the exited workers handed their garbages over to the
+/// scheme, the new thread reclaims them, so that only about a bag of garbages is left.
+fn drain<M: MapAdapter>(map: &M, config: &Config, phase: u64, duration: Duration) -> usize {
+    scope(|s| {
+        s.spawn(|_| {
+            map.with_local(|local| {
+                let rng = &mut thread_rng(config, phase, config.threads + 1);
+                let key_sampler = &mut config.key_dist.sampler(0, 1);
+                let start = Instant::now();
+                let mut ops = 0;
+                while start.elapsed() < duration {
+                    let key = key_sampler.sample(rng);
+                    // Reinserts the removed key, so that the map keeps its keys for `--validate`.
+                    if map.remove(local, &key) {
+                        ops += 1;
+                        map.end_op(local, ops);
+                        map.insert(local, key, key);
+                    }
+                    ops += 1;
+                    map.end_op(local, ops);
+                }
+            })
+        });
+    })
+    .unwrap();
+    map.garbage_count()
+}
+
+/// The output of `--timeseries`, which has a row per sampling tick of each trial.
+struct Timeseries {
+    output: csv::Writer<File>,
+    trial: usize,
+}
+
+impl Timeseries {
+    fn create(name: &str) -> Self {
+        let path = Path::new(name);
+        create_dir_all(path.parent().unwrap()).unwrap();
+        let mut output = csv::Writer::from_writer(File::create(path).unwrap());
+        output
+            .write_record(["trial", "elapsed_ms", "ops", "allocated", "garbage"])
+            .unwrap();
+        Self { output, trial: 0 }
+    }
+
+    fn write(&mut self, elapsed: Duration, ops: u64, allocated: usize, garbages: usize) {
+        self.output
+            .write_record(&[
+                self.trial.to_string(),
+                elapsed.as_millis().to_string(),
+                ops.to_string(),
+                allocated.to_string(),
+                garbages.to_string(),
+            ])
+            .unwrap();
+    }
+}
+
+/// The body of the auxiliary thread: samples the memory usage and the number of garbages. If
+/// `timeseries` is given, each sample is also written to it together with the sum of `progress`.
+///
+/// Returns the peak and average of the allocated bytes and the garbage count.
+fn sample<M: MapAdapter>(
+    map: &M,
+    config: &Config,
+    duration: Duration,
+    barrier: &Arc<Barrier>,
+    progress: &[CachePadded<AtomicU64>],
+    finished: &AtomicUsize,
+    mut timeseries: Option<&mut Timeseries>,
+) -> (usize, usize, usize, usize) {
+    let mut samples = 0usize;
+    let mut acc = 0usize;
+    let mut peak = 0usize;
+    let mut garb_acc = 0usize;
+    let mut garb_peak = 0usize;
+    barrier.clone().wait();
+
+    let start = Instant::now();
+    let mut tick = |now: Instant| {
+        let allocated = config.mem_sampler.sample();
+        acc += allocated;
+        peak = max(peak, allocated);
+
+        let garbages = map.garbage_count();
+        garb_acc += garbages;
+        garb_peak = max(garb_peak, garbages);
+
+        if let Some(timeseries) = &mut timeseries {
+            let ops = progress
+                .iter()
+                .map(|p| p.load(Ordering::Relaxed))
+                .sum::<u64>();
+            timeseries.write(now - start, ops, allocated, garbages);
+        }
+    };
+    let mut next_sampling = start + config.sampling_period;
+    while start.elapsed() < duration && finished.load(Ordering::Acquire) < config.threads {
+        let now = Instant::now();
+        if now > next_sampling {
+            tick(now);
+            samples += 1;
+            next_sampling = now + config.sampling_period;
+        }
+        std::thread::sleep(config.aux_thread_period);
+    }
+    // The workers may finish before the first tick (e.g., on a short `--trace`), so a sample is
+    // taken after them in that case.
+    if samples == 0 {
+        tick(Instant::now());
+        samples += 1;
+    }
+    if let Some(timeseries) = timeseries {
+        timeseries.output.flush().unwrap();
+    }
+
+    if config.sampling {
+        (peak, acc / samples, garb_peak, garb_acc / samples)
+    } else {
+        (0, 0, 0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cdrc::Cdrc;
+    use super::circ_hp::CircHp;
+    use super::hp::Hp;
+    use super::hp_brcu::HpBrcu;
+    use super::nr::Nr;
+    use super::pebr::Pebr;
+    use super::{scheme, MapAdapter, SCHEMES};
+    use crate::config::map::{setup_from, Config, Op};
+    use crate::ds_impl;
+    use clap::ValueEnum;
+    use crossbeam_utils::thread::scope;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Barrier;
+
+    /// Checks the linearizability of every data structure of the schemes, in order, as some
+    /// schemes share global settings configured by `MapAdapter::new`.
+    fn check(names: &[&str]) {
+        for name in names {
+            let scheme = scheme(name).unwrap();
+            for ds in scheme.data_structures() {
+                let ds = ds.to_possible_value().unwrap();
+                let args = [
+                    "smr-bench",
+                    "-m",
+                    name,
+                    "-d",
+                    ds.get_name(),
+                    "-t",
+                    "4",
+                    "-r",
+                    "4",
+                    "--mix",
+                    "get=20,insert=40,remove=40",
+                ];
+                let (config, _) = setup_from(SCHEMES, args);
+                let driver = (scheme.driver)(&config.ds).unwrap();
+                if let Err(e) = (driver.check)(&config, 1000) {
+                    panic!("{name} {}: {e}", ds.get_name());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn nr() {
+        check(&["nr"]);
+    }
+
+    #[test]
+    fn ebr() {
+        check(&["ebr"]);
+    }
+
+    #[test]
+    fn pebr() {
+        check(&["pebr"]);
+    }
+
+    #[test]
+    fn hp() {
+        check(&["hp"]);
+    }
+
+    #[test]
+    fn hp_pp() {
+        check(&["hp-pp"]);
+    }
+
+    #[test]
+    fn nbr() {
+        check(&["nbr"]);
+    }
+
+    #[test]
+    fn hp_brcu() {
+        check(&["hp-brcu", "hp-rcu"]);
+    }
+
+    #[test]
+    fn vbr() {
+        check(&["vbr"]);
+    }
+
+    #[test]
+    fn cdrc() {
+        check(&["cdrc-ebr", "cdrc-ebr-flush", "cdrc-hp"]);
+    }
+
+    #[test]
+    fn circ() {
+        check(&["circ-ebr", "circ-hp"]);
+    }
+
+    /// Parses the arguments of `smr-bench` running `ds` under `scheme` with `threads` workers.
+    fn config(scheme: &str, ds: &str, threads: usize) -> Config {
+        let threads = threads.to_string();
+        let args = ["smr-bench", "-m", scheme, "-d", ds, "-t", &threads];
+        setup_from(SCHEMES, args).0
+    }
+
+    /// Runs operations on a key in a single thread, each of which sees the previous ones.
+    fn sequential<M: MapAdapter>(config: &Config) {
+        let mut map = M::new(config);
+        map.prepare(1);
+        let map = &map;
+        let steps = [
+            (Op::Get, false),
+            (Op::Remove, false),
+            (Op::Insert, true),
+            (Op::Insert, false),
+            (Op::Get, true),
+            (Op::Remove, true),
+            (Op::Get, false),
+            (Op::Remove, false),
+        ];
+        map.with_local(|local| {
+            for (ops, (op, expected)) in (1..).zip(steps) {
+                let result = match op {
+                    Op::Get => map.get(local, &0),
+                    Op::Insert => map.insert(local, 0, 0),
+                    Op::Remove => map.remove(local, &0),
+                };
+                assert_eq!(result, expected, "{op} (#{ops})");
+                map.end_op(local, ops);
+            }
+        });
+    }
+
+    /// Makes all threads remove the keys `0..keys` at once in each round, after the first thread
+    /// inserted them. Exactly one insertion and one removal of each key must succeed in a round.
+    fn contended_removes<M: MapAdapter>(config: &Config, keys: usize) {
+        const ROUNDS: usize = 1000;
+        let threads = config.threads;
+        let mut map = M::new(config);
+        map.prepare(threads);
+        let map = &map;
+        let barrier = &Barrier::new(threads);
+        let counter = || (0..ROUNDS * keys).map(|_| AtomicUsize::new(0)).collect();
+        let (inserted, removed): (&Vec<_>, &Vec<_>) = (&counter(), &counter());
+        scope(|s| {
+            for t in 0..threads {
+                s.spawn(move |_| {
+                    map.with_local(|local| {
+                        let mut ops = 0;
+                        for round in 0..ROUNDS {
+                            if t == 0 {
+                                for key in 0..keys {
+                                    if map.insert(local, key, key) {
+                                        inserted[round * keys + key]
+                                            .fetch_add(1, Ordering::Relaxed);
+                                    }
+                                    ops += 1;
+                                    map.end_op(local, ops);
+                                }
+                            }
+                            barrier.wait();
+                            let key = t % keys;
+                            if map.remove(local, &key) {
+                                removed[round * keys + key].fetch_add(1, Ordering::Relaxed);
+                            }
+                            ops += 1;
+                            map.end_op(local, ops);
+                            barrier.wait();
+                        }
+                    });
+                });
+            }
+        })
+        .unwrap();
+        for (i, (inserted, removed)) in inserted.iter().zip(removed).enumerate() {
+            let counts = (
+                inserted.load(Ordering::Relaxed),
+                removed.load(Ordering::Relaxed),
+            );
+            assert_eq!(counts, (1, 1), "key {} in round {}", i % keys, i / keys);
+        }
+    }
+
+    #[test]
+    fn hp_brcu_list_remove_absent() {
+        use ds_impl::hp_brcu::{HHSList, HList, HMList};
+        let config = &config("hp-brcu", "h-list", 1);
+        sequential::<HpBrcu<true, HList<usize, usize>>>(config);
+        sequential::<HpBrcu<true, HMList<usize, usize>>>(config);
+        sequential::<HpBrcu<true, HHSList<usize, usize>>>(config);
+    }
+
+    #[test]
+    fn circ_hp_skip_list_get_empty() {
+        let config = &config("circ-hp", "skip-list", 1);
+        sequential::<CircHp<ds_impl::circ_hp::SkipList<usize, usize>>>(config);
+    }
+
+    #[test]
+    fn skip_list_contended_removes() {
+        use ds_impl::{cdrc, hp, hp_pp, nr, pebr};
+        contended_removes::<Nr<nr::SkipList<usize, usize>>>(&config("nr", "skip-list", 4), 1);
+        contended_removes::<Pebr<pebr::SkipList<usize, usize>>>(&config("pebr", "skip-list", 4), 1);
+        contended_removes::<Hp<hp::SkipList<usize, usize>>>(&config("hp", "skip-list", 4), 1);
+        contended_removes::<Hp<hp_pp::SkipList<usize, usize>>>(&config("hp-pp", "skip-list", 4), 1);
+        type CdrcSkipList<C> = Cdrc<cdrc::SkipList<usize, usize, C>, C, false>;
+        let cdrc_ebr = &config("cdrc-ebr", "skip-list", 4);
+        contended_removes::<CdrcSkipList<::cdrc::CsEBR>>(cdrc_ebr, 1);
+        let cdrc_hp = &config("cdrc-hp", "skip-list", 4);
+        contended_removes::<CdrcSkipList<::cdrc::CsHP>>(cdrc_hp, 1);
+    }
+
+    #[test]
+    fn hp_pp_list_contended_removes() {
+        use ds_impl::hp_pp::{HHSList, HList, HMList};
+        let config = &config("hp-pp", "h-list", 4);
+        contended_removes::<Hp<HList<usize, usize>>>(config, 1);
+        contended_removes::<Hp<HMList<usize, usize>>>(config, 1);
+        contended_removes::<Hp<HHSList<usize, usize>>>(config, 1);
+    }
+
+    #[test]
+    fn hp_pp_efrb_tree_contended_removes() {
+        let config = &config("hp-pp", "efrb-tree", 4);
+        contended_removes::<Hp<ds_impl::hp_pp::EFRBTree<usize, usize>>>(config, 2);
+    }
+
+    #[test]
+    fn all_schemes_checked() {
+        let checked = [
+            "nr",
+            "ebr",
+            "pebr",
+            "hp",
+            "hp-pp",
+            "nbr",
+            "hp-brcu",
+            "hp-rcu",
+            "vbr",
+            "cdrc-ebr",
+            "cdrc-ebr-flush",
+            "cdrc-hp",
+            "circ-ebr",
+            "circ-hp",
+        ];
+        assert!(SCHEMES.iter().all(|s| checked.contains(&s.name)));
+    }
+}
@@ -0,0 +1,139 @@
+use nbr::{read_phase, Collector, Guard};
+use smr_stats::Stats;
+use std::sync::atomic::Ordering;
+
+use super::{Driver, MapAdapter};
+use crate::config::map::{BagSize, Config, DS};
+use crate::ds_impl::nbr::{
+    BonsaiTreeMap, ConcurrentMap, EFRBTree, HHSList, HList, HMList, HashMap, NMTreeMap, SkipList,
+};
+
+pub fn driver(ds: &DS) -> Option<Driver> {
+    Some(match ds {
+        DS::HList => Driver::of::<Nbr<HList<usize, usize>, 2>>(),
+        DS::HMList => Driver::of::<Nbr<HMList<usize, usize>, 2>>(),
+        DS::HHSList => Driver::of::<Nbr<HHSList<usize, usize>, 2>>(),
+        DS::HashMap => Driver::of::<Nbr<HashMap<usize, usize>, 2>>(),
+        DS::NMTree => Driver::of::<Nbr<NMTreeMap<usize, usize>, 4>>(),
+        DS::EFRBTree => Driver::of::<Nbr<EFRBTree<usize, usize>, 8>>(),
+        DS::BonsaiTree => Driver::of::<Nbr<BonsaiTreeMap<usize, usize>, 3>>(),
+        DS::SkipList => Driver::of::<Nbr<SkipList<usize, usize>, 65>>(),
+    })
+}
+
+/// The capacity of the retirement bag and the low watermark of NBR. The capacity must be a power
+/// of two, and the low watermark, which defaults to an eighth of it, must be positive and below it.
+pub fn extract_nbr_params(
+    bag_size: BagSize,
+    lowatermark: Option<usize>,
+) -> Result<(usize, usize), String> {
+    let bag_cap_pow2 = bag_size.size(256, 8192);
+    if !bag_cap_pow2.is_power_of_two() {
+        return Err(format!(
+            "the bag size of NBR must be a power of two, found `{bag_cap_pow2}`"
+        ));
+    }
+    let lowatermark = lowatermark.unwrap_or((bag_cap_pow2 / 8).max(1));
+    if lowatermark == 0 || lowatermark >= bag_cap_pow2 {
+        return Err(format!(
+            "the low watermark of NBR must be positive and below the bag size \
+             {bag_cap_pow2}, found `{lowatermark}`"
+        ));
+    }
+    Ok((bag_cap_pow2, lowatermark))
+}
+
+/// A NBR collector waits for exactly as many threads as it is created for, so a new collector
+/// is created for every group of threads (see [`MapAdapter::prepare`]).
+pub struct Nbr<M, const MAX_HAZPTRS: usize> {
+    map: M,
+    bag_cap_pow2: usize,
+    lowatermark: usize,
+    collector: Option<Collector>,
+}
+
+pub struct NbrLocal<H> {
+    guard: Guard,
+    handle: H,
+}
+
+impl<M: ConcurrentMap<usize, usize> + Sync, const MAX_HAZPTRS: usize> MapAdapter
+    for Nbr<M, MAX_HAZPTRS>
+{
+    type Local<'l> = NbrLocal<M::Handle>;
+
+    // The collector has a fixed number of slots, which are not released when a thread exits.
+    const CHURN: bool = false;
+
+    const VALIDATE_STRUCTURE: bool = true;
+
+    fn check_config(config: &Config) -> Result<(), String> {
+        extract_nbr_params(config.bag_size, config.nbr_lowatermark).map(|_| ())
+    }
+
+    fn new(config: &Config) -> Self {
+        let (bag_cap_pow2, lowatermark) =
+            extract_nbr_params(config.bag_size, config.nbr_lowatermark).unwrap();
+        Self {
+            map: M::new(),
+            bag_cap_pow2,
+            lowatermark,
+            collector: None,
+        }
+    }
+
+    fn prepare(&mut self, threads: usize) {
+        self.collector = Some(Collector::new(
+            threads,
+            self.bag_cap_pow2,
+            self.lowatermark,
+            MAX_HAZPTRS,
+        ));
+    }
+
+    fn with_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        let collector = self
+            .collector
+            .as_ref()
+            .expect("NBR collector is not prepared");
+        let mut guard = collector.register();
+        let handle = M::handle(&mut guard);
+        f(&mut NbrLocal { guard, handle })
+    }
+
+    fn get(&self, local: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map.get(key, &mut local.handle, &local.guard).is_some()
+    }
+
+    fn insert(&self, local: &mut Self::Local<'_>, key: usize, value: usize) -> bool {
+        self.map.insert(key, value, &mut local.handle, &local.guard)
+    }
+
+    fn remove(&self, local: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map
+            .remove(key, &mut local.handle, &local.guard)
+            .is_some()
+    }
+
+    /// Stalls in a read phase, which is restarted whenever the thread is neutralized.
+    fn stall(&self, local: &mut Self::Local<'_>, _key: usize, mut wait: impl FnMut()) {
+        let guard = &local.guard;
+        read_phase!(guard => {
+            wait();
+        });
+    }
+
+    fn garbage_count(&self) -> usize {
+        nbr::count_garbages()
+    }
+
+    fn stats(&self) -> Option<Stats> {
+        Some(nbr::stats())
+    }
+
+    fn validate(&self) -> Result<Vec<usize>, String> {
+        self.map
+            .validate()
+            .map(|keys| keys.into_iter().copied().collect())
+    }
+}
@@ -0,0 +1,58 @@
+use super::{Driver, MapAdapter};
+use crate::config::map::{Config, DS};
+use crate::ds_impl::nr::{
+    BonsaiTreeMap, ConcurrentMap, EFRBTree, HHSList, HList, HMList, HashMap, NMTreeMap, SkipList,
+};
+
+pub fn driver(ds: &DS) -> Option<Driver> {
+    Some(match ds {
+        DS::HList => Driver::of::<Nr<HList<usize, usize>>>(),
+        DS::HMList => Driver::of::<Nr<HMList<usize, usize>>>(),
+        DS::HHSList => Driver::of::<Nr<HHSList<usize, usize>>>(),
+        DS::HashMap => Driver::of::<Nr<HashMap<usize, usize>>>(),
+        DS::NMTree => Driver::of::<Nr<NMTreeMap<usize, usize>>>(),
+        DS::SkipList => Driver::of::<Nr<SkipList<usize, usize>>>(),
+        DS::BonsaiTree => Driver::of::<Nr<BonsaiTreeMap<usize, usize>>>(),
+        DS::EFRBTree => Driver::of::<Nr<EFRBTree<usize, usize>>>(),
+    })
+}
+
+pub struct Nr<M> {
+    map: M,
+}
+
+impl<M: ConcurrentMap<usize, usize> + Sync> MapAdapter for Nr<M> {
+    type Local<'l> = ();
+
+    const NON_COOP: bool = false;
+
+    const BAG_SIZE: bool = false;
+
+    const VALIDATE_STRUCTURE: bool = true;
+
+    fn new(_config: &Config) -> Self {
+        Self { map: M::new() }
+    }
+
+    fn with_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        f(&mut ())
+    }
+
+    fn get(&self, _local: &mut (), key: &usize) -> bool {
+        self.map.get(key).is_some()
+    }
+
+    fn insert(&self, _local: &mut (), key: usize, value: usize) -> bool {
+        self.map.insert(key, value)
+    }
+
+    fn remove(&self, _local: &mut (), key: &usize) -> bool {
+        self.map.remove(key).is_some()
+    }
+
+    fn validate(&self) -> Result<Vec<usize>, String> {
+        self.map
+            .validate()
+            .map(|keys| keys.into_iter().copied().collect())
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::atomic::Ordering;
+
+use super::{Driver, MapAdapter};
+use crate::config::map::{BagSize, Config, DS};
+use crate::ds_impl::pebr::{
+    BonsaiTreeMap, ConcurrentMap, EFRBTree, HHSList, HList, HMList, HashMap, NMTreeMap, SkipList,
+};
+
+pub fn driver(ds: &DS) -> Option<Driver> {
+    Some(match ds {
+        DS::HList => Driver::of::<Pebr<HList<usize, usize>>>(),
+        DS::HMList => Driver::of::<Pebr<HMList<usize, usize>>>(),
+        DS::HHSList => Driver::of::<Pebr<HHSList<usize, usize>>>(),
+        DS::HashMap => Driver::of::<Pebr<HashMap<usize, usize>>>(),
+        DS::NMTree => Driver::of::<Pebr<NMTreeMap<usize, usize>>>(),
+        DS::BonsaiTree => Driver::of::<Pebr<BonsaiTreeMap<usize, usize>>>(),
+        DS::EFRBTree => Driver::of::<Pebr<EFRBTree<usize, usize>>>(),
+        DS::SkipList => Driver::of::<Pebr<SkipList<usize, usize>>>(),
+    })
+}
+
+/// Clears the shields and repins the guard every `-c` operations.
+pub struct Pebr<M> {
+    map: M,
+    collector: crossbeam_pebr::Collector,
+    ops_per_cs: u64,
+}
+
+pub struct PebrLocal<H> {
+    guard: crossbeam_pebr::Guard,
+    handle: H,
+}
+
+impl<M: ConcurrentMap<usize, usize> + Sync> MapAdapter for Pebr<M> {
+    type Local<'l> = PebrLocal<M::Handle>;
+
+    const OPS_PER_CS: bool = true;
+
+    const BAG_SIZE: bool = false;
+
+    const VALIDATE_STRUCTURE: bool = true;
+
+    fn new(config: &Config) -> Self {
+        if config.bag_size != BagSize::Small {
+            println!(
+                "Warning: Bag size {} is currently unavailable for PEBR.",
+                config.bag_size
+            );
+        }
+        Self {
+            map: M::new(),
+            collector: crossbeam_pebr::Collector::new(),
+            ops_per_cs: config.ops_per_cs,
+        }
+    }
+
+    fn with_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        let local = self.collector.register();
+        let guard = local.pin();
+        let handle = M::handle(&guard);
+        f(&mut PebrLocal { guard, handle })
+    }
+
+    fn get(&self, local: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map
+            .get(&mut local.handle, key, &mut local.guard)
+            .is_some()
+    }
+
+    fn insert(&self, local: &mut Self::Local<'_>, key: usize, value: usize) -> bool {
+        self.map
+            .insert(&mut local.handle, key, value, &mut local.guard)
+    }
+
+    fn remove(&self, local: &mut Self::Local<'_>, key: &usize) -> bool {
+        self.map
+            .remove(&mut local.handle, key, &mut local.guard)
+            .is_some()
+    }
+
+    fn end_op(&self, local: &mut Self::Local<'_>, ops: u64) {
+        if ops % self.ops_per_cs == 0 {
+            M::clear(&mut local.handle);
+            local.guard.repin();
+        }
+    }
+
+    fn garbage_count(&self) -> usize {
+        crossbeam_pebr::GLOBAL_GARBAGE_COUNT.load(Ordering::Acquire)
+    }
+
+    fn validate(&self) -> Result<Vec<usize>, String> {
+        self.map
+            .validate()
+            .map(|keys| keys.into_iter().copied().collect())
+    }
+}
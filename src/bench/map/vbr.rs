@@ -0,0 +1,94 @@
+use smr_stats::{Pool, Stats};
+use vbr::Local;
+
+use super::{Driver, MapAdapter};
+use crate::config::map::{BagSize, Config, DS};
+use crate::ds_impl::vbr::{
+    BonsaiTreeMap, ConcurrentMap, EFRBTree, HHSList, HList, HMList, HashMap, NMTreeMap, SkipList,
+};
+
+pub fn driver(ds: &DS) -> Option<Driver> {
+    Some(match ds {
+        DS::HList => Driver::of::<Vbr<HList<usize, usize>>>(),
+        DS::HMList => Driver::of::<Vbr<HMList<usize, usize>>>(),
+        DS::HHSList => Driver::of::<Vbr<HHSList<usize, usize>>>(),
+        DS::HashMap => Driver::of::<Vbr<HashMap<usize, usize>>>(),
+        DS::NMTree => Driver::of::<Vbr<NMTreeMap<usize, usize>>>(),
+        DS::SkipList => Driver::of::<Vbr<SkipList<usize, usize>>>(),
+        DS::EFRBTree => Driver::of::<Vbr<EFRBTree<usize, usize>>>(),
+        DS::BonsaiTree => Driver::of::<Vbr<BonsaiTreeMap<usize, usize>>>(),
+    })
+}
+
+pub struct Vbr<M: ConcurrentMap<usize, usize>> {
+    map: M,
+    // Boxed, as locals keep a pointer to the global.
+    global: Box<M::Global>,
+}
+
+impl<N, M: ConcurrentMap<usize, usize, Local = Local<N>> + Sync> MapAdapter for Vbr<M> {
+    type Local<'l> = Local<N>;
+
+    const BAG_SIZE: bool = false;
+
+    const VALIDATE_STRUCTURE: bool = true;
+
+    fn new(config: &Config) -> Self {
+        if config.bag_size != BagSize::Small {
+            println!(
+                "Warning: Bag size {} is currently unavailable for VBR.",
+                config.bag_size
+            );
+        }
+        let global = Box::new(M::global(config.prefill));
+        let local = &M::local(&global);
+        let map = M::new(local);
+        Self { map, global }
+    }
+
+    fn with_local<R>(&self, f: impl FnOnce(&mut Self::Local<'_>) -> R) -> R {
+        f(&mut M::local(&self.global))
+    }
+
+    fn get(&self, local: &mut M::Local, key: &usize) -> bool {
+        self.map.get(key, local).is_some()
+    }
+
+    fn insert(&self, local: &mut M::Local, key: usize, value: usize) -> bool {
+        self.map.insert(key, value, local)
+    }
+
+    fn remove(&self, local: &mut M::Local, key: &usize) -> bool {
+        self.map.remove(key, local).is_some()
+    }
+
+    /// Takes the epoch snapshot of an operation, and validates it after `wait` as the reads of the
+    /// operation do, restarting if the epoch advanced meanwhile.
+    fn stall(&self, local: &mut M::Local, _key: usize, mut wait: impl FnMut()) {
+        loop {
+            let guard = local.guard();
+            wait();
+            if guard.validate_epoch().is_ok() {
+                return;
+            }
+        }
+    }
+
+    /// The retired nodes that are not yet returned to the global pool. The nodes in the pool are
+    /// reused by the later allocations, so they are not counted.
+    fn garbage_count(&self) -> usize {
+        M::stats(&self.global).garbages()
+    }
+
+    fn stats(&self) -> Option<Stats> {
+        Some(M::stats(&self.global))
+    }
+
+    fn pool(&self) -> Option<Pool> {
+        Some(M::pool(&self.global))
+    }
+
+    fn validate(&self) -> Result<Vec<usize>, String> {
+        self.with_local(|local| self.map.validate(local))
+    }
+}
@@ -0,0 +1,10 @@
+//! Benchmark drivers shared by the `smr-bench` binary.
+
+pub mod affinity;
+pub mod compare;
+pub mod histogram;
+pub mod linearizability;
+pub mod map;
+pub mod stats;
+pub mod trace;
+pub mod validate;
@@ -0,0 +1,58 @@
+//! Statistics over the results of repeated trials.
+
+/// Two-sided 95% critical values of Student's t-distribution with 1, 2, ..., 30 degrees of
+/// freedom. The normal approximation (1.96) is used for more trials.
+const T95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+/// The mean, the sample standard deviation and the 95% confidence interval of the mean.
+#[derive(Clone, Copy, Debug)]
+pub struct Summary {
+    pub mean: f64,
+    pub stddev: f64,
+    /// The half width of the 95% confidence interval, i.e., the interval is `mean ± ci95`.
+    pub ci95: f64,
+}
+
+impl Summary {
+    /// Summarizes the samples. The deviation and the interval are 0 for a single sample.
+    pub fn of(samples: &[f64]) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarize");
+        let n = samples.len();
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        if n == 1 {
+            return Self {
+                mean,
+                stddev: 0.0,
+                ci95: 0.0,
+            };
+        }
+        let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        let stddev = var.sqrt();
+        let t = T95.get(n - 2).copied().unwrap_or(1.96);
+        Self {
+            mean,
+            stddev,
+            ci95: t * stddev / (n as f64).sqrt(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Summary;
+
+    #[test]
+    fn summary() {
+        let s = Summary::of(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert_eq!(s.mean, 5.0);
+        assert!((s.stddev - 2.138).abs() < 1e-3, "{}", s.stddev);
+        assert!((s.ci95 - 2.365 * s.stddev / 8f64.sqrt()).abs() < 1e-9);
+
+        let s = Summary::of(&[3.0]);
+        assert_eq!((s.mean, s.stddev, s.ci95), (3.0, 0.0, 0.0));
+    }
+}
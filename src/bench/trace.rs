@@ -0,0 +1,192 @@
+//! Operation traces, which are replayed with `--trace` and recorded with `--record`.
+//!
+//! A trace is a text file with a line per operation:
+//!
+//! ```text
+//! # thread op key [time_ns]
+//! 0 insert 42 1200
+//! 1 get 42 1350
+//! 0 remove 42 2400
+//! ```
+//!
+//! Each thread replays its own operations in the order of the file. The optional time is the
+//! delay (ns) from the start of the measurement, before which the operation is not issued. Empty
+//! lines and lines starting with `#` are ignored.
+
+use std::fs::{create_dir_all, remove_file, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::map::Op;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TraceOp {
+    pub op: Op,
+    pub key: usize,
+    pub time: Option<Duration>,
+}
+
+/// The sequences of operations of each thread.
+#[derive(Default, PartialEq, Debug)]
+pub struct Trace {
+    pub threads: Vec<Vec<TraceOp>>,
+}
+
+impl Trace {
+    /// Reads a trace file.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("cannot open the trace `{path}`: {e}"))?;
+        Self::parse(BufReader::new(file)).map_err(|e| format!("invalid trace `{path}`: {e}"))
+    }
+
+    fn parse(input: impl BufRead) -> Result<Self, String> {
+        let mut trace = Trace::default();
+        for (i, line) in input.lines().enumerate() {
+            let line = line.map_err(|e| e.to_string())?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || format!("line {}: expected `thread op key [time_ns]`", i + 1);
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let (thread, op, key, time) = match fields[..] {
+                [thread, op, key] => (thread, op, key, None),
+                [thread, op, key, time] => (thread, op, key, Some(time)),
+                _ => return Err(invalid()),
+            };
+            let thread = thread.parse::<usize>().map_err(|_| invalid())?;
+            let op = match op {
+                "get" => Op::Get,
+                "insert" => Op::Insert,
+                "remove" => Op::Remove,
+                _ => return Err(invalid()),
+            };
+            let key = key.parse::<usize>().map_err(|_| invalid())?;
+            let time = match time {
+                Some(time) => Some(Duration::from_nanos(
+                    time.parse::<u64>().map_err(|_| invalid())?,
+                )),
+                None => None,
+            };
+            if trace.threads.len() <= thread {
+                trace.threads.resize_with(thread + 1, Vec::new);
+            }
+            trace.threads[thread].push(TraceOp { op, key, time });
+        }
+        if trace.threads.is_empty() {
+            return Err("no operations".to_string());
+        }
+        Ok(trace)
+    }
+
+    /// Joins the parts of the first `threads` threads written by `TraceWriter`s into the trace file
+    /// `path`, thread by thread, and removes the parts.
+    pub fn join_parts(path: &str, threads: usize) {
+        let mut output = BufWriter::new(File::create(path).unwrap());
+        writeln!(output, "# thread op key [time_ns]").unwrap();
+        for thread in 0..threads {
+            let part = part_path(path, thread);
+            io::copy(&mut File::open(&part).unwrap(), &mut output).unwrap();
+            remove_file(part).unwrap();
+        }
+        output.flush().unwrap();
+    }
+}
+
+/// Writes the operations of a thread to its own part of a trace file as they are done, so that a
+/// recording does not keep the trace in memory. `Trace::join_parts` joins the parts.
+pub struct TraceWriter {
+    thread: usize,
+    output: BufWriter<File>,
+}
+
+impl TraceWriter {
+    /// Creates the part of the trace file `path` for the `thread`-th thread.
+    pub fn create(path: &str, thread: usize) -> Self {
+        create_dir_all(Path::new(path).parent().unwrap()).unwrap();
+        let output = BufWriter::new(File::create(part_path(path, thread)).unwrap());
+        Self { thread, output }
+    }
+
+    pub fn write(&mut self, TraceOp { op, key, time }: TraceOp) {
+        write!(self.output, "{} {op} {key}", self.thread).unwrap();
+        if let Some(time) = time {
+            write!(self.output, " {}", time.as_nanos()).unwrap();
+        }
+        writeln!(self.output).unwrap();
+    }
+
+    pub fn finish(mut self) {
+        self.output.flush().unwrap();
+    }
+}
+
+fn part_path(path: &str, thread: usize) -> String {
+    format!("{path}.{thread}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Trace, TraceOp, TraceWriter};
+    use crate::config::map::Op;
+    use std::time::Duration;
+
+    #[test]
+    fn parse() {
+        let input = "# thread op key [time_ns]\n0 insert 42 1200\n\n2 get 7\n0 remove 42 2400\n";
+        let trace = Trace::parse(input.as_bytes()).unwrap();
+        assert_eq!(
+            trace.threads,
+            vec![
+                vec![
+                    TraceOp {
+                        op: Op::Insert,
+                        key: 42,
+                        time: Some(Duration::from_nanos(1200)),
+                    },
+                    TraceOp {
+                        op: Op::Remove,
+                        key: 42,
+                        time: Some(Duration::from_nanos(2400)),
+                    },
+                ],
+                vec![],
+                vec![TraceOp {
+                    op: Op::Get,
+                    key: 7,
+                    time: None,
+                }],
+            ]
+        );
+        assert!(Trace::parse("0 put 1".as_bytes()).is_err());
+        assert!(Trace::parse("# empty".as_bytes()).is_err());
+    }
+    #[test]
+    fn record() {
+        let path = std::env::temp_dir().join(format!("smr-trace-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        let ops = [
+            TraceOp {
+                op: Op::Insert,
+                key: 42,
+                time: None,
+            },
+            TraceOp {
+                op: Op::Remove,
+                key: 7,
+                time: Some(Duration::from_nanos(1200)),
+            },
+        ];
+        // The threads finish in any order.
+        for (thread, op) in ops.iter().enumerate().rev() {
+            let mut writer = TraceWriter::create(path, thread);
+            writer.write(*op);
+            writer.finish();
+        }
+        Trace::join_parts(path, ops.len());
+        let trace = Trace::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(trace.threads, ops.map(|op| vec![op]));
+    }
+}
@@ -0,0 +1,89 @@
+//! Post-run validation of the map (`--validate`).
+
+use std::collections::HashMap;
+
+/// The number of successful inserts minus successful removes of each key.
+///
+/// Each prefilling or worker thread keeps its own tally, and they are merged after the threads
+/// stop. As the map starts empty, each key must end with 0 (absent) or 1 (present).
+#[derive(Default, Debug)]
+pub struct Tally {
+    net: HashMap<usize, i64>,
+}
+
+impl Tally {
+    pub fn insert(&mut self, key: usize) {
+        *self.net.entry(key).or_default() += 1;
+    }
+
+    pub fn remove(&mut self, key: usize) {
+        *self.net.entry(key).or_default() -= 1;
+    }
+
+    pub fn merge(&mut self, other: Tally) {
+        for (key, n) in other.net {
+            *self.net.entry(key).or_default() += n;
+        }
+    }
+
+    /// Checks the map, given by whether it `contains` each key and optionally by all of its keys,
+    /// against the tally. Returns the number of keys in the map.
+    pub fn check(
+        &self,
+        mut contains: impl FnMut(usize) -> bool,
+        keys: Option<Vec<usize>>,
+    ) -> Result<usize, String> {
+        let mut expected = Vec::new();
+        for (&key, &n) in &self.net {
+            let present = match n {
+                0 => false,
+                1 => true,
+                n if n > 1 => return Err(format!("key {key} was inserted {n} times in a row")),
+                n => return Err(format!("key {key} was removed {} times in a row", 1 - n)),
+            };
+            if contains(key) != present {
+                let state = if present { "absent" } else { "present" };
+                return Err(format!("key {key} is {state}, unlike the tally"));
+            }
+            if present {
+                expected.push(key);
+            }
+        }
+        if let Some(mut keys) = keys {
+            keys.sort_unstable();
+            expected.sort_unstable();
+            if keys != expected {
+                return Err(format!(
+                    "the map has {} keys, but {} keys are expected",
+                    keys.len(),
+                    expected.len()
+                ));
+            }
+        }
+        Ok(expected.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tally;
+
+    #[test]
+    fn check() {
+        let mut tally = Tally::default();
+        tally.insert(1);
+        tally.insert(2);
+        tally.remove(2);
+        let mut other = Tally::default();
+        other.insert(2);
+        other.insert(3);
+        other.remove(1);
+        tally.merge(other);
+
+        assert_eq!(tally.check(|k| k != 1, Some(vec![3, 2])), Ok(2));
+        assert!(tally.check(|k| k == 3, None).is_err());
+        assert!(tally.check(|k| k != 1, Some(vec![2, 3, 4])).is_err());
+        tally.insert(3);
+        assert!(tally.check(|k| k != 1, None).is_err());
+    }
+}
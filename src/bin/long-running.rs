@@ -7,37 +7,23 @@ extern crate crossbeam_ebr;
 extern crate crossbeam_pebr;
 extern crate smr_benchmark;
 
+use clap::builder::RangedU64ValueParser;
 use clap::{value_parser, Arg, ArgMatches, Command, ValueEnum};
 use crossbeam_utils::thread::scope;
 use csv::Writer;
 use rand::distributions::Uniform;
 use rand::prelude::*;
+use smr_benchmark::bench::map::nbr::extract_nbr_params;
+use smr_benchmark::config::map::BagSize;
 use smr_benchmark::ds_impl;
 use smr_benchmark::ds_impl::cdrc::OutputHolder;
 use std::cmp::max;
-use std::fmt;
 use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::{stdout, Write};
 use std::path::Path;
 use std::sync::atomic::Ordering;
 use std::sync::{mpsc, Arc, Barrier};
 use std::time::{Duration, Instant};
-use typenum::{Unsigned, U1};
-
-const NBR_CAP: NBRConfig = NBRConfig {
-    bag_cap_pow2: 256,
-    lowatermark: 32,
-};
-
-const NBR_LARGE_CAP: NBRConfig = NBRConfig {
-    bag_cap_pow2: 8192,
-    lowatermark: 1024,
-};
-
-struct NBRConfig {
-    bag_cap_pow2: usize,
-    lowatermark: usize,
-}
 
 #[derive(PartialEq, Debug, ValueEnum, Clone)]
 #[allow(non_camel_case_types)]
@@ -55,20 +41,6 @@ pub enum MM {
     VBR,
 }
 
-pub enum OpsPerCs {
-    One,
-    Four,
-}
-
-impl fmt::Display for OpsPerCs {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            OpsPerCs::One => write!(f, "1"),
-            OpsPerCs::Four => write!(f, "4"),
-        }
-    }
-}
-
 struct Config {
     mm: MM,
     readers: usize,
@@ -83,6 +55,9 @@ struct Config {
     prefill: usize,
     interval: u64,
     duration: Duration,
+    ops_per_cs: u64,
+    bag_size: BagSize,
+    nbr_lowatermark: Option<usize>,
 
     mem_sampler: MemSampler,
 }
@@ -168,6 +143,30 @@ fn main() {
                 )
                 .default_value("1"),
         )
+        .arg(
+            Arg::new("ops per cs")
+                .short('c')
+                .value_parser(value_parser!(u64).range(1..))
+                .help("Operations per each critical section (EBR, PEBR and CDRC-EBR)")
+                .default_value("1"),
+        )
+        .arg(
+            Arg::new("bag size")
+                .short('b')
+                .long("bag-size")
+                .value_parser(BagSize::parse)
+                .help(
+                    "The size of deferred bag of NBR. small (256), large (8192, as nbr-large) \
+                     or the number of retired nodes",
+                )
+                .default_value("small"),
+        )
+        .arg(
+            Arg::new("nbr lowatermark")
+                .long("nbr-lowatermark")
+                .value_parser(RangedU64ValueParser::<usize>::new().range(1..))
+                .help("The low watermark of NBR (default: 1/8 of the bag size)"),
+        )
         .arg(
             Arg::new("output")
                 .short('o')
@@ -176,7 +175,7 @@ fn main() {
         .get_matches();
 
     let (config, mut output) = setup(matches);
-    bench::<U1>(&config, output.as_mut());
+    bench(&config, output.as_mut());
 }
 
 fn setup(m: ArgMatches) -> (Config, Option<Writer<File>>) {
@@ -190,6 +189,22 @@ fn setup(m: ArgMatches) -> (Config, Option<Writer<File>>) {
     let sampling_period = m.get_one::<u64>("sampling period").copied().unwrap();
     let sampling = sampling_period > 0 && cfg!(all(not(feature = "sanitize"), target_os = "linux"));
     let duration = Duration::from_secs(interval);
+    let ops_per_cs = m.get_one::<u64>("ops per cs").copied().unwrap();
+    let bag_size = m.get_one::<BagSize>("bag size").copied().unwrap();
+    let nbr_lowatermark = m.get_one::<usize>("nbr lowatermark").copied();
+    // The bag size and the low watermark only configure NBR.
+    match mm {
+        MM::NBR => {
+            extract_nbr_params(bag_size, nbr_lowatermark).unwrap_or_else(|e| panic!("{e}"));
+        }
+        MM::NBR_LARGE => {
+            extract_nbr_params(BagSize::Large, nbr_lowatermark).unwrap_or_else(|e| panic!("{e}"));
+        }
+        _ => assert!(
+            nbr_lowatermark.is_none(),
+            "The low watermark is only available for NBR!"
+        ),
+    }
 
     assert!(
         readers >= 1,
@@ -226,6 +241,8 @@ fn setup(m: ArgMatches) -> (Config, Option<Writer<File>>) {
                         "peak_garb",
                         "avg_garb",
                         "key_range",
+                        "pooled",
+                        "bags",
                     ])
                     .unwrap();
                 output.flush().unwrap();
@@ -248,31 +265,40 @@ fn setup(m: ArgMatches) -> (Config, Option<Writer<File>>) {
         prefill,
         interval,
         duration,
+        ops_per_cs,
+        bag_size,
+        nbr_lowatermark,
 
         mem_sampler,
     };
     (config, output)
 }
 
-fn bench<N: Unsigned>(config: &Config, output: Option<&mut Writer<File>>) {
+fn bench(config: &Config, output: Option<&mut Writer<File>>) {
     println!(
         "{}: {} writers, {} readers",
         config.mm.to_possible_value().unwrap().get_name(),
         config.writers,
         config.readers
     );
+    // The pool of the reused nodes, which only `vbr` has.
+    let mut pool = None;
     let (ops_per_sec, peak_mem, avg_mem, peak_garb, avg_garb) = match config.mm {
         MM::NR => bench_map_nr(config, PrefillStrategy::Decreasing),
-        MM::EBR => bench_map_ebr::<N>(config, PrefillStrategy::Decreasing),
-        MM::PEBR => bench_map_pebr::<N>(config, PrefillStrategy::Decreasing),
+        MM::EBR => bench_map_ebr(config, PrefillStrategy::Decreasing),
+        MM::PEBR => bench_map_pebr(config, PrefillStrategy::Decreasing),
         MM::HP => bench_map_hp(config, PrefillStrategy::Decreasing),
         MM::HP_PP => bench_map_hp_pp(config, PrefillStrategy::Decreasing),
-        MM::CDRC_EBR => bench_map_cdrc::<cdrc::CsEBR, N>(config, PrefillStrategy::Decreasing),
+        MM::CDRC_EBR => bench_map_cdrc::<cdrc::CsEBR>(config, PrefillStrategy::Decreasing),
         MM::HP_BRCU => bench_map_hp_brcu::<true>(config, PrefillStrategy::Decreasing),
         MM::HP_RCU => bench_map_hp_brcu::<false>(config, PrefillStrategy::Decreasing),
-        MM::NBR => bench_map_nbr(config, PrefillStrategy::Decreasing, &NBR_CAP, 2),
-        MM::NBR_LARGE => bench_map_nbr(config, PrefillStrategy::Decreasing, &NBR_LARGE_CAP, 2),
-        MM::VBR => bench_map_vbr(config, PrefillStrategy::Decreasing),
+        MM::NBR => bench_map_nbr(config, PrefillStrategy::Decreasing, config.bag_size, 2),
+        MM::NBR_LARGE => bench_map_nbr(config, PrefillStrategy::Decreasing, BagSize::Large, 2),
+        MM::VBR => {
+            let (result, vbr_pool) = bench_map_vbr(config, PrefillStrategy::Decreasing);
+            pool = Some(vbr_pool);
+            result
+        }
     };
     if let Some(output) = output {
         output
@@ -290,6 +316,8 @@ fn bench<N: Unsigned>(config: &Config, output: Option<&mut Writer<File>>) {
                 peak_garb.to_string(),
                 avg_garb.to_string(),
                 (config.prefill * 2).to_string(),
+                pool.map(|pool| pool.pooled.to_string()).unwrap_or_default(),
+                pool.map(|pool| pool.bags.to_string()).unwrap_or_default(),
             ])
             .unwrap();
         output.flush().unwrap();
@@ -298,6 +326,9 @@ fn bench<N: Unsigned>(config: &Config, output: Option<&mut Writer<File>>) {
         "ops/s: {}, peak mem: {}, avg_mem: {}, peak garb: {}, avg garb: {}",
         ops_per_sec, peak_mem, avg_mem, peak_garb, avg_garb
     );
+    if let Some(pool) = pool {
+        println!("pooled: {}, bags: {}", pool.pooled, pool.bags);
+    }
 }
 
 #[allow(unused)]
@@ -634,10 +665,7 @@ fn bench_map_nr(config: &Config, strategy: PrefillStrategy) -> (u64, usize, usiz
     (ops_per_sec, peak_mem, avg_mem, garb_peak, garb_avg)
 }
 
-fn bench_map_ebr<N: Unsigned>(
-    config: &Config,
-    strategy: PrefillStrategy,
-) -> (u64, usize, usize, usize, usize) {
+fn bench_map_ebr(config: &Config, strategy: PrefillStrategy) -> (u64, usize, usize, usize, usize) {
     use ds_impl::ebr::ConcurrentMap;
     let map = &ds_impl::ebr::HHSList::new();
     strategy.prefill_ebr(config, map);
@@ -712,7 +740,7 @@ fn bench_map_ebr<N: Unsigned>(
                         acquired = Some((key.clone(), value.clone()));
                     }
                     ops += 1;
-                    if ops % N::to_u64() == 0 {
+                    if ops % config.ops_per_cs == 0 {
                         drop(guard);
                         guard = handle.pin();
                     }
@@ -735,7 +763,7 @@ fn bench_map_ebr<N: Unsigned>(
                     let key = config.key_dist.sample(rng);
                     let _ = map.get(&key, &guard);
                     ops += 1;
-                    if ops % N::to_u64() == 0 {
+                    if ops % config.ops_per_cs == 0 {
                         drop(guard);
                         guard = handle.pin();
                     }
@@ -758,10 +786,7 @@ fn bench_map_ebr<N: Unsigned>(
     (ops_per_sec, peak_mem, avg_mem, garb_peak, garb_avg)
 }
 
-fn bench_map_pebr<N: Unsigned>(
-    config: &Config,
-    strategy: PrefillStrategy,
-) -> (u64, usize, usize, usize, usize) {
+fn bench_map_pebr(config: &Config, strategy: PrefillStrategy) -> (u64, usize, usize, usize, usize) {
     use ds_impl::pebr::ConcurrentMap;
     let map = &ds_impl::pebr::HHSList::new();
     strategy.prefill_pebr(config, map);
@@ -837,7 +862,7 @@ fn bench_map_pebr<N: Unsigned>(
                         acquired = Some((key.clone(), value.clone()));
                     }
                     ops += 1;
-                    if ops % N::to_u64() == 0 {
+                    if ops % config.ops_per_cs == 0 {
                         ds_impl::pebr::HHSList::clear(&mut map_handle);
                         guard.repin();
                     }
@@ -861,7 +886,7 @@ fn bench_map_pebr<N: Unsigned>(
                     let key = config.key_dist.sample(rng);
                     let _ = map.get(&mut map_handle, &key, &mut guard);
                     ops += 1;
-                    if ops % N::to_u64() == 0 {
+                    if ops % config.ops_per_cs == 0 {
                         ds_impl::pebr::HHSList::clear(&mut map_handle);
                         guard.repin();
                     }
@@ -1101,7 +1126,7 @@ fn bench_map_hp_pp(
     (ops_per_sec, peak_mem, avg_mem, garb_peak, garb_avg)
 }
 
-fn bench_map_cdrc<C: cdrc::Cs, N: Unsigned>(
+fn bench_map_cdrc<C: cdrc::Cs>(
     config: &Config,
     strategy: PrefillStrategy,
 ) -> (u64, usize, usize, usize, usize) {
@@ -1176,7 +1201,7 @@ fn bench_map_cdrc<C: cdrc::Cs, N: Unsigned>(
                         acquired = Some(output.output().clone());
                     }
                     ops += 1;
-                    if ops % N::to_u64() == 0 {
+                    if ops % config.ops_per_cs == 0 {
                         cs.clear();
                     }
                 }
@@ -1198,7 +1223,7 @@ fn bench_map_cdrc<C: cdrc::Cs, N: Unsigned>(
                     let key = config.key_dist.sample(rng);
                     let _ = map.get(&key, output, &cs);
                     ops += 1;
-                    if ops % N::to_u64() == 0 {
+                    if ops % config.ops_per_cs == 0 {
                         cs.clear();
                     }
                 }
@@ -1348,17 +1373,18 @@ fn bench_map_hp_brcu<const ROLLBACK: bool>(
 fn bench_map_nbr(
     config: &Config,
     strategy: PrefillStrategy,
-    nbr_config: &NBRConfig,
+    bag_size: BagSize,
     max_hazptrs: usize,
 ) -> (u64, usize, usize, usize, usize) {
     use ds_impl::nbr::ConcurrentMap;
     let map = &ds_impl::nbr::HHSList::new();
     strategy.prefill_nbr(config, map, max_hazptrs);
 
+    let (bag_cap_pow2, lowatermark) = extract_nbr_params(bag_size, config.nbr_lowatermark).unwrap();
     let collector = &nbr::Collector::new(
         config.writers + config.readers,
-        nbr_config.bag_cap_pow2,
-        nbr_config.lowatermark,
+        bag_cap_pow2,
+        lowatermark,
         max_hazptrs,
     );
 
@@ -1466,7 +1492,11 @@ fn bench_map_nbr(
     (ops_per_sec, peak_mem, avg_mem, garb_peak, garb_avg)
 }
 
-fn bench_map_vbr(config: &Config, strategy: PrefillStrategy) -> (u64, usize, usize, usize, usize) {
+/// Returns the pool of the reused nodes at the end of the run along with the results.
+fn bench_map_vbr(
+    config: &Config,
+    strategy: PrefillStrategy,
+) -> ((u64, usize, usize, usize, usize), smr_stats::Pool) {
     use ds_impl::vbr::ConcurrentMap;
     let global = &ds_impl::vbr::HHSList::global(config.prefill);
     let local = &ds_impl::vbr::HHSList::local(global);
@@ -1502,7 +1532,7 @@ fn bench_map_vbr(config: &Config, strategy: PrefillStrategy) -> (u64, usize, usi
                         acc += allocated;
                         peak = max(peak, allocated);
 
-                        let garbages = nbr::count_garbages();
+                        let garbages = ds_impl::vbr::HHSList::stats(global).garbages();
                         garb_acc += garbages;
                         garb_peak = max(garb_peak, garbages);
 
@@ -1572,5 +1602,6 @@ fn bench_map_vbr(config: &Config, strategy: PrefillStrategy) -> (u64, usize, usi
     }
     let ops_per_sec = ops / config.interval;
     let (peak_mem, avg_mem, garb_peak, garb_avg) = mem_receiver.recv().unwrap();
-    (ops_per_sec, peak_mem, avg_mem, garb_peak, garb_avg)
+    let pool = ds_impl::vbr::HHSList::pool(global);
+    ((ops_per_sec, peak_mem, avg_mem, garb_peak, garb_avg), pool)
 }
@@ -0,0 +1,18 @@
+use smr_benchmark::bench::map::{scheme, SCHEMES};
+use smr_benchmark::config::map::{setup, PerfSummary};
+
+fn main() {
+    let (config, output) = setup(SCHEMES);
+    let scheme = scheme(&config.mm).unwrap();
+    println!("{}", config);
+    let perfs = scheme.bench(&config);
+    output.write_record(&config, &perfs);
+    if let [perf] = perfs.as_slice() {
+        println!("{}", perf);
+    } else {
+        for (trial, perf) in perfs.iter().enumerate() {
+            println!("trial {}: {}", trial, perf);
+        }
+        println!("{}", PerfSummary::of(&perfs));
+    }
+}
@@ -0,0 +1,130 @@
+//! Compares the results of `smr-bench` against a baseline (see `smr_benchmark::bench::compare`),
+//! and exits with 1 if any configuration regressed beyond the thresholds.
+
+use std::fs;
+
+use clap::{error::ErrorKind, value_parser, Arg, Command};
+use smr_benchmark::bench::compare::{compare, Delta, Results, Thresholds};
+use smr_benchmark::config::map::readable_bytes;
+
+fn main() {
+    let mut cmd = Command::new("smr-compare")
+        .about("Compares the results of the benchmark (CSV or JSON lines) against a baseline")
+        .arg(
+            Arg::new("baseline")
+                .required(true)
+                .help("The result file of the baseline"),
+        )
+        .arg(
+            Arg::new("new")
+                .required(true)
+                .help("The result file to compare"),
+        )
+        .arg(
+            Arg::new("threshold")
+                .long("threshold")
+                .value_parser(value_parser!(f64))
+                .default_value("5")
+                .help("The decrease of the throughput (%) regarded as a regression"),
+        )
+        .arg(
+            Arg::new("memory threshold")
+                .long("memory-threshold")
+                .value_parser(value_parser!(f64))
+                .default_value("10")
+                .help(
+                    "The increase of the peak or average memory usage (%) regarded as a regression",
+                ),
+        );
+    let m = cmd.get_matches_mut();
+    let mut load = |name: &str| {
+        let path = m.get_one::<String>(name).unwrap();
+        fs::read_to_string(path)
+            .map_err(|e| format!("cannot read `{path}`: {e}"))
+            .and_then(|input| Results::parse(&input).map_err(|e| format!("invalid `{path}`: {e}")))
+            .unwrap_or_else(|e| cmd.error(ErrorKind::InvalidValue, e).exit())
+    };
+    let base = load("baseline");
+    let new = load("new");
+    let thresholds = Thresholds {
+        throughput: m.get_one::<f64>("threshold").unwrap() / 100.0,
+        memory: m.get_one::<f64>("memory threshold").unwrap() / 100.0,
+    };
+
+    let report = compare(&base, &new);
+    if !report.ignored.is_empty() {
+        println!(
+            "columns not in both files, ignored: {}",
+            report.ignored.join(", ")
+        );
+    }
+    // Only the columns that differ between the configurations are printed, unless there is a
+    // single configuration.
+    let mut varying = (0..report.columns.len())
+        .filter(|&i| {
+            report
+                .comparisons
+                .iter()
+                .any(|c| c.key[i] != report.comparisons[0].key[i])
+        })
+        .collect::<Vec<_>>();
+    if varying.is_empty() {
+        varying = (0..report.columns.len()).collect();
+    }
+
+    let mut regressed = 0;
+    for c in &report.comparisons {
+        let key = varying
+            .iter()
+            .map(|&i| format!("{}={}", report.columns[i], c.key[i]))
+            .collect::<Vec<_>>();
+        let regressions = c.regressions(&thresholds);
+        println!(
+            "{}: throughput {}, peak_mem {}, avg_mem {}{}",
+            key.join(" "),
+            delta(&c.throughput, |v| format!("{v:.0}")),
+            delta(&c.peak_mem, |v| readable_bytes(v as usize)),
+            delta(&c.avg_mem, |v| readable_bytes(v as usize)),
+            if regressions.is_empty() {
+                String::new()
+            } else {
+                format!("  REGRESSION ({})", regressions.join(", "))
+            }
+        );
+        if !regressions.is_empty() {
+            regressed += 1;
+        }
+    }
+    println!(
+        "{} configurations compared, {} regressed ({} only in the baseline, {} only in the new \
+         results)",
+        report.comparisons.len(),
+        regressed,
+        report.unmatched.0,
+        report.unmatched.1
+    );
+    if regressed > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// Formats the change of the means, and marks the changes within the noise of the trials.
+fn delta(delta: &Delta, format: impl Fn(f64) -> String) -> String {
+    let change = match delta.change() {
+        Some(change) => {
+            let noise = delta.new.mean != delta.base.mean && !delta.significant();
+            format!(
+                " ({:+.1}%{})",
+                change * 100.0,
+                if noise { ", noise" } else { "" }
+            )
+        }
+        None => String::new(),
+    };
+    format!(
+        "{} -> {}{}",
+        format(delta.base.mean),
+        format(delta.new.mean),
+        change
+    )
+}
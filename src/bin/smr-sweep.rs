@@ -0,0 +1,82 @@
+//! Runs `smr-bench` for every configuration of an experiment specification (see
+//! `smr_benchmark::config::sweep`).
+//!
+//! The finished runs are listed in `<output>.done`, and the runs in the list are skipped when the
+//! sweep is started again, so an interrupted sweep resumes where it stopped.
+
+use std::collections::HashSet;
+use std::env::current_exe;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::process::Command as Process;
+
+use clap::{error::ErrorKind, Arg, ArgAction, Command};
+use smr_benchmark::config::sweep::Spec;
+
+fn main() {
+    let mut cmd = Command::new("smr-sweep")
+        .about("Runs the benchmark for every configuration of a TOML experiment specification")
+        .arg(
+            Arg::new("spec")
+                .required(true)
+                .help("The experiment specification"),
+        )
+        .arg(
+            Arg::new("dry run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .help("Prints the runs to perform without running them"),
+        );
+    let m = cmd.get_matches_mut();
+    let path = m.get_one::<String>("spec").unwrap();
+    let spec = fs::read_to_string(path)
+        .map_err(|e| format!("cannot read `{path}`: {e}"))
+        .and_then(|input| Spec::parse(&input).map_err(|e| format!("invalid `{path}`: {e}")))
+        .unwrap_or_else(|e| cmd.error(ErrorKind::InvalidValue, e).exit());
+    let dry_run = m.get_flag("dry run");
+
+    let (runs, unsupported) = spec.runs();
+    let done_path = format!("{}.done", spec.output);
+    let done = fs::read_to_string(&done_path).unwrap_or_default();
+    let done = done.lines().collect::<HashSet<_>>();
+    let pending = runs
+        .iter()
+        .filter(|run| !done.contains(run.id(&spec).as_str()))
+        .collect::<Vec<_>>();
+    println!(
+        "{} runs: {} finished, {} to run ({} unsupported combinations skipped)",
+        runs.len(),
+        runs.len() - pending.len(),
+        pending.len(),
+        unsupported
+    );
+
+    let bench = current_exe().unwrap().with_file_name("smr-bench");
+    let mut failed = 0;
+    for (i, run) in pending.iter().enumerate() {
+        let args = run.args(&spec);
+        println!("[{}/{}] smr-bench {}", i + 1, pending.len(), args.join(" "));
+        if dry_run {
+            continue;
+        }
+        let status = Process::new(&bench)
+            .args(&args)
+            .status()
+            .unwrap_or_else(|e| panic!("failed to run {}: {e}", bench.display()));
+        if !status.success() {
+            eprintln!("run failed: {status}");
+            failed += 1;
+            continue;
+        }
+        let mut done = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&done_path)
+            .unwrap();
+        writeln!(done, "{}", run.id(&spec)).unwrap();
+    }
+    if failed > 0 {
+        eprintln!("{failed} runs failed; run the sweep again to retry them");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,50 @@
+//! Metadata of the host and the build, recorded along with the results.
+
+use std::fs;
+use std::thread::available_parallelism;
+
+/// The name of the Cargo profile of this build (e.g., `release`).
+pub const PROFILE: &str = env!("SMR_BENCH_PROFILE");
+
+/// The git commit this binary was built from, or `unknown`.
+pub const COMMIT: &str = env!("SMR_BENCH_COMMIT");
+
+/// The features of this crate and whether each of them is enabled.
+pub const FEATURES: &[(&str, bool)] = &[("sanitize", cfg!(feature = "sanitize"))];
+
+/// The global allocator of the benchmarks. Memory usage is sampled only with jemalloc.
+pub const ALLOCATOR: &str = if cfg!(all(not(feature = "sanitize"), target_os = "linux")) {
+    "jemalloc"
+} else {
+    "system"
+};
+
+pub struct Host {
+    pub cpus: usize,
+    /// The CPU model name in `/proc/cpuinfo`, if any.
+    pub cpu_model: Option<String>,
+    /// The kernel release in `/proc/sys/kernel/osrelease`, if any.
+    pub kernel: Option<String>,
+}
+
+impl Host {
+    pub fn detect() -> Self {
+        let cpus = available_parallelism().map(|v| v.get()).unwrap_or(1);
+        let cpu_model = fs::read_to_string("/proc/cpuinfo")
+            .ok()
+            .and_then(|cpuinfo| {
+                cpuinfo.lines().find_map(|line| {
+                    let (key, value) = line.split_once(':')?;
+                    (key.trim() == "model name").then(|| value.trim().to_string())
+                })
+            });
+        let kernel = fs::read_to_string("/proc/sys/kernel/osrelease")
+            .ok()
+            .map(|release| release.trim().to_string());
+        Self {
+            cpus,
+            cpu_model,
+            kernel,
+        }
+    }
+}
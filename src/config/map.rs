@@ -1,11 +1,25 @@
+use super::host::{self, Host};
+use crate::bench::affinity::Affinity;
+use crate::bench::histogram::Histogram;
+use crate::bench::map::{Driver, Scheme};
+use crate::bench::stats::Summary;
+use crate::bench::trace::Trace;
 use crate::MemSampler;
+use clap::builder::{PossibleValuesParser, RangedU64ValueParser};
+use clap::error::ErrorKind;
 use clap::{value_parser, Arg, ArgAction, Command, ValueEnum};
 use csv::Writer;
-use rand::distributions::{Uniform, WeightedIndex};
+use rand::distributions::{Distribution, Uniform, WeightedIndex};
+use rand::Rng;
+use rand_distr::Zipf;
+use serde_json::json;
+use smr_stats::{Pool, Stats};
+use std::ffi::OsString;
 use std::fmt;
 use std::fs::{create_dir_all, File, OpenOptions};
+use std::io::Write;
 use std::path::Path;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(PartialEq, Debug, ValueEnum, Clone)]
 pub enum DS {
@@ -19,24 +33,73 @@ pub enum DS {
     SkipList,
 }
 
-pub enum OpsPerCs {
-    One,
-    Four,
+impl DS {
+    /// The prefill strategy used unless `--prefill-strategy` is given. The trees are prefilled in
+    /// random order to keep them balanced, and the others in decreasing order, which is the
+    /// fastest for the lists.
+    pub fn default_prefill_strategy(&self) -> PrefillStrategy {
+        match self {
+            DS::NMTree | DS::BonsaiTree | DS::EFRBTree => PrefillStrategy::Random,
+            DS::HList | DS::HMList | DS::HHSList | DS::HashMap | DS::SkipList => {
+                PrefillStrategy::Decreasing
+            }
+        }
+    }
 }
 
-impl fmt::Display for OpsPerCs {
+/// The order of the keys inserted before the benchmark (`--prefill-strategy`).
+#[derive(PartialEq, Eq, Debug, ValueEnum, Clone, Copy)]
+pub enum PrefillStrategy {
+    /// Random keys inserted by all available threads.
+    Random,
+    /// Random keys sorted in decreasing order, inserted by a single thread.
+    Decreasing,
+    /// Random keys sorted in increasing order, inserted by a single thread.
+    Increasing,
+    /// Starts from an empty map.
+    None,
+}
+
+impl fmt::Display for PrefillStrategy {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            OpsPerCs::One => write!(f, "1"),
-            OpsPerCs::Four => write!(f, "4"),
-        }
+        write!(f, "{}", self.to_possible_value().unwrap().get_name())
     }
 }
 
+/// The number of retired nodes a thread collects before it tries to reclaim them (`-b`).
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum BagSize {
+    /// The small size of each scheme (e.g., 64 for EBR and 256 for NBR).
     Small,
+    /// The large size of each scheme (e.g., 4096 for EBR and 8192 for NBR).
     Large,
+    Custom(usize),
+}
+
+impl BagSize {
+    /// Parses `small`, `large` or a positive number.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec {
+            "small" => Ok(BagSize::Small),
+            "large" => Ok(BagSize::Large),
+            _ => match spec.parse::<usize>() {
+                Ok(0) => Err("the bag size must be positive".to_string()),
+                Ok(size) => Ok(BagSize::Custom(size)),
+                Err(e) => Err(format!(
+                    "expected `small`, `large` or a number, found `{spec}`: {e}"
+                )),
+            },
+        }
+    }
+
+    /// The size for a scheme whose small and large sizes are `small` and `large`.
+    pub fn size(self, small: usize, large: usize) -> usize {
+        match self {
+            BagSize::Small => small,
+            BagSize::Large => large,
+            BagSize::Custom(size) => size,
+        }
+    }
 }
 
 impl fmt::Display for BagSize {
@@ -44,11 +107,12 @@ impl fmt::Display for BagSize {
         match self {
             BagSize::Small => write!(f, "small"),
             BagSize::Large => write!(f, "large"),
+            BagSize::Custom(size) => write!(f, "{size}"),
         }
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum Op {
     Get,
     Insert,
@@ -59,6 +123,17 @@ impl Op {
     pub const OPS: [Op; 3] = [Op::Get, Op::Insert, Op::Remove];
 }
 
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Get => write!(f, "get"),
+            Op::Insert => write!(f, "insert"),
+            Op::Remove => write!(f, "remove"),
+        }
+    }
+}
+
+/// The presets of the operation mix (`-g`).
 #[derive(Clone, Copy, PartialEq)]
 pub enum GetRate {
     WriteOnly = 0,
@@ -67,27 +142,240 @@ pub enum GetRate {
     ReadOnly = 3,
 }
 
+impl GetRate {
+    pub fn mix(self) -> OpMix {
+        let (get, insert, remove) = match self {
+            GetRate::WriteOnly => (0, 50, 50),
+            GetRate::ReadWrite => (50, 25, 25),
+            GetRate::ReadIntensive => (90, 5, 5),
+            GetRate::ReadOnly => (100, 0, 0),
+        };
+        OpMix {
+            get,
+            insert,
+            remove,
+        }
+    }
+}
+
+/// The percentages of each operation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OpMix {
+    pub get: u8,
+    pub insert: u8,
+    pub remove: u8,
+}
+
+impl OpMix {
+    /// Parses a mix like `get=70,insert=20,remove=10`. Omitted operations are never performed,
+    /// and the percentages must sum up to 100.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut mix = OpMix {
+            get: 0,
+            insert: 0,
+            remove: 0,
+        };
+        for entry in spec.split(',') {
+            let (op, percent) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected `<op>=<percentage>`, found `{entry}`"))?;
+            let percent = percent
+                .trim()
+                .parse::<u8>()
+                .map_err(|e| format!("invalid percentage `{percent}`: {e}"))?;
+            match op.trim() {
+                "get" => mix.get = percent,
+                "insert" => mix.insert = percent,
+                "remove" => mix.remove = percent,
+                op => return Err(format!("unknown operation `{op}`")),
+            }
+        }
+        let sum = mix.get as u32 + mix.insert as u32 + mix.remove as u32;
+        if sum != 100 {
+            return Err(format!("the percentages sum up to {sum}, not 100"));
+        }
+        Ok(mix)
+    }
+
+    /// The weights of `Op::OPS`.
+    pub fn weights(&self) -> [i32; 3] {
+        [self.get as i32, self.insert as i32, self.remove as i32]
+    }
+}
+
+impl fmt::Display for OpMix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "get={},insert={},remove={}",
+            self.get, self.insert, self.remove
+        )
+    }
+}
+
+/// The distribution of the keys of operations, shared by the prefilling and the worker threads.
+pub enum KeyDist {
+    /// Every key is equally likely.
+    Uniform(Uniform<usize>),
+    /// The `k`-th key is chosen with probability proportional to `1 / (k + 1)^skew`, so that
+    /// the smallest keys are the hottest.
+    Zipf { dist: Zipf<f64>, skew: f64 },
+    /// `hot_ops`% of operations are on the first `hot_keys`% of keys, and the others are on the
+    /// rest of keys.
+    Hotspot {
+        hot: Uniform<usize>,
+        cold: Option<Uniform<usize>>,
+        hot_ops: u8,
+        hot_keys: u8,
+    },
+    /// Each thread chooses its keys in increasing order, wrapping around at the end of the key
+    /// range (see `KeySampler`).
+    Sequential { key_range: usize },
+}
+
+impl KeyDist {
+    /// Parses a key distribution over `[0..key_range]`. The accepted formats are `uniform`,
+    /// `zipf:<skew>`, `hotspot:<hot ops %>,<hot keys %>` and `sequential`.
+    pub fn parse(spec: &str, key_range: usize) -> Result<Self, String> {
+        let (name, params) = match spec.split_once(':') {
+            Some((name, params)) => (name, Some(params)),
+            None => (spec, None),
+        };
+        match (name, params) {
+            ("uniform", None) => Ok(KeyDist::Uniform(Uniform::from(0..key_range))),
+            ("zipf", Some(skew)) => {
+                let skew = skew
+                    .parse::<f64>()
+                    .map_err(|e| format!("invalid skew `{skew}`: {e}"))?;
+                let dist = Zipf::new(key_range as u64, skew)
+                    .map_err(|e| format!("invalid skew `{skew}`: {e}"))?;
+                Ok(KeyDist::Zipf { dist, skew })
+            }
+            ("hotspot", Some(params)) => {
+                let percent = |p: &str| match p.trim().parse::<u8>() {
+                    Ok(p) if p <= 100 => Ok(p),
+                    _ => Err(format!("invalid percentage `{p}`")),
+                };
+                let (hot_ops, hot_keys) = params
+                    .split_once(',')
+                    .ok_or("expected `hotspot:<hot ops %>,<hot keys %>`")?;
+                let (hot_ops, hot_keys) = (percent(hot_ops)?, percent(hot_keys)?);
+                let num_hot = (key_range * hot_keys as usize / 100).clamp(1, key_range);
+                Ok(KeyDist::Hotspot {
+                    hot: Uniform::from(0..num_hot),
+                    cold: (num_hot < key_range).then(|| Uniform::from(num_hot..key_range)),
+                    hot_ops,
+                    hot_keys,
+                })
+            }
+            ("sequential", None) => Ok(KeyDist::Sequential { key_range }),
+            _ => Err(format!("unknown key distribution `{spec}`")),
+        }
+    }
+
+    /// Returns the sampler of the keys of the `t`-th of `threads` threads.
+    pub fn sampler(&self, t: usize, threads: usize) -> KeySampler<'_> {
+        let next = match self {
+            KeyDist::Sequential { key_range } => t % key_range,
+            _ => 0,
+        };
+        KeySampler {
+            dist: self,
+            next,
+            stride: threads,
+        }
+    }
+}
+
+/// The keys of a thread drawn from a `KeyDist`. For `sequential`, the `t`-th of `threads` threads
+/// chooses `t`, `t + threads`, `t + 2 * threads`, ..., so that the threads do not contend on a
+/// shared counter nor choose the same keys until they wrap around.
+pub struct KeySampler<'d> {
+    dist: &'d KeyDist,
+    next: usize,
+    stride: usize,
+}
+
+impl KeySampler<'_> {
+    pub fn sample<R: Rng + ?Sized>(&mut self, rng: &mut R) -> usize {
+        match self.dist {
+            KeyDist::Uniform(dist) => dist.sample(rng),
+            KeyDist::Zipf { dist, .. } => dist.sample(rng) as usize - 1,
+            KeyDist::Hotspot {
+                hot, cold, hot_ops, ..
+            } => match cold {
+                Some(cold) if rng.gen_range(0..100) >= *hot_ops => cold.sample(rng),
+                _ => hot.sample(rng),
+            },
+            KeyDist::Sequential { key_range } => {
+                let key = self.next;
+                self.next = (self.next + self.stride) % key_range;
+                key
+            }
+        }
+    }
+}
+
+impl fmt::Display for KeyDist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyDist::Uniform(_) => write!(f, "uniform"),
+            KeyDist::Zipf { skew, .. } => write!(f, "zipf:{skew}"),
+            KeyDist::Hotspot {
+                hot_ops, hot_keys, ..
+            } => write!(f, "hotspot:{hot_ops},{hot_keys}"),
+            KeyDist::Sequential { .. } => write!(f, "sequential"),
+        }
+    }
+}
+
 pub struct Config {
     pub ds: DS,
     pub mm: String,
     pub threads: usize,
     pub bag_size: BagSize,
+    /// The low watermark of NBR (`--nbr-lowatermark`), which defaults to an eighth of the bag.
+    pub nbr_lowatermark: Option<usize>,
+    pub affinity: Affinity,
 
     pub aux_thread: usize,
     pub aux_thread_period: Duration,
     pub non_coop: u8,
     pub non_coop_period: Duration,
+    /// The period after which each worker thread exits and is replaced by a new one (`--churn`).
+    pub churn: Option<Duration>,
     pub sampling: bool,
     pub sampling_period: Duration,
 
-    pub get_rate: GetRate,
+    pub op_mix: OpMix,
     pub op_dist: WeightedIndex<i32>,
-    pub key_dist: Uniform<usize>,
+    pub key_dist: KeyDist,
+    /// The number of keys sampled from the key distribution and inserted before the benchmark.
     pub prefill: usize,
+    pub prefill_strategy: PrefillStrategy,
     pub key_range: usize,
     pub interval: u64,
     pub duration: Duration,
-    pub ops_per_cs: OpsPerCs,
+    /// The duration to run the workers before the measurement, which is not measured.
+    pub warmup: Duration,
+    /// The number of measurements on the same prefilled map.
+    pub trials: usize,
+    /// The seed of the random number generators of all threads (`--seed`).
+    pub seed: u64,
+    /// The number of operations per critical section (`-c`) of the schemes that have one.
+    pub ops_per_cs: u64,
+    pub latency: bool,
+    /// The CSV file to write a row of the progress to on every sampling tick (`--timeseries`).
+    pub timeseries: Option<String>,
+    /// The trace file replayed by the workers instead of sampling the operations (`--trace`).
+    pub trace_file: Option<String>,
+    pub trace: Option<Trace>,
+    /// The file to record the operations of an unmeasured run to (`--record`).
+    pub record: Option<String>,
+    /// Whether to record the time of each operation, which delays it on a replay (`--record-times`).
+    pub record_times: bool,
+    /// Whether to check the map against the successful operations after the trials (`--validate`).
+    pub validate: bool,
 
     pub mem_sampler: MemSampler,
 }
@@ -96,19 +384,29 @@ impl fmt::Display for Config {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{}: {} threads, n{}, c{}, g{}, {} bag",
+            "{}: {} threads, n{}, c{}, {}, {} bag, {} keys, seed {}",
             self.ds.to_possible_value().unwrap().get_name(),
             self.threads,
             self.non_coop,
             self.ops_per_cs,
-            self.get_rate as u8,
+            self.op_mix,
             self.bag_size,
+            self.key_dist,
+            self.seed,
         )
     }
 }
 
 pub struct BenchWriter {
-    output: Option<Writer<File>>,
+    output: Option<Output>,
+}
+
+/// The format of the output file (`--output-format`).
+enum Output {
+    /// A CSV row per run, with the columns written in `setup`.
+    Csv(Box<Writer<File>>),
+    /// A self-describing JSON object per line, with the metadata of the host and the build.
+    Jsonl(File),
 }
 
 #[derive(Clone)]
@@ -118,8 +416,23 @@ pub struct Perf {
     pub avg_mem: usize,
     pub peak_garb: usize,
     pub avg_garb: usize,
+    /// The number of worker threads that exited and were replaced by a new one.
+    pub replaced: u64,
+    /// The garbages left after a new thread ran for a churn period once the workers exited, if
+    /// `--churn` is given.
+    pub drained_garb: Option<usize>,
+    /// The reclamation events during the run, if the scheme counts them.
+    pub reclamation: Option<Stats>,
+    /// The pool of the reused nodes at the end of the run, if the scheme reuses them.
+    pub pool: Option<Pool>,
+    /// The latency histograms (ns) of each operation in the order of `Op::OPS`, if measured.
+    pub latency: Option<[Histogram; 3]>,
 }
 
+/// The reported quantiles of the operation latencies.
+const LATENCY_QUANTILES: [(&str, f64); 4] =
+    [("p50", 0.5), ("p90", 0.9), ("p99", 0.99), ("p99.9", 0.999)];
+
 impl fmt::Display for Perf {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -130,11 +443,157 @@ impl fmt::Display for Perf {
             readable_bytes(self.avg_mem),
             self.peak_garb,
             self.avg_garb
-        )
+        )?;
+        if let Some(drained_garb) = self.drained_garb {
+            write!(
+                f,
+                ", replaced threads: {}, drained garb: {drained_garb}",
+                self.replaced
+            )?;
+        }
+        if let Some(stats) = &self.reclamation {
+            write!(
+                f,
+                "\nretired: {}, reclaimed: {}, scans: {}, avg batch: {:.1}, epoch advances: {}, \
+                 signals: {}, restarts: {}",
+                stats.retired,
+                stats.reclaimed,
+                stats.scans,
+                stats.avg_batch(),
+                stats.epoch_advances,
+                stats.signals,
+                stats.restarts
+            )?;
+        }
+        if let Some(pool) = &self.pool {
+            write!(f, "\npooled: {}, bags: {}", pool.pooled, pool.bags)?;
+        }
+        if let Some(latency) = &self.latency {
+            for (op, hist) in Op::OPS.iter().zip(latency) {
+                if hist.count() == 0 {
+                    continue;
+                }
+                write!(f, "\n{op} latency (ns):")?;
+                for (name, q) in LATENCY_QUANTILES {
+                    write!(f, " {name}: {},", hist.quantile(q))?;
+                }
+                write!(f, " max: {}", hist.max())?;
+            }
+        }
+        Ok(())
     }
 }
 
-fn readable_bytes(num: usize) -> String {
+/// The statistics of the throughput and the memory usage over the trials.
+pub struct PerfSummary {
+    pub throughput: Summary,
+    pub peak_mem: Summary,
+    pub avg_mem: Summary,
+}
+
+impl PerfSummary {
+    pub fn of(perfs: &[Perf]) -> Self {
+        let summary = |f: fn(&Perf) -> f64| Summary::of(&perfs.iter().map(f).collect::<Vec<_>>());
+        Self {
+            throughput: summary(|p| p.ops_per_sec as f64),
+            peak_mem: summary(|p| p.peak_mem as f64),
+            avg_mem: summary(|p| p.avg_mem as f64),
+        }
+    }
+}
+
+impl fmt::Display for PerfSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let t = &self.throughput;
+        write!(
+            f,
+            "ops/s: mean {:.0}, stddev {:.0}, 95% CI ±{:.0}",
+            t.mean, t.stddev, t.ci95
+        )?;
+        for (name, m) in [("peak mem", &self.peak_mem), ("avg mem", &self.avg_mem)] {
+            write!(
+                f,
+                "\n{name}: mean {}, stddev {}, 95% CI ±{}",
+                readable_bytes(m.mean as usize),
+                readable_bytes(m.stddev as usize),
+                readable_bytes(m.ci95 as usize),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The CSV columns of the latencies, e.g., `get_p50`, ..., `get_max`, `insert_p50`, ...
+fn latency_columns() -> Vec<String> {
+    Op::OPS
+        .iter()
+        .flat_map(|op| {
+            LATENCY_QUANTILES
+                .iter()
+                .map(|(name, _)| name.replace('.', ""))
+                .chain(["max".to_string()])
+                .map(move |name| format!("{op}_{name}"))
+        })
+        .collect()
+}
+
+/// The values of `latency_columns`, which are empty if the latencies were not measured.
+fn latency_values(latency: &Option<[Histogram; 3]>) -> Vec<String> {
+    match latency {
+        Some(latency) => latency
+            .iter()
+            .flat_map(|hist| {
+                LATENCY_QUANTILES
+                    .iter()
+                    .map(|(_, q)| hist.quantile(*q))
+                    .chain([hist.max()])
+                    .map(|v| v.to_string())
+            })
+            .collect(),
+        None => vec![String::new(); latency_columns().len()],
+    }
+}
+
+/// The CSV columns of the pool of the reused nodes.
+const POOL_COLUMNS: [&str; 2] = ["pooled", "bags"];
+
+/// The values of `POOL_COLUMNS`, which are empty if the scheme does not reuse the nodes.
+fn pool_values(pool: &Option<Pool>) -> Vec<String> {
+    match pool {
+        Some(pool) => [pool.pooled.to_string(), pool.bags.to_string()].into(),
+        None => vec![String::new(); POOL_COLUMNS.len()],
+    }
+}
+
+/// The CSV columns of the reclamation statistics.
+const RECLAMATION_COLUMNS: [&str; 7] = [
+    "retired",
+    "reclaimed",
+    "scans",
+    "avg_batch",
+    "epoch_advances",
+    "signals",
+    "restarts",
+];
+
+/// The values of `RECLAMATION_COLUMNS`, which are empty if the scheme does not count them.
+fn reclamation_values(stats: &Option<Stats>) -> Vec<String> {
+    match stats {
+        Some(stats) => [
+            stats.retired.to_string(),
+            stats.reclaimed.to_string(),
+            stats.scans.to_string(),
+            format!("{:.1}", stats.avg_batch()),
+            stats.epoch_advances.to_string(),
+            stats.signals.to_string(),
+            stats.restarts.to_string(),
+        ]
+        .into(),
+        None => vec![String::new(); RECLAMATION_COLUMNS.len()],
+    }
+}
+
+pub fn readable_bytes(num: usize) -> String {
     const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
     for (i, unit) in UNITS.iter().enumerate() {
         if num / 2usize.pow(i as u32 * 10) < 1000 {
@@ -149,45 +608,268 @@ fn readable_bytes(num: usize) -> String {
 }
 
 impl BenchWriter {
-    pub fn write_record(self, config: &Config, perf: &Perf) {
-        if let Some(mut output) = self.output {
+    /// Writes the results of the trials, a CSV row per trial or a JSON object for all trials.
+    pub fn write_record(self, config: &Config, perfs: &[Perf]) {
+        match self.output {
+            Some(Output::Csv(output)) => Self::write_csv(*output, config, perfs),
+            Some(Output::Jsonl(output)) => Self::write_json(output, config, perfs),
+            None => {}
+        }
+    }
+
+    fn write_csv(mut output: Writer<File>, config: &Config, perfs: &[Perf]) {
+        for (trial, perf) in perfs.iter().enumerate() {
             output
-                .write_record(&[
-                    // chrono::Local::now().to_rfc3339(),
-                    config
-                        .ds
-                        .to_possible_value()
-                        .unwrap()
-                        .get_name()
-                        .to_string(),
-                    config.mm.clone(),
-                    config.threads.to_string(),
-                    config.bag_size.to_string(),
-                    config.sampling_period.as_millis().to_string(),
-                    config.non_coop.to_string(),
-                    (config.get_rate as u8).to_string(),
-                    config.ops_per_cs.to_string(),
-                    perf.ops_per_sec.to_string(),
-                    perf.peak_mem.to_string(),
-                    perf.avg_mem.to_string(),
-                    perf.peak_garb.to_string(),
-                    perf.avg_garb.to_string(),
-                    config.key_range.to_string(),
-                    config.interval.to_string(),
-                ])
+                .write_record(
+                    [
+                        // chrono::Local::now().to_rfc3339(),
+                        config
+                            .ds
+                            .to_possible_value()
+                            .unwrap()
+                            .get_name()
+                            .to_string(),
+                        config.mm.clone(),
+                        config.threads.to_string(),
+                        config.bag_size.to_string(),
+                        config.sampling_period.as_millis().to_string(),
+                        config.non_coop.to_string(),
+                        config.op_mix.get.to_string(),
+                        config.op_mix.insert.to_string(),
+                        config.op_mix.remove.to_string(),
+                        config.ops_per_cs.to_string(),
+                        perf.ops_per_sec.to_string(),
+                        perf.peak_mem.to_string(),
+                        perf.avg_mem.to_string(),
+                        perf.peak_garb.to_string(),
+                        perf.avg_garb.to_string(),
+                        config.key_range.to_string(),
+                        config.interval.to_string(),
+                        config.key_dist.to_string(),
+                    ]
+                    .into_iter()
+                    .chain(latency_values(&perf.latency))
+                    .chain([
+                        trial.to_string(),
+                        config.affinity.to_string(),
+                        config.prefill.to_string(),
+                        config.prefill_strategy.to_string(),
+                        config.seed.to_string(),
+                        config.trace_file.clone().unwrap_or_default(),
+                        config
+                            .nbr_lowatermark
+                            .map(|w| w.to_string())
+                            .unwrap_or_default(),
+                        config
+                            .churn
+                            .map(|churn| churn.as_millis().to_string())
+                            .unwrap_or_default(),
+                        perf.replaced.to_string(),
+                        perf.drained_garb
+                            .map(|garb| garb.to_string())
+                            .unwrap_or_default(),
+                    ])
+                    .chain(reclamation_values(&perf.reclamation))
+                    .chain(pool_values(&perf.pool)),
+                )
                 .unwrap();
-            output.flush().unwrap();
+        }
+        output.flush().unwrap();
+    }
+
+    fn perf_json(perf: &Perf) -> serde_json::Value {
+        let latency = perf.latency.as_ref().map(|latency| {
+            Op::OPS
+                .iter()
+                .zip(latency)
+                .map(|(op, hist)| {
+                    let mut summary = serde_json::Map::new();
+                    summary.insert("count".into(), hist.count().into());
+                    for (name, q) in LATENCY_QUANTILES {
+                        summary.insert(name.replace('.', ""), hist.quantile(q).into());
+                    }
+                    summary.insert("max".into(), hist.max().into());
+                    (op.to_string(), summary.into())
+                })
+                .collect::<serde_json::Map<_, _>>()
+        });
+        json!({
+            "throughput": perf.ops_per_sec,
+            "peak_mem": perf.peak_mem,
+            "avg_mem": perf.avg_mem,
+            "peak_garb": perf.peak_garb,
+            "avg_garb": perf.avg_garb,
+            "replaced": perf.replaced,
+            "drained_garb": perf.drained_garb,
+            "reclamation": perf.reclamation.map(|stats| json!({
+                "retired": stats.retired,
+                "reclaimed": stats.reclaimed,
+                "scans": stats.scans,
+                "avg_batch": stats.avg_batch(),
+                "epoch_advances": stats.epoch_advances,
+                "signals": stats.signals,
+                "restarts": stats.restarts,
+            })),
+            "pool": perf.pool.map(|pool| json!({
+                "pooled": pool.pooled,
+                "bags": pool.bags,
+            })),
+            "latency": latency,
+        })
+    }
+
+    fn write_json(mut output: File, config: &Config, perfs: &[Perf]) {
+        let host = Host::detect();
+        let summary = PerfSummary::of(perfs);
+        let summary_json =
+            |s: Summary| json!({ "mean": s.mean, "stddev": s.stddev, "ci95": s.ci95 });
+        let record = json!({
+            "timestamp": SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            "command": std::env::args().collect::<Vec<_>>(),
+            "config": {
+                "ds": config.ds.to_possible_value().unwrap().get_name(),
+                "mm": config.mm,
+                "threads": config.threads,
+                "affinity": config.affinity.to_string(),
+                "bag_size": config.bag_size.to_string(),
+                "nbr_lowatermark": config.nbr_lowatermark,
+                "non_coop": config.non_coop,
+                "non_coop_period_ms": config.non_coop_period.as_millis() as u64,
+                "churn_ms": config.churn.map(|churn| churn.as_millis() as u64),
+                "sampling": config.sampling,
+                "sampling_period_ms": config.sampling_period.as_millis() as u64,
+                "aux_thread": config.aux_thread,
+                "aux_thread_period_ms": config.aux_thread_period.as_millis() as u64,
+                "op_mix": {
+                    "get": config.op_mix.get,
+                    "insert": config.op_mix.insert,
+                    "remove": config.op_mix.remove,
+                },
+                "key_dist": config.key_dist.to_string(),
+                "prefill": config.prefill,
+                "prefill_strategy": config.prefill_strategy.to_string(),
+                "key_range": config.key_range,
+                "interval": config.interval,
+                "warmup": config.warmup.as_secs(),
+                "trials": config.trials,
+                "seed": config.seed,
+                "ops_per_cs": config.ops_per_cs,
+                "latency": config.latency,
+                "timeseries": config.timeseries,
+                "trace": config.trace_file,
+                "record": config.record,
+                "record_times": config.record_times,
+                "validate": config.validate,
+            },
+            "perf": perfs.iter().map(Self::perf_json).collect::<Vec<_>>(),
+            "summary": {
+                "throughput": summary_json(summary.throughput),
+                "peak_mem": summary_json(summary.peak_mem),
+                "avg_mem": summary_json(summary.avg_mem),
+            },
+            "host": {
+                "cpus": host.cpus,
+                "cpu_model": host.cpu_model,
+                "kernel": host.kernel,
+            },
+            "build": {
+                "profile": host::PROFILE,
+                "commit": host::COMMIT,
+                "allocator": host::ALLOCATOR,
+                "features": host::FEATURES
+                    .iter()
+                    .filter(|(_, enabled)| *enabled)
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>(),
+            },
+        });
+        writeln!(output, "{record}").unwrap();
+        output.flush().unwrap();
+    }
+}
+
+/// Parses `--prefill`: a count if it is an integer, or a fraction of `key_range` otherwise.
+fn parse_prefill(spec: &str, key_range: usize) -> Result<usize, String> {
+    if let Ok(count) = spec.parse::<usize>() {
+        return Ok(count);
+    }
+    match spec.parse::<f64>() {
+        Ok(fraction) if (0.0..=1.0).contains(&fraction) => {
+            Ok((fraction * key_range as f64) as usize)
+        }
+        _ => Err(format!(
+            "invalid prefill `{spec}`: expected a count or a fraction in [0, 1]"
+        )),
+    }
+}
+
+/// Prints a line for every pair of a scheme and a data structure, with the options taking effect if
+/// the pair is supported.
+fn print_capabilities(schemes: &[Scheme]) {
+    for scheme in schemes {
+        for ds in DS::value_variants() {
+            let name = ds.to_possible_value().unwrap().get_name().to_string();
+            let line = match (scheme.driver)(ds) {
+                Some(driver) => json!({
+                    "scheme": scheme.name,
+                    "data_structure": name,
+                    "supported": true,
+                    "bag_size": driver.bag_size,
+                    "ops_per_cs": driver.ops_per_cs,
+                    "non_coop": if driver.non_coop { vec![0, 1, 2, 3] } else { vec![0] },
+                    "churn": driver.churn,
+                    "validate": if driver.validate_structure { "structure" } else { "keys" },
+                }),
+                None => json!({
+                    "scheme": scheme.name,
+                    "data_structure": name,
+                    "supported": false,
+                }),
+            };
+            println!("{line}");
         }
     }
 }
 
-pub fn setup(mm: String) -> (Config, BenchWriter) {
-    let m = Command::new(mm.clone())
+/// The schemes that do not honor an option with any data structure, e.g., `nr` for `-n`.
+fn unavailable_on(schemes: &[Scheme], honors: fn(&Driver) -> bool) -> Vec<&'static str> {
+    schemes
+        .iter()
+        .filter(|scheme| {
+            !DS::value_variants()
+                .iter()
+                .filter_map(|ds| (scheme.driver)(ds))
+                .any(|driver| honors(&driver))
+        })
+        .map(|scheme| scheme.name)
+        .collect()
+}
+
+/// Parses the command line arguments. `schemes` are the reclamation schemes that can be selected
+/// with `-m`.
+pub fn setup(schemes: &'static [Scheme]) -> (Config, BenchWriter) {
+    setup_from(schemes, std::env::args_os())
+}
+
+/// Like [`setup`], but parses the given command-line arguments, starting with the binary name.
+pub fn setup_from<I, T>(schemes: &'static [Scheme], args: I) -> (Config, BenchWriter)
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let mut cmd = Command::new("smr-bench")
+        .arg(
+            Arg::new("memory manager")
+                .short('m')
+                .value_parser(PossibleValuesParser::new(schemes.iter().map(|s| s.name)))
+                .required_unless_present("list capabilities")
+                .help("Memory reclamation scheme"),
+        )
         .arg(
             Arg::new("data structure")
                 .short('d')
                 .value_parser(value_parser!(DS))
-                .required(true)
+                .required_unless_present("list capabilities")
                 .ignore_case(true)
                 .help("Data structure(s)"),
         )
@@ -195,19 +877,42 @@ pub fn setup(mm: String) -> (Config, BenchWriter) {
             Arg::new("threads")
                 .short('t')
                 .value_parser(value_parser!(usize))
-                .required(true)
+                .required_unless_present_any(["trace", "list capabilities"])
+                .conflicts_with("trace")
                 .help("Numbers of threads to run."),
         )
         .arg(
-            Arg::new("non-coop")
-                .short('n')
+            Arg::new("affinity")
+                .long("affinity")
                 .help(
-                    "The degree of non-cooperation (available on EBR and PEBR). \
-                     1: 1ms, 2: 10ms, 3: stall",
+                    "Pins the worker threads and the auxiliary thread to CPUs. \
+                     none: no pinning, compact: fill each core and socket first, \
+                     scatter: spread across sockets and cores first, \
+                     or a CPU list such as 0,2,4-7",
                 )
+                .default_value("none"),
+        )
+        .arg(
+            Arg::new("non-coop")
+                .short('n')
+                .help(format!(
+                    "The degree of non-cooperation of an extra thread stalled in the middle of \
+                     an operation (not available on {}). 1: 1ms, 2: 10ms, 3: stall",
+                    unavailable_on(schemes, |driver| driver.non_coop).join(", ")
+                ))
                 .value_parser(value_parser!(u8).range(0..4))
                 .default_value("0"),
         )
+        .arg(
+            Arg::new("churn")
+                .long("churn")
+                .value_name("MS")
+                .value_parser(value_parser!(u64).range(1..))
+                .help(
+                    "Replaces each worker thread by a new one every MS milliseconds, and reports \
+                     the garbages left after the workers exited (not available on NBR)",
+                ),
+        )
         .arg(
             Arg::new("get rate")
                 .short('g')
@@ -218,6 +923,15 @@ pub fn setup(mm: String) -> (Config, BenchWriter) {
                 .value_parser(value_parser!(u8).range(0..4))
                 .default_value("0"),
         )
+        .arg(
+            Arg::new("op mix")
+                .long("mix")
+                .conflicts_with("get rate")
+                .help(
+                    "The percentages of operations, e.g., get=70,insert=20,remove=10. \
+                     Overrides the presets of -g",
+                ),
+        )
         .arg(
             Arg::new("range")
                 .short('r')
@@ -225,6 +939,34 @@ pub fn setup(mm: String) -> (Config, BenchWriter) {
                 .help("Key range: [0..RANGE]")
                 .default_value("100000"),
         )
+        .arg(
+            Arg::new("prefill")
+                .long("prefill")
+                .help(
+                    "The number of keys to insert before the benchmark, either a count (e.g., \
+                     5000) or a fraction of the key range (e.g., 0.5). The keys are sampled \
+                     from the key distribution, so duplicates are inserted only once",
+                )
+                .default_value("0.5"),
+        )
+        .arg(
+            Arg::new("prefill strategy")
+                .long("prefill-strategy")
+                .value_parser(value_parser!(PrefillStrategy))
+                .help(
+                    "The order of the prefilled keys. \
+                     Defaults to random for the trees and decreasing for the others",
+                ),
+        )
+        .arg(
+            Arg::new("key distribution")
+                .long("key-dist")
+                .help(
+                    "The distribution of keys. uniform, zipf:<skew> (the smaller keys are \
+                     hotter), hotspot:<X>,<Y> (X% of operations on Y% of keys) or sequential",
+                )
+                .default_value("uniform"),
+        )
         .arg(
             Arg::new("interval")
                 .short('i')
@@ -232,6 +974,32 @@ pub fn setup(mm: String) -> (Config, BenchWriter) {
                 .help("Time interval in seconds to run the benchmark")
                 .default_value("10"),
         )
+        .arg(
+            Arg::new("warmup")
+                .long("warmup")
+                .value_parser(value_parser!(u64))
+                .help("Time in seconds to run the benchmark before the measurement, which is not measured")
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("trials")
+                .long("trials")
+                .value_parser(value_parser!(u32).range(1..))
+                .help(
+                    "The number of measurements on the same prefilled map. \
+                     Reports the mean, the standard deviation and the 95% confidence interval.",
+                )
+                .default_value("1"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .value_parser(value_parser!(u64))
+                .help(
+                    "The seed of the random number generators for the keys, the operations \
+                     and the data structures (e.g., skip list heights). Random if not given",
+                ),
+        )
         .arg(
             Arg::new("sampling period")
                 .short('s')
@@ -245,105 +1013,259 @@ pub fn setup(mm: String) -> (Config, BenchWriter) {
         .arg(
             Arg::new("ops per cs")
                 .short('c')
-                .value_parser(["1", "4"])
+                .value_parser(value_parser!(u64).range(1..))
                 .help("Operations per each critical section")
                 .default_value("1"),
         )
         .arg(
             Arg::new("bag size")
                 .short('b')
-                .value_parser(["small", "large"])
-                .help("The size of deferred bag")
+                .long("bag-size")
+                .value_parser(BagSize::parse)
+                .help(
+                    "The size of deferred bag. small or large: the sizes each scheme is tuned \
+                     with, or the number of retired nodes (a power of two for NBR)",
+                )
                 .default_value("small"),
         )
+        .arg(
+            Arg::new("nbr lowatermark")
+                .long("nbr-lowatermark")
+                .value_parser(RangedU64ValueParser::<usize>::new().range(1..))
+                .help(
+                    "The low watermark of NBR, i.e., the number of retired nodes between the \
+                     attempts of an early reclamation (default: 1/8 of the bag size)",
+                ),
+        )
         .arg(
             Arg::new("output")
                 .short('o')
-                .help("Output CSV filename. Appends the data if the file already exists."),
+                .help("Output filename. Appends the data if the file already exists."),
+        )
+        .arg(
+            Arg::new("output format")
+                .long("output-format")
+                .value_parser(["csv", "jsonl"])
+                .help(
+                    "The format of the output file. `jsonl` writes a JSON object per line, \
+                     which also describes the host and the build.",
+                )
+                .default_value("csv"),
+        )
+        .arg(
+            Arg::new("latency")
+                .long("latency")
+                .action(ArgAction::SetTrue)
+                .help("Measure the latency of each operation and report its percentiles"),
+        )
+        .arg(
+            Arg::new("timeseries")
+                .long("timeseries")
+                .value_name("FILE")
+                .help(
+                    "Output CSV filename of the time series of the cumulative operations, \
+                     memory usage and garbages, written on every sampling tick. \
+                     Overwrites the file if it already exists.",
+                ),
+        )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .value_name("FILE")
+                .help(
+                    "Replays the operations in the trace file instead of sampling them, \
+                     with a worker per thread of the trace",
+                ),
+        )
+        .arg(
+            Arg::new("record")
+                .long("record")
+                .value_name("FILE")
+                .conflicts_with("trace")
+                .help(
+                    "Records the operations of an unmeasured run before the trials, which draws \
+                     the same operations as the first trial, to a trace file",
+                ),
+        )
+        .arg(
+            Arg::new("record times")
+                .long("record-times")
+                .action(ArgAction::SetTrue)
+                .requires("record")
+                .help(
+                    "Also records the time of each operation, so that a replay does not issue it \
+                     earlier. Without it, a replay issues the operations as fast as possible.",
+                ),
+        )
+        .arg(
+            Arg::new("validate")
+                .long("validate")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "After the trials, checks that the map has the keys left by the successful \
+                     inserts and removes, and also its structure where --list-capabilities \
+                     reports \"validate\":\"structure\"",
+                ),
+        )
+        .arg(
+            Arg::new("list capabilities")
+                .long("list-capabilities")
+                .action(ArgAction::SetTrue)
+                .exclusive(true)
+                .help(
+                    "Prints the data structures of each scheme and the options they support, \
+                     as a JSON object per line",
+                ),
         )
         .arg(
             Arg::new("dry run")
                 .long("dry-run")
                 .action(ArgAction::SetTrue)
                 .help("Check whether the arguments are parsable, without running a benchmark"),
-        )
-        .get_matches();
+        );
+    let m = cmd
+        .try_get_matches_from_mut(args)
+        .unwrap_or_else(|e| e.exit());
+
+    if m.get_flag("list capabilities") {
+        print_capabilities(schemes);
+        std::process::exit(0);
+    }
 
+    let mm = m.get_one::<String>("memory manager").cloned().unwrap();
     let ds = m.get_one::<DS>("data structure").cloned().unwrap();
-    let threads = m.get_one::<usize>("threads").copied().unwrap();
-    let bag_size = match m.get_one::<String>("bag size").unwrap().as_str() {
-        "small" => BagSize::Small,
-        "large" => BagSize::Large,
-        _ => unreachable!("bag_size should be small or large"),
+    let trace_file = m.get_one::<String>("trace").cloned();
+    let trace = trace_file.as_deref().map(|path| {
+        Trace::load(path).unwrap_or_else(|e| cmd.error(ErrorKind::InvalidValue, e).exit())
+    });
+    let threads = match &trace {
+        Some(trace) => trace.threads.len(),
+        None => m.get_one::<usize>("threads").copied().unwrap(),
     };
+    let affinity = Affinity::parse(m.get_one::<String>("affinity").unwrap())
+        .unwrap_or_else(|e| cmd.error(ErrorKind::InvalidValue, e).exit());
+    let bag_size = m.get_one::<BagSize>("bag size").copied().unwrap();
+    let nbr_lowatermark = m.get_one::<usize>("nbr lowatermark").copied();
     let non_coop = m.get_one::<u8>("non-coop").copied().unwrap();
-    let get_rate = match m.get_one::<u8>("get rate").copied().unwrap() {
-        0 => GetRate::WriteOnly,
-        1 => GetRate::ReadWrite,
-        2 => GetRate::ReadIntensive,
-        3 => GetRate::ReadOnly,
-        _ => unreachable!("get_rate is invalid"),
+    let churn = m
+        .get_one::<u64>("churn")
+        .copied()
+        .map(Duration::from_millis);
+    let op_mix = match m.get_one::<String>("op mix") {
+        Some(spec) => {
+            OpMix::parse(spec).unwrap_or_else(|e| cmd.error(ErrorKind::InvalidValue, e).exit())
+        }
+        None => match m.get_one::<u8>("get rate").copied().unwrap() {
+            0 => GetRate::WriteOnly,
+            1 => GetRate::ReadWrite,
+            2 => GetRate::ReadIntensive,
+            3 => GetRate::ReadOnly,
+            _ => unreachable!("get_rate is invalid"),
+        }
+        .mix(),
     };
     let key_range = m.get_one::<usize>("range").copied().unwrap();
-    let prefill = key_range / 2;
-    let key_dist = Uniform::from(0..key_range);
+    let prefill = parse_prefill(m.get_one::<String>("prefill").unwrap(), key_range)
+        .unwrap_or_else(|e| cmd.error(ErrorKind::InvalidValue, e).exit());
+    let prefill_strategy = m
+        .get_one::<PrefillStrategy>("prefill strategy")
+        .copied()
+        .unwrap_or_else(|| ds.default_prefill_strategy());
+    let key_dist = KeyDist::parse(m.get_one::<String>("key distribution").unwrap(), key_range)
+        .unwrap_or_else(|e| cmd.error(ErrorKind::InvalidValue, e).exit());
     let interval = m.get_one::<u64>("interval").copied().unwrap();
     let sampling_period = m.get_one::<u64>("sampling period").copied().unwrap();
     let sampling = sampling_period > 0 && cfg!(all(not(feature = "sanitize"), target_os = "linux"));
-    let ops_per_cs = match m.get_one::<String>("ops per cs").unwrap().as_str() {
-        "1" => OpsPerCs::One,
-        "4" => OpsPerCs::Four,
-        _ => unreachable!("ops_per_cs should be one or four"),
-    };
+    let ops_per_cs = m.get_one::<u64>("ops per cs").copied().unwrap();
     let duration = Duration::from_secs(interval);
+    let warmup = Duration::from_secs(m.get_one::<u64>("warmup").copied().unwrap());
+    let trials = m.get_one::<u32>("trials").copied().unwrap() as usize;
+    let seed = m
+        .get_one::<u64>("seed")
+        .copied()
+        .unwrap_or_else(rand::random);
+    let latency = m.get_flag("latency");
+    let timeseries = m.get_one::<String>("timeseries").cloned();
 
-    let op_weights = match get_rate {
-        GetRate::WriteOnly => &[0, 1, 1],
-        GetRate::ReadWrite => &[2, 1, 1],
-        GetRate::ReadIntensive => &[18, 1, 1],
-        GetRate::ReadOnly => &[1, 0, 0],
-    };
-    let op_dist = WeightedIndex::new(op_weights).unwrap();
+    let op_dist = WeightedIndex::new(op_mix.weights()).unwrap();
 
+    let output_format = m.get_one::<String>("output format").unwrap().as_str();
     let output = m.get_one::<String>("output").map(|output_name| {
         let output_path = Path::new(output_name);
         let dir = output_path.parent().unwrap();
         create_dir_all(dir).unwrap();
-        match OpenOptions::new().read(true).append(true).open(output_path) {
-            Ok(f) => csv::Writer::from_writer(f),
-            Err(_) => {
-                let f = OpenOptions::new()
-                    .read(true)
-                    .write(true)
+        if output_format == "jsonl" {
+            return Output::Jsonl(
+                OpenOptions::new()
+                    .append(true)
                     .create(true)
                     .open(output_path)
-                    .unwrap();
-                let mut output = csv::Writer::from_writer(f);
-                // NOTE: `write_record` on `bench`
-                output
-                    .write_record([
-                        // "timestamp",
-                        "ds",
-                        "mm",
-                        "threads",
-                        "bag_size",
-                        "sampling_period",
-                        "non_coop",
-                        "get_rate",
-                        "ops_per_cs",
-                        "throughput",
-                        "peak_mem",
-                        "avg_mem",
-                        "peak_garb",
-                        "avg_garb",
-                        "key_range",
-                        "interval",
-                    ])
-                    .unwrap();
-                output.flush().unwrap();
-                output
-            }
+                    .unwrap(),
+            );
         }
+        Output::Csv(Box::new(
+            match OpenOptions::new().read(true).append(true).open(output_path) {
+                Ok(f) => csv::Writer::from_writer(f),
+                Err(_) => {
+                    let f = OpenOptions::new()
+                        .read(true)
+                        .write(true)
+                        .create(true)
+                        .open(output_path)
+                        .unwrap();
+                    let mut output = csv::Writer::from_writer(f);
+                    // NOTE: `write_record` on `bench`
+                    output
+                        .write_record(
+                            [
+                                // "timestamp",
+                                "ds",
+                                "mm",
+                                "threads",
+                                "bag_size",
+                                "sampling_period",
+                                "non_coop",
+                                "get",
+                                "insert",
+                                "remove",
+                                "ops_per_cs",
+                                "throughput",
+                                "peak_mem",
+                                "avg_mem",
+                                "peak_garb",
+                                "avg_garb",
+                                "key_range",
+                                "interval",
+                                "key_dist",
+                            ]
+                            .map(String::from)
+                            .into_iter()
+                            .chain(latency_columns())
+                            .chain(
+                                [
+                                    "trial",
+                                    "affinity",
+                                    "prefill",
+                                    "prefill_strategy",
+                                    "seed",
+                                    "trace",
+                                    "nbr_lowatermark",
+                                    "churn",
+                                    "replaced",
+                                    "drained_garb",
+                                ]
+                                .into_iter()
+                                .chain(RECLAMATION_COLUMNS)
+                                .chain(POOL_COLUMNS)
+                                .map(String::from),
+                            ),
+                        )
+                        .unwrap();
+                    output.flush().unwrap();
+                    output
+                }
+            },
+        ))
     });
     let mem_sampler = MemSampler::new();
     let config = Config {
@@ -351,31 +1273,72 @@ pub fn setup(mm: String) -> (Config, BenchWriter) {
         mm,
         threads,
         bag_size,
+        nbr_lowatermark,
+        affinity,
 
-        aux_thread: if sampling || non_coop > 0 { 1 } else { 0 },
+        aux_thread: if sampling || timeseries.is_some() {
+            1
+        } else {
+            0
+        },
         aux_thread_period: Duration::from_millis(1),
         non_coop,
         non_coop_period: match non_coop {
             1 => Duration::from_millis(1),
             2 => Duration::from_millis(10),
-            // No repin if -n0 or -n3
+            // Stalls for the whole run if -n3
             _ => Duration::from_secs(interval),
         },
+        churn,
         sampling,
         sampling_period: Duration::from_millis(sampling_period),
 
-        get_rate,
+        op_mix,
         op_dist,
         key_dist,
         prefill,
+        prefill_strategy,
         key_range,
         interval,
         duration,
+        warmup,
+        trials,
+        seed,
         ops_per_cs,
+        latency,
+        timeseries,
+        trace_file,
+        trace,
+        record: m.get_one::<String>("record").cloned(),
+        record_times: m.get_flag("record times"),
+        validate: m.get_flag("validate"),
 
         mem_sampler,
     };
 
+    let driver = schemes
+        .iter()
+        .find(|s| s.name == config.mm)
+        .and_then(|s| (s.driver)(&config.ds));
+    if let Some(driver) = driver {
+        if !driver.non_coop && config.non_coop > 0 {
+            cmd.error(
+                ErrorKind::InvalidValue,
+                format!("-n is not available for {}", config.mm),
+            )
+            .exit();
+        }
+        if config.nbr_lowatermark.is_some() && config.mm != "nbr" {
+            cmd.error(
+                ErrorKind::InvalidValue,
+                format!("--nbr-lowatermark is not available for {}", config.mm),
+            )
+            .exit();
+        }
+        (driver.check_config)(&config)
+            .unwrap_or_else(|e| cmd.error(ErrorKind::InvalidValue, e).exit());
+    }
+
     if m.get_flag("dry run") {
         std::process::exit(0);
     }
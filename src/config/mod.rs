@@ -1,3 +1,5 @@
 //! Shared runtime configuration for map benchmarks.
 
+pub mod host;
 pub mod map;
+pub mod sweep;
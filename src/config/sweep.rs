@@ -0,0 +1,270 @@
+//! Experiment specifications of the `smr-sweep` runner.
+//!
+//! A specification is a TOML file describing the cartesian product of the configurations to run:
+//!
+//! ```toml
+//! output = "results/map.csv"
+//! interval = 10
+//! repetitions = 1
+//! schemes = ["ebr", "hp", "nbr"]
+//! data-structures = ["h-list", "hm-list", "nm-tree"]
+//! threads = [1, 4, 8]
+//! mixes = ["get=0,insert=50,remove=50", "get=90,insert=5,remove=5"]
+//! key-ranges = [100000]
+//! # Arguments passed to every run as is.
+//! args = ["-b", "small"]
+//!
+//! # Overrides `key-ranges` for some data structures.
+//! [key-ranges-for]
+//! h-list = [1000, 10000]
+//!
+//! # Skips the configurations matching all given fields.
+//! [[exclude]]
+//! data-structure = "hhs-list"
+//! mix = "get=0,insert=50,remove=50"
+//! ```
+//!
+//! The combinations of a scheme and a data structure that the scheme does not implement are
+//! skipped, according to the registry of the schemes (see [`Scheme::supports`]).
+
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use super::map::{OpMix, DS};
+use crate::bench::map::{scheme, Scheme};
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Spec {
+    /// The result file passed to `-o` of every run.
+    pub output: String,
+    /// The time interval in seconds of each run (`-i`).
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+    /// The number of times to run the whole matrix.
+    #[serde(default = "default_repetitions")]
+    pub repetitions: usize,
+    pub schemes: Vec<String>,
+    pub data_structures: Vec<String>,
+    pub threads: Vec<usize>,
+    /// The operation mixes (`--mix`).
+    #[serde(default = "default_mixes")]
+    pub mixes: Vec<String>,
+    pub key_ranges: Vec<usize>,
+    #[serde(default)]
+    pub key_ranges_for: BTreeMap<String, Vec<usize>>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<Exclude>,
+}
+
+fn default_interval() -> u64 {
+    10
+}
+
+fn default_repetitions() -> usize {
+    1
+}
+
+fn default_mixes() -> Vec<String> {
+    vec!["get=0,insert=50,remove=50".to_string()]
+}
+
+/// A pattern of the configurations to skip. The omitted fields match anything.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Exclude {
+    pub scheme: Option<String>,
+    pub data_structure: Option<String>,
+    pub threads: Option<usize>,
+    pub mix: Option<String>,
+    pub key_range: Option<usize>,
+}
+
+/// A single configuration of the matrix.
+pub struct Run {
+    pub repetition: usize,
+    pub scheme: &'static Scheme,
+    pub ds: String,
+    pub threads: usize,
+    pub mix: OpMix,
+    pub key_range: usize,
+}
+
+impl Run {
+    /// The arguments of `smr-bench` for this run.
+    pub fn args(&self, spec: &Spec) -> Vec<String> {
+        let mut args = vec![
+            "-m".to_string(),
+            self.scheme.name.to_string(),
+            "-d".to_string(),
+            self.ds.clone(),
+            "-t".to_string(),
+            self.threads.to_string(),
+            "--mix".to_string(),
+            self.mix.to_string(),
+            "-r".to_string(),
+            self.key_range.to_string(),
+            "-i".to_string(),
+            spec.interval.to_string(),
+        ];
+        args.extend(spec.args.iter().cloned());
+        args.extend(["-o".to_string(), spec.output.clone()]);
+        args
+    }
+
+    /// The identifier of this run in the list of the finished runs, which is used to resume.
+    pub fn id(&self, spec: &Spec) -> String {
+        format!("{} {}", self.repetition, self.args(spec).join(" "))
+    }
+}
+
+impl Exclude {
+    fn matches(&self, run: &Run) -> bool {
+        self.scheme.as_ref().map_or(true, |s| *s == run.scheme.name)
+            && self
+                .data_structure
+                .as_ref()
+                .map_or(true, |ds| *ds == run.ds)
+            && self.threads.map_or(true, |t| t == run.threads)
+            && self.key_range.map_or(true, |r| r == run.key_range)
+            && self
+                .mix
+                .as_ref()
+                .map_or(true, |mix| OpMix::parse(mix).ok() == Some(run.mix))
+    }
+}
+
+impl Spec {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let spec: Spec = basic_toml::from_str(input).map_err(|e| e.to_string())?;
+        let schemes = spec
+            .schemes
+            .iter()
+            .chain(spec.exclude.iter().flat_map(|e| &e.scheme));
+        if let Some(name) = schemes.into_iter().find(|name| scheme(name).is_none()) {
+            return Err(format!("unknown scheme `{name}`"));
+        }
+        let dss = spec
+            .data_structures
+            .iter()
+            .chain(spec.key_ranges_for.keys())
+            .chain(spec.exclude.iter().flat_map(|e| &e.data_structure));
+        if let Some(name) = dss
+            .into_iter()
+            .find(|name| DS::from_str(name, false).is_err())
+        {
+            return Err(format!("unknown data structure `{name}`"));
+        }
+        for mix in spec
+            .mixes
+            .iter()
+            .chain(spec.exclude.iter().flat_map(|e| &e.mix))
+        {
+            OpMix::parse(mix)?;
+        }
+        Ok(spec)
+    }
+
+    /// All runs of the matrix in order, and the number of the skipped combinations that are not
+    /// implemented.
+    pub fn runs(&self) -> (Vec<Run>, usize) {
+        let mut runs = Vec::new();
+        let mut unsupported = 0;
+        for repetition in 0..self.repetitions {
+            for ds in &self.data_structures {
+                let key_ranges = self.key_ranges_for.get(ds).unwrap_or(&self.key_ranges);
+                for &key_range in key_ranges {
+                    for name in &self.schemes {
+                        let scheme = scheme(name).unwrap();
+                        if !scheme.supports(&DS::from_str(ds, false).unwrap()) {
+                            unsupported += 1;
+                            continue;
+                        }
+                        for mix in &self.mixes {
+                            for &threads in &self.threads {
+                                let run = Run {
+                                    repetition,
+                                    scheme,
+                                    ds: ds.clone(),
+                                    threads,
+                                    mix: OpMix::parse(mix).unwrap(),
+                                    key_range,
+                                };
+                                if !self.exclude.iter().any(|e| e.matches(&run)) {
+                                    runs.push(run);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        (runs, unsupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Spec;
+
+    #[test]
+    fn runs() {
+        let spec = Spec::parse(
+            r#"
+            output = "results/map.csv"
+            schemes = ["ebr", "hp"]
+            data-structures = ["h-list", "hm-list"]
+            threads = [1, 4]
+            mixes = ["get=0,insert=50,remove=50", "get=90,insert=5,remove=5"]
+            key-ranges = [100000]
+
+            [key-ranges-for]
+            h-list = [1000, 10000]
+
+            [[exclude]]
+            scheme = "ebr"
+            mix = "insert=50,remove=50"
+            "#,
+        )
+        .unwrap();
+        let (runs, unsupported) = spec.runs();
+        // HP does not implement HList.
+        assert_eq!(unsupported, 2);
+        // With 2 thread counts each: (EBR, HList) for 2 key ranges and (EBR, HMList) without the
+        // excluded mix, and (HP, HMList) with both mixes.
+        assert_eq!(runs.len(), (2 + 1 + 2) * 2);
+        assert!(runs
+            .iter()
+            .all(|r| r.scheme.name != "ebr" || r.mix.get == 90));
+        assert_eq!(
+            runs[0].args(&spec).join(" "),
+            "-m ebr -d h-list -t 1 --mix get=90,insert=5,remove=5 -r 1000 -i 10 \
+             -o results/map.csv"
+        );
+
+        let spec = |schemes: &str, exclude: &str| {
+            Spec::parse(&format!(
+                "output = 'a'\nschemes = {schemes}\ndata-structures = ['h-list']\nthreads = [1]\n\
+                 key-ranges = [100]\n{exclude}"
+            ))
+        };
+        assert!(spec(
+            "['ebr']",
+            "[[exclude]]\nscheme = 'ebr'\ndata-structure = 'h-list'"
+        )
+        .is_ok());
+        assert_eq!(spec("['ebr2']", "").unwrap_err(), "unknown scheme `ebr2`");
+        assert_eq!(
+            spec("['ebr']", "[[exclude]]\nscheme = 'ebr2'").unwrap_err(),
+            "unknown scheme `ebr2`"
+        );
+        assert_eq!(
+            spec("['ebr']", "[[exclude]]\ndata-structure = 'hlist'").unwrap_err(),
+            "unknown data structure `hlist`"
+        );
+    }
+}
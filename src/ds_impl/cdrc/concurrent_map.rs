@@ -1,3 +1,5 @@
+use cdrc::{AtomicRc, Cs, Pointer, Snapshot, StrongPtr};
+
 pub trait OutputHolder<V> {
     fn default() -> Self;
     fn output(&self) -> &V;
@@ -14,6 +16,22 @@ pub trait ConcurrentMap<K, V, C> {
     fn get(&self, key: &K, output: &mut Self::Output, cs: &C) -> bool;
     fn insert(&self, key: K, value: V, output: &mut Self::Output, cs: &C) -> bool;
     fn remove(&self, key: &K, output: &mut Self::Output, cs: &C) -> bool;
+
+    /// Checks the structural invariants of the map and returns its keys. It must not run
+    /// concurrently with the other operations.
+    fn validate(&self) -> Result<Vec<&K>, String>;
+}
+
+/// Loads the node of `link` for a `validate`. As it does not run concurrently with the other
+/// operations, the node lives as long as `link`.
+pub(crate) fn load_quiescent<T, C: Cs>(link: &AtomicRc<T, C>) -> Option<&T> {
+    let mut snapshot = Snapshot::new();
+    snapshot.load(link, unsafe { &C::unprotected() });
+    if snapshot.is_null() {
+        None
+    } else {
+        Some(unsafe { snapshot.deref() })
+    }
 }
 
 #[cfg(test)]
@@ -83,5 +101,13 @@ pub mod tests {
             }
         })
         .unwrap();
+
+        let mut keys = map.validate().unwrap();
+        keys.sort();
+        let mut expected = ((THREADS / 2)..THREADS)
+            .flat_map(|t| (0..ELEMENTS_PER_THREADS).map(move |k| k * THREADS + t))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert!(keys.into_iter().eq(expected.iter()));
     }
 }
@@ -1,4 +1,4 @@
-use super::concurrent_map::{ConcurrentMap, OutputHolder};
+use super::concurrent_map::{load_quiescent, ConcurrentMap, OutputHolder};
 use cdrc::{AtomicRc, Cs, Pointer, Rc, Snapshot, StrongPtr};
 
 use std::cmp::Ordering::{Equal, Greater, Less};
@@ -286,6 +286,29 @@ where
         }
     }
 
+    /// Checks that the nodes are sorted and the unmarked ones have distinct keys, and returns the
+    /// keys of the unmarked nodes. Marked nodes that are not unlinked yet may remain.
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys: Vec<&K> = Vec::new();
+        let mut last: Option<&K> = None;
+        let head = load_quiescent(&self.head).unwrap();
+        let mut link = &head.next;
+        while let Some(curr_node) = load_quiescent(link) {
+            if last.map_or(false, |last| *last > curr_node.key) {
+                return Err(format!("the node #{} is out of order", keys.len()));
+            }
+            if curr_node.next.load(Ordering::Acquire).tag() == 0 {
+                if keys.last().map_or(false, |last| **last == curr_node.key) {
+                    return Err(format!("the node #{} has a duplicate key", keys.len()));
+                }
+                keys.push(&curr_node.key);
+            }
+            last = Some(&curr_node.key);
+            link = &curr_node.next;
+        }
+        Ok(keys)
+    }
+
     #[inline]
     fn get<F>(&self, key: &K, find: F, cursor: &mut Cursor<K, V, C>, cs: &C) -> bool
     where
@@ -422,6 +445,10 @@ where
     fn remove(&self, key: &K, output: &mut Self::Output, cs: &C) -> bool {
         self.inner.harris_remove(key, output, cs)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HMList<K, V, C: Cs> {
@@ -465,6 +492,10 @@ where
     fn remove(&self, key: &K, output: &mut Self::Output, cs: &C) -> bool {
         self.inner.harris_michael_remove(key, output, cs)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HHSList<K, V, C: Cs> {
@@ -508,6 +539,10 @@ where
     fn remove(&self, key: &K, output: &mut Self::Output, cs: &C) -> bool {
         self.inner.harris_remove(key, output, cs)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 #[cfg(test)]
@@ -50,6 +50,22 @@ where
         let i = Self::hash(k);
         self.get_bucket(i).remove(k, cursor, cs)
     }
+
+    /// Checks each bucket and that its keys belong to it.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys = Vec::new();
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            let bucket_keys = bucket.validate().map_err(|e| format!("bucket {i}: {e}"))?;
+            if bucket_keys
+                .iter()
+                .any(|k| Self::hash(k) % self.buckets.len() != i)
+            {
+                return Err(format!("bucket {i} has a key of another bucket"));
+            }
+            keys.extend(bucket_keys);
+        }
+        Ok(keys)
+    }
 }
 
 impl<K, V, C> ConcurrentMap<K, V, C> for HashMap<K, V, C>
@@ -76,6 +92,10 @@ where
     fn remove(&self, key: &K, output: &mut Self::Output, cs: &C) -> bool {
         self.remove(key, output, cs)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,8 @@
-use std::{fmt::Display, sync::atomic::Ordering};
+use std::{collections::HashSet, fmt::Display, sync::atomic::Ordering};
 
 use cdrc::{AtomicRc, Cs, Pointer, Rc, Snapshot, StrongPtr, TaggedCnt};
 
-use super::concurrent_map::{ConcurrentMap, OutputHolder};
+use super::concurrent_map::{load_quiescent, ConcurrentMap, OutputHolder};
 
 const MAX_HEIGHT: usize = 32;
 
@@ -47,12 +47,12 @@ where
 
     fn generate_height() -> usize {
         // returns 1 with probability 3/4
-        if rand::random::<usize>() % 4 < 3 {
+        if crate::utils::random::<usize>() % 4 < 3 {
             return 1;
         }
         // returns h with probability 2^(−(h+1))
         let mut height = 2;
-        while height < MAX_HEIGHT && rand::random::<bool>() {
+        while height < MAX_HEIGHT && crate::utils::random::<bool>() {
             height += 1;
         }
         height
@@ -154,9 +154,49 @@ where
         }
     }
 
+    /// Checks that each level is sorted and only links the unmarked nodes of the lower levels
+    /// that are tall enough, and returns the keys of the unmarked nodes. Marked nodes that are not
+    /// unlinked yet may remain.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let head = load_quiescent(&self.head).unwrap();
+        let mut keys: Vec<&K> = Vec::new();
+        // The unmarked nodes of the level below.
+        let mut lower = HashSet::new();
+        for level in 0..MAX_HEIGHT {
+            let mut nodes = HashSet::new();
+            let mut last: Option<&K> = None;
+            let mut link = &head.next[level];
+            while let Some(node) = load_quiescent(link) {
+                if last.map_or(false, |last| *last > node.key) {
+                    return Err(format!("level {level} is out of order"));
+                }
+                last = Some(&node.key);
+                link = &node.next[level];
+                if node.next[0].load(Ordering::Acquire).tag() & 1 != 0 {
+                    continue;
+                }
+                if level >= node.height || (level > 0 && !lower.contains(&(node as *const _))) {
+                    return Err(format!("level {level} links a node not in the lower level"));
+                }
+                if level == 0 {
+                    if keys.last().map_or(false, |last| **last == node.key) {
+                        return Err("duplicate keys".to_string());
+                    }
+                    keys.push(&node.key);
+                }
+                nodes.insert(node as *const Node<K, V, C>);
+            }
+            lower = nodes;
+        }
+        Ok(keys)
+    }
+
     pub fn find_optimistic(&self, key: &K, cursor: &mut Cursor<K, V, C>, cs: &C) -> bool {
         cursor.found_level = None;
         cursor.preds[0].load(&self.head, cs);
+        // If the list is empty, the search below skips all levels and must not return the node
+        // found by the last operation.
+        cursor.succs[0].clear();
 
         let mut level = MAX_HEIGHT;
         while level >= 1
@@ -365,39 +405,41 @@ where
     }
 
     pub fn remove(&self, key: &K, cursor: &mut Cursor<K, V, C>, cs: &C) -> bool {
-        let found = self.find(key, cursor, cs);
-        if !found {
-            return false;
-        }
-        let height = unsafe { cursor.found().deref() }.height;
-        cursor.found_value = Some(unsafe { cursor.found().deref() }.value.clone());
-
-        // Try removing the node by marking its tower.
-        if unsafe { cursor.found().deref() }.mark_tower(cs) {
-            for level in (0..height).rev() {
-                cursor
-                    .next
-                    .load(&unsafe { cursor.found().deref() }.next[level], cs);
-                if (cursor.next.tag() & 2) != 0 {
-                    continue;
-                }
-                // Try linking the predecessor and successor at this level.
-                if unsafe { cursor.pred(level).deref() }.next[level]
-                    .compare_exchange(
-                        cursor.found().as_ptr(),
-                        cursor.next.with_tag(0),
-                        Ordering::SeqCst,
-                        Ordering::SeqCst,
-                        cs,
-                    )
-                    .is_err()
-                {
-                    self.find(key, cursor, cs);
-                    break;
+        loop {
+            let found = self.find(key, cursor, cs);
+            if !found {
+                return false;
+            }
+            let height = unsafe { cursor.found().deref() }.height;
+            cursor.found_value = Some(unsafe { cursor.found().deref() }.value.clone());
+
+            // Try removing the node by marking its tower.
+            if unsafe { cursor.found().deref() }.mark_tower(cs) {
+                for level in (0..height).rev() {
+                    cursor
+                        .next
+                        .load(&unsafe { cursor.found().deref() }.next[level], cs);
+                    if (cursor.next.tag() & 2) != 0 {
+                        continue;
+                    }
+                    // Try linking the predecessor and successor at this level.
+                    if unsafe { cursor.pred(level).deref() }.next[level]
+                        .compare_exchange(
+                            cursor.found().as_ptr(),
+                            cursor.next.with_tag(0),
+                            Ordering::SeqCst,
+                            Ordering::SeqCst,
+                            cs,
+                        )
+                        .is_err()
+                    {
+                        self.find(key, cursor, cs);
+                        break;
+                    }
                 }
+                return true;
             }
         }
-        true
     }
 }
 
@@ -428,13 +470,17 @@ where
     fn remove(&self, key: &K, output: &mut Self::Output, cs: &C) -> bool {
         self.remove(key, output, cs)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::SkipList;
-    use crate::ds_impl::cdrc::concurrent_map;
-    use cdrc::{CsEBR, CsHP};
+    use crate::ds_impl::cdrc::concurrent_map::{self, ConcurrentMap};
+    use cdrc::{Cs, CsEBR, CsHP};
 
     #[test]
     fn smoke_skip_list_ebr() {
@@ -445,4 +491,14 @@ mod tests {
     fn smoke_skip_list_hp() {
         concurrent_map::tests::smoke::<CsHP, SkipList<i32, String, CsHP>>();
     }
+
+    #[test]
+    fn find_optimistic_empty() {
+        let map = &SkipList::<i32, String, CsHP>::new();
+        let cursor = &mut SkipList::<i32, String, CsHP>::empty_output();
+        let cs = &CsHP::new();
+        assert!(map.insert(0, 0.to_string(), cursor, cs));
+        assert!(map.remove(&0, cursor, cs));
+        assert!(!map.find_optimistic(&0, cursor, cs));
+    }
 }
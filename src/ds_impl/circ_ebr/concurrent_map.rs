@@ -1,4 +1,4 @@
-use circ::CsEBR;
+use circ::{AtomicRc, Cs, CsEBR, GraphNode, Pointer, Snapshot, StrongPtr};
 
 pub trait OutputHolder<V> {
     fn output(&self) -> &V;
@@ -11,6 +11,22 @@ pub trait ConcurrentMap<K, V> {
     fn get(&self, key: &K, cs: &CsEBR) -> Option<Self::Output>;
     fn insert(&self, key: K, value: V, cs: &CsEBR) -> bool;
     fn remove(&self, key: &K, cs: &CsEBR) -> Option<Self::Output>;
+
+    /// Checks the structural invariants of the map and returns its keys. It must not run
+    /// concurrently with the other operations.
+    fn validate(&self) -> Result<Vec<&K>, String>;
+}
+
+/// Loads the node of `link` for a `validate`. As it does not run concurrently with the other
+/// operations, the node lives as long as `link`.
+pub(crate) fn load_quiescent<T: GraphNode<CsEBR>>(link: &AtomicRc<T, CsEBR>) -> Option<&T> {
+    let mut snapshot = Snapshot::new();
+    snapshot.load(link, unsafe { &CsEBR::unprotected() });
+    if snapshot.is_null() {
+        None
+    } else {
+        Some(unsafe { snapshot.deref() })
+    }
 }
 
 #[cfg(test)]
@@ -80,5 +96,13 @@ pub mod tests {
             }
         })
         .unwrap();
+
+        let mut keys = map.validate().unwrap();
+        keys.sort();
+        let mut expected = ((THREADS / 2)..THREADS)
+            .flat_map(|t| (0..ELEMENTS_PER_THREADS).map(move |k| k * THREADS + t))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert!(keys.into_iter().eq(expected.iter()));
     }
 }
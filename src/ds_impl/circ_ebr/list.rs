@@ -1,4 +1,4 @@
-use super::concurrent_map::{ConcurrentMap, OutputHolder};
+use super::concurrent_map::{load_quiescent, ConcurrentMap, OutputHolder};
 use circ::{AtomicRc, CsEBR, GraphNode, Pointer, Rc, Snapshot, StrongPtr};
 
 use std::cmp::Ordering::{Equal, Greater, Less};
@@ -256,6 +256,29 @@ where
         }
     }
 
+    /// Checks that the nodes are sorted and the unmarked ones have distinct keys, and returns the
+    /// keys of the unmarked nodes. Marked nodes that are not unlinked yet may remain.
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys: Vec<&K> = Vec::new();
+        let mut last: Option<&K> = None;
+        let head = load_quiescent(&self.head).unwrap();
+        let mut link = &head.next;
+        while let Some(curr_node) = load_quiescent(link) {
+            if last.map_or(false, |last| *last > curr_node.key) {
+                return Err(format!("the node #{} is out of order", keys.len()));
+            }
+            if curr_node.next.load(Ordering::Acquire).tag() == 0 {
+                if keys.last().map_or(false, |last| **last == curr_node.key) {
+                    return Err(format!("the node #{} has a duplicate key", keys.len()));
+                }
+                keys.push(&curr_node.key);
+            }
+            last = Some(&curr_node.key);
+            link = &curr_node.next;
+        }
+        Ok(keys)
+    }
+
     #[inline]
     fn get<F>(&self, key: &K, find: F, cs: &CsEBR) -> (Cursor<K, V>, bool)
     where
@@ -410,6 +433,10 @@ where
     fn remove(&self, key: &K, cs: &CsEBR) -> Option<Self::Output> {
         self.inner.harris_remove(key, cs)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HMList<K, V> {
@@ -455,6 +482,10 @@ where
     fn remove(&self, key: &K, cs: &CsEBR) -> Option<Self::Output> {
         self.inner.harris_michael_remove(key, cs)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HHSList<K, V> {
@@ -496,6 +527,10 @@ where
     fn remove(&self, key: &K, cs: &CsEBR) -> Option<Self::Output> {
         self.inner.harris_remove(key, cs)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,8 @@
-use std::{fmt::Display, mem::forget, sync::atomic::Ordering};
+use std::{collections::HashSet, fmt::Display, mem::forget, sync::atomic::Ordering};
 
 use circ::{AtomicRc, Cs, CsEBR, GraphNode, Pointer, Rc, Snapshot, StrongPtr};
 
-use super::concurrent_map::{ConcurrentMap, OutputHolder};
+use super::concurrent_map::{load_quiescent, ConcurrentMap, OutputHolder};
 
 const MAX_HEIGHT: usize = 32;
 
@@ -73,12 +73,12 @@ where
 
     fn generate_height() -> usize {
         // returns 1 with probability 3/4
-        if rand::random::<usize>() % 4 < 3 {
+        if crate::utils::random::<usize>() % 4 < 3 {
             return 1;
         }
         // returns h with probability 2^(−(h+1))
         let mut height = 2;
-        while height < MAX_HEIGHT && rand::random::<bool>() {
+        while height < MAX_HEIGHT && crate::utils::random::<bool>() {
             height += 1;
         }
         height
@@ -166,6 +166,43 @@ where
         }
     }
 
+    /// Checks that each level is sorted and only links the unmarked nodes of the lower levels
+    /// that are tall enough, and returns the keys of the unmarked nodes. Marked nodes that are not
+    /// unlinked yet may remain.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let head = load_quiescent(&self.head).unwrap();
+        let mut keys: Vec<&K> = Vec::new();
+        // The unmarked nodes of the level below.
+        let mut lower = HashSet::new();
+        for level in 0..MAX_HEIGHT {
+            let mut nodes = HashSet::new();
+            let mut last: Option<&K> = None;
+            let mut link = &head.next[level];
+            while let Some(node) = load_quiescent(link) {
+                if last.map_or(false, |last| *last > node.key) {
+                    return Err(format!("level {level} is out of order"));
+                }
+                last = Some(&node.key);
+                link = &node.next[level];
+                if node.next[0].load(Ordering::Acquire).tag() & 1 != 0 {
+                    continue;
+                }
+                if level >= node.height || (level > 0 && !lower.contains(&(node as *const _))) {
+                    return Err(format!("level {level} links a node not in the lower level"));
+                }
+                if level == 0 {
+                    if keys.last().map_or(false, |last| **last == node.key) {
+                        return Err("duplicate keys".to_string());
+                    }
+                    keys.push(&node.key);
+                }
+                nodes.insert(node as *const Node<K, V>);
+            }
+            lower = nodes;
+        }
+        Ok(keys)
+    }
+
     fn find_optimistic(&self, key: &K, cs: &CsEBR) -> Option<Snapshot<Node<K, V>, CsEBR>> {
         let mut pred = self.head.load_ss(cs);
         let mut level = MAX_HEIGHT;
@@ -438,6 +475,10 @@ where
     fn remove(&self, key: &K, cs: &CsEBR) -> Option<Self::Output> {
         self.remove(key, cs)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
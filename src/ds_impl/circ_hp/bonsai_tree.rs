@@ -1,6 +1,6 @@
 use circ::{AtomicRc, CsHP, GraphNode, Pointer, Rc, Snapshot, StrongPtr, TaggedCnt};
 
-use super::concurrent_map::{ConcurrentMap, OutputHolder};
+use super::concurrent_map::{load_quiescent, ConcurrentMap, OutputHolder};
 
 use std::{cmp, sync::atomic::Ordering};
 
@@ -621,6 +621,39 @@ where
         }
     }
 
+    /// Checks that the tree is a BST with correct sizes and without retired nodes, and returns its
+    /// keys in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        /// Checks the subtree at `link` whose keys are in `(lo, hi)`, and returns its size.
+        fn check<'g, K: Ord + Clone, V: Clone>(
+            link: &'g AtomicRc<Node<K, V>, CsHP>,
+            lo: Option<&K>,
+            hi: Option<&K>,
+            keys: &mut Vec<&'g K>,
+        ) -> Result<usize, String> {
+            if Node::is_retired(link.load(Ordering::Acquire)) {
+                return Err("a retired node remains".to_string());
+            }
+            let node_ref = some_or!(load_quiescent(link), return Ok(0));
+            if lo.map_or(false, |lo| node_ref.key <= *lo)
+                || hi.map_or(false, |hi| node_ref.key >= *hi)
+            {
+                return Err("a node is in the wrong subtree".to_string());
+            }
+            let left_size = check(&node_ref.left, lo, Some(&node_ref.key), keys)?;
+            keys.push(&node_ref.key);
+            let right_size = check(&node_ref.right, Some(&node_ref.key), hi, keys)?;
+            if node_ref.size != left_size + right_size + 1 {
+                return Err("a node has a wrong size".to_string());
+            }
+            Ok(node_ref.size)
+        }
+
+        let mut keys = Vec::new();
+        check(&self.root, None, None, &mut keys)?;
+        Ok(keys)
+    }
+
     pub fn get(&self, key: &K, holder: &mut Holder<K, V>, cs: &CsHP) -> bool {
         loop {
             // NOTE: In this context, `holder.curr` and `holder.temp` is similar
@@ -727,6 +760,10 @@ where
     fn remove(&self, key: &K, output: &mut Self::Output, cs: &CsHP) -> bool {
         self.remove(key, output, cs)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,633 @@
+use std::sync::atomic::Ordering;
+
+use std::mem;
+
+use circ::{AtomicRc, CsHP, GraphNode, Pointer, Rc, Snapshot, StrongPtr, TaggedCnt, Weak};
+
+use super::concurrent_map::{load_quiescent, ConcurrentMap, OutputHolder};
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    struct UpdateTag: usize {
+        const CLEAN = 0usize;
+        const DFLAG = 1usize;
+        const IFLAG = 2usize;
+        const MARK = 3usize;
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Key<K> {
+    Fin(K),
+    Inf1,
+    Inf2,
+}
+
+impl<K> PartialOrd for Key<K>
+where
+    K: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (Key::Fin(k1), Key::Fin(k2)) => k1.partial_cmp(k2),
+            (Key::Fin(_), Key::Inf1) => Some(std::cmp::Ordering::Less),
+            (Key::Fin(_), Key::Inf2) => Some(std::cmp::Ordering::Less),
+            (Key::Inf1, Key::Fin(_)) => Some(std::cmp::Ordering::Greater),
+            (Key::Inf1, Key::Inf1) => Some(std::cmp::Ordering::Equal),
+            (Key::Inf1, Key::Inf2) => Some(std::cmp::Ordering::Less),
+            (Key::Inf2, Key::Fin(_)) => Some(std::cmp::Ordering::Greater),
+            (Key::Inf2, Key::Inf1) => Some(std::cmp::Ordering::Greater),
+            (Key::Inf2, Key::Inf2) => Some(std::cmp::Ordering::Equal),
+        }
+    }
+}
+
+impl<K> PartialEq<K> for Key<K>
+where
+    K: PartialEq,
+{
+    fn eq(&self, rhs: &K) -> bool {
+        match self {
+            Key::Fin(k) => k == rhs,
+            _ => false,
+        }
+    }
+}
+
+impl<K> PartialOrd<K> for Key<K>
+where
+    K: PartialOrd,
+{
+    fn partial_cmp(&self, rhs: &K) -> Option<std::cmp::Ordering> {
+        match self {
+            Key::Fin(k) => k.partial_cmp(rhs),
+            _ => Some(std::cmp::Ordering::Greater),
+        }
+    }
+}
+
+impl<K> Key<K>
+where
+    K: Ord,
+{
+    fn cmp(&self, rhs: &K) -> std::cmp::Ordering {
+        match self {
+            Key::Fin(k) => k.cmp(rhs),
+            _ => std::cmp::Ordering::Greater,
+        }
+    }
+}
+
+pub struct Node<K, V> {
+    key: Key<K>,
+    value: Option<V>,
+    // tag on low bits: {Clean, DFlag, IFlag, Mark}
+    update: AtomicRc<Update<K, V>, CsHP>,
+    left: AtomicRc<Node<K, V>, CsHP>,
+    right: AtomicRc<Node<K, V>, CsHP>,
+}
+
+impl<K, V> GraphNode<CsHP> for Node<K, V> {
+    const UNIQUE_OUTDEGREE: bool = false;
+
+    #[inline]
+    fn pop_outgoings(&mut self, result: &mut Vec<Rc<Self, CsHP>>)
+    where
+        Self: Sized,
+    {
+        result.push(self.left.swap(Rc::null(), Ordering::Relaxed));
+        result.push(self.right.swap(Rc::null(), Ordering::Relaxed));
+    }
+
+    #[inline]
+    fn pop_unique(&mut self) -> Rc<Self, CsHP>
+    where
+        Self: Sized,
+    {
+        unimplemented!()
+    }
+}
+
+/// The Info record of an update.
+///
+/// Note: An Info record is installed in the `update` field of `p` (and `gp`), so it refers to them
+/// with weak pointers. Otherwise, they would make a reference cycle and never be reclaimed.
+pub enum Update<K, V> {
+    Insert {
+        p: Weak<Node<K, V>, CsHP>,
+        new_internal: Rc<Node<K, V>, CsHP>,
+        l: Rc<Node<K, V>, CsHP>,
+    },
+    Delete {
+        gp: Weak<Node<K, V>, CsHP>,
+        p: Weak<Node<K, V>, CsHP>,
+        l: Rc<Node<K, V>, CsHP>,
+        pupdate: Rc<Update<K, V>, CsHP>,
+    },
+}
+
+impl<K, V> GraphNode<CsHP> for Update<K, V> {
+    const UNIQUE_OUTDEGREE: bool = false;
+
+    #[inline]
+    fn pop_outgoings(&mut self, result: &mut Vec<Rc<Self, CsHP>>)
+    where
+        Self: Sized,
+    {
+        if let Update::Delete { pupdate, .. } = self {
+            result.push(mem::take(pupdate));
+        }
+    }
+
+    #[inline]
+    fn pop_unique(&mut self) -> Rc<Self, CsHP>
+    where
+        Self: Sized,
+    {
+        unimplemented!()
+    }
+}
+
+impl<K, V> Node<K, V> {
+    pub fn internal(key: Key<K>, value: Option<V>, left: Self, right: Self) -> Self {
+        Self {
+            key,
+            value,
+            update: AtomicRc::null(),
+            left: AtomicRc::new(left),
+            right: AtomicRc::new(right),
+        }
+    }
+
+    pub fn leaf(key: Key<K>, value: Option<V>) -> Self {
+        Self {
+            key,
+            value,
+            update: AtomicRc::null(),
+            left: AtomicRc::null(),
+            right: AtomicRc::null(),
+        }
+    }
+
+    #[inline]
+    pub fn is_leaf(&self) -> bool {
+        self.left.load(Ordering::Acquire).is_null()
+    }
+}
+
+/// The nodes protected while helping an update.
+struct HelpRecord<K, V> {
+    gp: Snapshot<Node<K, V>, CsHP>,
+    p: Snapshot<Node<K, V>, CsHP>,
+    /// The sibling of `l`, which replaces `p`.
+    other: Snapshot<Node<K, V>, CsHP>,
+}
+
+pub struct Cursor<K, V> {
+    gp: Snapshot<Node<K, V>, CsHP>,
+    p: Snapshot<Node<K, V>, CsHP>,
+    l: Snapshot<Node<K, V>, CsHP>,
+    pupdate: Snapshot<Update<K, V>, CsHP>,
+    gpupdate: Snapshot<Update<K, V>, CsHP>,
+    /// The update that this thread tries to install.
+    op: Snapshot<Update<K, V>, CsHP>,
+    /// The found node for Get and Remove operation.
+    found: Snapshot<Node<K, V>, CsHP>,
+    help: HelpRecord<K, V>,
+}
+
+impl<K, V> OutputHolder<V> for Cursor<K, V> {
+    fn default() -> Self {
+        Self {
+            gp: Snapshot::new(),
+            p: Snapshot::new(),
+            l: Snapshot::new(),
+            pupdate: Snapshot::new(),
+            gpupdate: Snapshot::new(),
+            op: Snapshot::new(),
+            found: Snapshot::new(),
+            help: HelpRecord {
+                gp: Snapshot::new(),
+                p: Snapshot::new(),
+                other: Snapshot::new(),
+            },
+        }
+    }
+
+    fn output(&self) -> &V {
+        unsafe { self.found.deref() }.value.as_ref().unwrap()
+    }
+}
+
+impl<K, V> Cursor<K, V>
+where
+    K: Ord,
+{
+    /// Used by Insert, Delete and Find to traverse a branch of the BST.
+    ///
+    /// # Safety
+    /// It satisfies following postconditions:
+    ///
+    /// 1. l points to a Leaf node and p points to an Internal node
+    /// 2. Either p → left has contained l (if k<p → key) or p → right has contained l (if k ≥ p → key)
+    /// 3. p → update has contained pupdate
+    /// 4. if l → key != Inf1, then the following three statements hold:
+    ///     - gp points to an Internal node
+    ///     - either gp → left has contained p (if k < gp → key) or gp → right has contained p (if k ≥ gp → key)
+    ///     - gp → update has contained gpupdate
+    #[inline]
+    fn search(&mut self, root: &AtomicRc<Node<K, V>, CsHP>, key: &K, cs: &CsHP) {
+        self.gp.clear();
+        self.p.clear();
+        self.pupdate.clear();
+        self.gpupdate.clear();
+        self.l.load(root, cs);
+        loop {
+            // Safety of deref: Even if `self.l` is mutated by `swap`, `l_node` is protected by
+            // `self.p`.
+            let l_node = unsafe { self.l.deref() };
+            if l_node.is_leaf() {
+                break;
+            }
+            Snapshot::swap(&mut self.gp, &mut self.p);
+            Snapshot::swap(&mut self.p, &mut self.l);
+            Snapshot::swap(&mut self.gpupdate, &mut self.pupdate);
+            self.pupdate.load(&l_node.update, cs);
+            match l_node.key.cmp(key) {
+                std::cmp::Ordering::Greater => self.l.load(&l_node.left, cs),
+                _ => self.l.load(&l_node.right, cs),
+            }
+        }
+    }
+}
+
+pub struct EFRBTree<K, V> {
+    root: AtomicRc<Node<K, V>, CsHP>,
+}
+
+impl<K, V> Default for EFRBTree<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> EFRBTree<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            root: AtomicRc::new(Node::internal(
+                Key::Inf2,
+                None,
+                Node::leaf(Key::Inf1, None),
+                Node::leaf(Key::Inf2, None),
+            )),
+        }
+    }
+
+    /// Checks that the tree is a leaf-oriented BST whose internal nodes are all clean, and returns
+    /// the keys of its leaves in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys = Vec::new();
+        // Edges to the nodes with the bounds of the keys of their leaves, in the order of a DFS.
+        let mut stack = vec![(&self.root, None, None)];
+        while let Some((link, lo, hi)) = stack.pop() {
+            let node_ref = load_quiescent(link).unwrap();
+            let left = node_ref.left.load(Ordering::Acquire);
+            let right = node_ref.right.load(Ordering::Acquire);
+            match (left.is_null(), right.is_null()) {
+                (true, true) => {
+                    if let Key::Fin(key) = &node_ref.key {
+                        if lo.map_or(false, |lo: &Key<K>| node_ref.key < *lo)
+                            || hi.map_or(false, |hi: &Key<K>| node_ref.key >= *hi)
+                        {
+                            return Err("a leaf is in the wrong subtree".to_string());
+                        }
+                        keys.push(key);
+                    }
+                }
+                (false, false) => {
+                    let update = node_ref.update.load(Ordering::Acquire);
+                    if UpdateTag::from_bits_truncate(update.tag()) != UpdateTag::CLEAN {
+                        return Err("an internal node is not clean".to_string());
+                    }
+                    stack.push((&node_ref.right, Some(&node_ref.key), hi));
+                    stack.push((&node_ref.left, lo, Some(&node_ref.key)));
+                }
+                _ => return Err("an internal node has a single child".to_string()),
+            }
+        }
+        Ok(keys)
+    }
+
+    pub fn find(&self, key: &K, cursor: &mut Cursor<K, V>, cs: &CsHP) -> bool {
+        cursor.search(&self.root, key, cs);
+        Snapshot::swap(&mut cursor.l, &mut cursor.found);
+        unsafe { cursor.found.deref() }.key == *key
+    }
+
+    pub fn insert(&self, key: &K, value: V, cursor: &mut Cursor<K, V>, cs: &CsHP) -> bool {
+        loop {
+            cursor.search(&self.root, key, cs);
+            let l_node = unsafe { cursor.l.deref() };
+            let p_node = unsafe { cursor.p.deref() };
+
+            if l_node.key == *key {
+                return false;
+            } else if cursor.pupdate.tag() != UpdateTag::CLEAN.bits() {
+                self.help(&cursor.pupdate, &mut cursor.help, cs);
+            } else {
+                let new = Node::leaf(Key::Fin(key.clone()), Some(value.clone()));
+                let new_sibling = Node::leaf(l_node.key.clone(), l_node.value.clone());
+
+                let (left, right) = match new.key.partial_cmp(&new_sibling.key) {
+                    Some(std::cmp::Ordering::Less) => (new, new_sibling),
+                    _ => (new_sibling, new),
+                };
+
+                let new_internal = Rc::new(Node::internal(
+                    // key field max(k, l → key)
+                    right.key.clone(),
+                    None,
+                    // two child fields equal to new and newSibling
+                    // (the one with the smaller key is the left child)
+                    left,
+                    right,
+                ));
+
+                let op = Rc::new(Update::Insert {
+                    p: cursor.p.downgrade(),
+                    new_internal,
+                    l: cursor.l.upgrade(),
+                })
+                .with_tag(UpdateTag::IFLAG.bits());
+                cursor.op.protect(&op, cs);
+
+                // On a failure, the new Info record is dropped and the search is retried, which
+                // helps the conflicting update.
+                if p_node
+                    .update
+                    .compare_exchange(
+                        cursor.pupdate.as_ptr(),
+                        op,
+                        Ordering::Release,
+                        Ordering::Relaxed,
+                        cs,
+                    )
+                    .is_ok()
+                {
+                    self.help_insert(&cursor.op, &mut cursor.help, cs);
+                    return true;
+                }
+            }
+        }
+    }
+
+    pub fn delete(&self, key: &K, cursor: &mut Cursor<K, V>, cs: &CsHP) -> bool {
+        loop {
+            cursor.search(&self.root, key, cs);
+
+            if cursor.gp.is_null() {
+                // The tree is empty. There's no more things to do.
+                return false;
+            }
+
+            let l_node = unsafe { cursor.l.deref() };
+            if l_node.key != *key {
+                return false;
+            }
+            if cursor.gpupdate.tag() != UpdateTag::CLEAN.bits() {
+                self.help(&cursor.gpupdate, &mut cursor.help, cs);
+            } else if cursor.pupdate.tag() != UpdateTag::CLEAN.bits() {
+                self.help(&cursor.pupdate, &mut cursor.help, cs);
+            } else {
+                let op = Rc::new(Update::Delete {
+                    gp: cursor.gp.downgrade(),
+                    p: cursor.p.downgrade(),
+                    l: cursor.l.upgrade(),
+                    pupdate: cursor.pupdate.upgrade(),
+                })
+                .with_tag(UpdateTag::DFLAG.bits());
+                cursor.op.protect(&op, cs);
+
+                if unsafe { cursor.gp.deref() }
+                    .update
+                    .compare_exchange(
+                        cursor.gpupdate.as_ptr(),
+                        op,
+                        Ordering::Release,
+                        Ordering::Relaxed,
+                        cs,
+                    )
+                    .is_ok()
+                    && self.help_delete(&cursor.op, &mut cursor.help, cs)
+                {
+                    Snapshot::swap(&mut cursor.l, &mut cursor.found);
+                    return true;
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn help(&self, update: &Snapshot<Update<K, V>, CsHP>, help: &mut HelpRecord<K, V>, cs: &CsHP) {
+        match UpdateTag::from_bits_truncate(update.tag()) {
+            UpdateTag::IFLAG => self.help_insert(update, help, cs),
+            UpdateTag::MARK => self.help_marked(update, help, cs),
+            UpdateTag::DFLAG => {
+                let _ = self.help_delete(update, help, cs);
+            }
+            _ => {}
+        }
+    }
+
+    fn help_delete(
+        &self,
+        op: &Snapshot<Update<K, V>, CsHP>,
+        help: &mut HelpRecord<K, V>,
+        cs: &CsHP,
+    ) -> bool {
+        // Precondition: op points to a DInfo record (i.e., it is not ⊥)
+        let Update::Delete { gp, p, pupdate, .. } = (unsafe { op.deref() }) else {
+            panic!("op is not pointing to a DInfo record")
+        };
+        // The deleter protects p, so only a helper, which ignores the result, may find it
+        // reclaimed. Then, op is already finished.
+        if !help.p.protect_weak(p, cs) {
+            return false;
+        }
+        let new_op = op
+            .as_ptr()
+            .with_high_tag(0)
+            .with_tag(UpdateTag::MARK.bits());
+
+        match unsafe { help.p.deref() }.update.compare_exchange(
+            pupdate.as_ptr(),
+            op.upgrade().with_tag(UpdateTag::MARK.bits()),
+            Ordering::Release,
+            Ordering::Acquire,
+            cs,
+        ) {
+            Ok(_) => {
+                // (prev value) = op → pupdate
+                self.help_marked(op, help, cs);
+                true
+            }
+            Err(e) => {
+                if e.current.with_high_tag(0) == new_op {
+                    // (prev value) = <Mark, op>
+                    self.help_marked(op, help, cs);
+                    true
+                } else {
+                    // The conflicting update is not protected, so it is left to the retry of the
+                    // deleter.
+                    if help.gp.protect_weak(gp, cs) {
+                        let _ = unsafe { help.gp.deref() }.update.compare_exchange(
+                            op.as_ptr().with_tag(UpdateTag::DFLAG.bits()),
+                            op.upgrade().with_tag(UpdateTag::CLEAN.bits()),
+                            Ordering::Release,
+                            Ordering::Relaxed,
+                            cs,
+                        );
+                    }
+                    false
+                }
+            }
+        }
+    }
+
+    fn help_marked(
+        &self,
+        op: &Snapshot<Update<K, V>, CsHP>,
+        help: &mut HelpRecord<K, V>,
+        cs: &CsHP,
+    ) {
+        // Precondition: op points to a DInfo record (i.e., it is not ⊥)
+        let Update::Delete { gp, p, l, .. } = (unsafe { op.deref() }) else {
+            panic!("op is not pointing to a DInfo record")
+        };
+        if !help.gp.protect_weak(gp, cs) {
+            // gp is already removed from the tree, so is p.
+            return;
+        }
+        let gp_node = unsafe { help.gp.deref() };
+
+        // If p is reclaimed, it is already spliced out.
+        if help.p.protect_weak(p, cs) {
+            // Set other to point to the sibling of the node to which op → l points
+            let p_node = unsafe { help.p.deref() };
+            let other = if p_node.right.load(Ordering::Acquire).with_high_tag(0)
+                == l.as_ptr().with_high_tag(0)
+            {
+                &p_node.left
+            } else {
+                &p_node.right
+            };
+            // Splice the node to which op → p points out of the tree, replacing it by other
+            help.other.load(other, cs);
+            self.cas_child(gp_node, help.p.as_ptr(), help.other.upgrade(), cs);
+        }
+        let _ = gp_node.update.compare_exchange(
+            op.as_ptr().with_tag(UpdateTag::DFLAG.bits()),
+            op.upgrade().with_tag(UpdateTag::CLEAN.bits()),
+            Ordering::Release,
+            Ordering::Relaxed,
+            cs,
+        );
+    }
+
+    fn help_insert(
+        &self,
+        op: &Snapshot<Update<K, V>, CsHP>,
+        help: &mut HelpRecord<K, V>,
+        cs: &CsHP,
+    ) {
+        // Precondition: op points to an IInfo record (i.e., it is not ⊥)
+        let Update::Insert { p, new_internal, l } = (unsafe { op.deref() }) else {
+            panic!("op is not pointing to an IInfo record")
+        };
+        // p is flagged until op is finished, so a reclaimed p means that op is already finished.
+        if !help.p.protect_weak(p, cs) {
+            return;
+        }
+        let p_node = unsafe { help.p.deref() };
+
+        self.cas_child(p_node, l.as_ptr(), new_internal.clone(), cs);
+        let _ = p_node.update.compare_exchange(
+            op.as_ptr().with_tag(UpdateTag::IFLAG.bits()),
+            op.upgrade().with_tag(UpdateTag::CLEAN.bits()),
+            Ordering::Release,
+            Ordering::Relaxed,
+            cs,
+        );
+    }
+
+    #[inline]
+    fn cas_child(
+        &self,
+        parent: &Node<K, V>,
+        old: TaggedCnt<Node<K, V>>,
+        new: Rc<Node<K, V>, CsHP>,
+        cs: &CsHP,
+    ) -> bool {
+        // Precondition: parent points to an Internal node and new points to a Node (i.e., neither is ⊥)
+        // This routine tries to change one of the child fields of the node that parent points to from old to new.
+        let new_node = unsafe { new.deref() };
+        let node_to_cas = if new_node.key < parent.key {
+            &parent.left
+        } else {
+            &parent.right
+        };
+        node_to_cas
+            .compare_exchange(old, new, Ordering::Release, Ordering::Acquire, cs)
+            .is_ok()
+    }
+}
+
+impl<K, V> ConcurrentMap<K, V> for EFRBTree<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    type Output = Cursor<K, V>;
+
+    fn new() -> Self {
+        EFRBTree::new()
+    }
+
+    #[inline(always)]
+    fn get(&self, key: &K, output: &mut Self::Output, cs: &CsHP) -> bool {
+        self.find(key, output, cs)
+    }
+
+    #[inline(always)]
+    fn insert(&self, key: K, value: V, output: &mut Self::Output, cs: &CsHP) -> bool {
+        self.insert(&key, value, output, cs)
+    }
+
+    #[inline(always)]
+    fn remove(&self, key: &K, output: &mut Self::Output, cs: &CsHP) -> bool {
+        self.delete(key, output, cs)
+    }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EFRBTree;
+    use crate::ds_impl::circ_hp::concurrent_map;
+
+    #[test]
+    fn smoke_efrb_tree() {
+        concurrent_map::tests::smoke::<EFRBTree<i32, String>>();
+    }
+}
@@ -2,6 +2,7 @@ pub mod concurrent_map;
 
 pub mod bonsai_tree;
 pub mod double_link;
+pub mod ellen_tree;
 pub mod list;
 pub mod michael_hash_map;
 pub mod natarajan_mittal_tree;
@@ -11,6 +12,7 @@ pub use self::concurrent_map::{ConcurrentMap, OutputHolder};
 
 pub use self::bonsai_tree::BonsaiTreeMap;
 pub use self::double_link::DoubleLink;
+pub use self::ellen_tree::EFRBTree;
 pub use self::list::{HHSList, HList, HMList};
 pub use self::michael_hash_map::HashMap;
 pub use self::natarajan_mittal_tree::NMTreeMap;
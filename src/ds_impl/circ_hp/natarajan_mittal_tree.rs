@@ -1,6 +1,6 @@
 use circ::{AtomicRc, CsHP, GraphNode, Pointer, Rc, Snapshot, StrongPtr};
 
-use super::concurrent_map::{ConcurrentMap, OutputHolder};
+use super::concurrent_map::{load_quiescent, ConcurrentMap, OutputHolder};
 use std::cmp;
 use std::mem::swap;
 use std::sync::atomic::Ordering;
@@ -243,6 +243,40 @@ where
         }
     }
 
+    /// Checks that the tree is a leaf-oriented BST without marked edges, and returns the keys of
+    /// its leaves in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys = Vec::new();
+        // Edges to the nodes with the bounds of the keys of their leaves, in the order of a DFS.
+        let mut stack = vec![(&self.r, None, None)];
+        while let Some((link, lo, hi)) = stack.pop() {
+            if link.load(Ordering::Acquire).tag() != 0 {
+                return Err("a marked edge remains".to_string());
+            }
+            let node_ref = load_quiescent(link).unwrap();
+            let left = node_ref.left.load(Ordering::Acquire);
+            let right = node_ref.right.load(Ordering::Acquire);
+            match (left.is_null(), right.is_null()) {
+                (true, true) => {
+                    if let Key::Fin(key) = &node_ref.key {
+                        if lo.map_or(false, |lo: &Key<K>| node_ref.key < *lo)
+                            || hi.map_or(false, |hi: &Key<K>| node_ref.key >= *hi)
+                        {
+                            return Err("a leaf is in the wrong subtree".to_string());
+                        }
+                        keys.push(key);
+                    }
+                }
+                (false, false) => {
+                    stack.push((&node_ref.right, Some(&node_ref.key), hi));
+                    stack.push((&node_ref.left, lo, Some(&node_ref.key)));
+                }
+                _ => return Err("an internal node has a single child".to_string()),
+            }
+        }
+        Ok(keys)
+    }
+
     // All `Shared<_>` fields are unmarked.
     fn seek(&self, key: &K, record: &mut SeekRecord<K, V>, cs: &CsHP) {
         record.ancestor.load(&self.r, cs);
@@ -542,6 +576,10 @@ where
     fn remove<'g>(&'g self, key: &K, output: &mut Self::Output, cs: &'g CsHP) -> bool {
         self.remove(key, output, cs)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
@@ -601,6 +601,43 @@ where
         }
     }
 
+    /// Checks that the tree is a BST with correct sizes and without retired nodes, and returns its
+    /// keys in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        /// Checks the subtree at `node` whose keys are in `(lo, hi)`, and returns its size.
+        fn check<'g, K: Ord + Clone, V: Clone>(
+            node: Shared<'g, Node<K, V>>,
+            lo: Option<&K>,
+            hi: Option<&K>,
+            keys: &mut Vec<&'g K>,
+        ) -> Result<usize, String> {
+            if Node::is_retired(node) {
+                return Err("a retired node remains".to_string());
+            }
+            let node_ref = some_or!(unsafe { node.as_ref() }, return Ok(0));
+            if lo.map_or(false, |lo| node_ref.key <= *lo)
+                || hi.map_or(false, |hi| node_ref.key >= *hi)
+            {
+                return Err("a node is in the wrong subtree".to_string());
+            }
+            let guard = unsafe { unprotected() };
+            let left = node_ref.left.load(Ordering::Acquire, guard);
+            let right = node_ref.right.load(Ordering::Acquire, guard);
+            let left_size = check(left, lo, Some(&node_ref.key), keys)?;
+            keys.push(&node_ref.key);
+            let right_size = check(right, Some(&node_ref.key), hi, keys)?;
+            if node_ref.size != left_size + right_size + 1 {
+                return Err("a node has a wrong size".to_string());
+            }
+            Ok(node_ref.size)
+        }
+
+        let mut keys = Vec::new();
+        let root = self.root.load(Ordering::Acquire, unsafe { unprotected() });
+        check(root, None, None, &mut keys)?;
+        Ok(keys)
+    }
+
     pub fn get<'g>(&self, key: &'g K, guard: &'g Guard) -> Option<&'g V> {
         loop {
             let mut node = self.root.load(Ordering::Acquire, guard);
@@ -731,6 +768,9 @@ where
     fn remove<'g>(&'g self, key: &K, guard: &'g Guard) -> Option<&'g V> {
         self.remove(key, guard)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
@@ -5,6 +5,10 @@ pub trait ConcurrentMap<K, V> {
     fn get<'g>(&'g self, key: &'g K, guard: &'g Guard) -> Option<&'g V>;
     fn insert(&self, key: K, value: V, guard: &Guard) -> bool;
     fn remove<'g>(&'g self, key: &'g K, guard: &'g Guard) -> Option<&'g V>;
+
+    /// Checks the structural invariants of the map and returns its keys. It must not run
+    /// concurrently with the other operations.
+    fn validate(&self) -> Result<Vec<&K>, String>;
 }
 
 #[cfg(test)]
@@ -65,5 +69,13 @@ pub mod tests {
             }
         })
         .unwrap();
+
+        let mut keys = map.validate().unwrap();
+        keys.sort();
+        let mut expected = ((THREADS / 2)..THREADS)
+            .flat_map(|t| (0..ELEMENTS_PER_THREADS).map(move |k| k * THREADS + t))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert!(keys.into_iter().eq(expected.iter()));
     }
 }
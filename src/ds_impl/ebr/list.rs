@@ -348,6 +348,31 @@ where
         }
     }
 
+    /// Checks that the nodes are sorted and the unmarked ones have distinct keys, and returns the
+    /// keys of the unmarked nodes. Marked nodes that are not unlinked yet may remain.
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        let guard = unsafe { unprotected() };
+        let mut keys: Vec<&K> = Vec::new();
+        let mut last: Option<&K> = None;
+        let head = unsafe { self.head.load(Ordering::Acquire, guard).deref() };
+        let mut curr = head.next.load(Ordering::Acquire, guard);
+        while let Some(curr_node) = unsafe { curr.with_tag(0).as_ref() } {
+            if last.map_or(false, |last| *last > curr_node.key) {
+                return Err(format!("the node #{} is out of order", keys.len()));
+            }
+            let next = curr_node.next.load(Ordering::Acquire, guard);
+            if next.tag() == 0 {
+                if keys.last().map_or(false, |last| **last == curr_node.key) {
+                    return Err(format!("the node #{} has a duplicate key", keys.len()));
+                }
+                keys.push(&curr_node.key);
+            }
+            last = Some(&curr_node.key);
+            curr = next;
+        }
+        Ok(keys)
+    }
+
     #[inline]
     pub fn harris_get<'g>(&'g self, key: &K, guard: &'g Guard) -> Option<&'g V> {
         self.get(key, Self::find_harris, guard)
@@ -409,6 +434,9 @@ where
     fn remove<'g>(&'g self, key: &K, guard: &'g Guard) -> Option<&'g V> {
         self.inner.harris_remove(key, guard)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HMList<K, V> {
@@ -436,6 +464,9 @@ where
     fn remove<'g>(&'g self, key: &K, guard: &'g Guard) -> Option<&'g V> {
         self.inner.harris_michael_remove(key, guard)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HHSList<K, V> {
@@ -475,6 +506,9 @@ where
     fn remove<'g>(&'g self, key: &K, guard: &'g Guard) -> Option<&'g V> {
         self.inner.harris_remove(key, guard)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 #[cfg(test)]
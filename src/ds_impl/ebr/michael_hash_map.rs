@@ -49,6 +49,22 @@ where
         let i = Self::hash(k);
         self.get_bucket(i).remove(k, guard)
     }
+
+    /// Checks each bucket and that its keys belong to it.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys = Vec::new();
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            let bucket_keys = bucket.validate().map_err(|e| format!("bucket {i}: {e}"))?;
+            if bucket_keys
+                .iter()
+                .any(|k| Self::hash(k) % self.buckets.len() != i)
+            {
+                return Err(format!("bucket {i} has a key of another bucket"));
+            }
+            keys.extend(bucket_keys);
+        }
+        Ok(keys)
+    }
 }
 
 impl<K, V> ConcurrentMap<K, V> for HashMap<K, V>
@@ -72,6 +88,9 @@ where
     fn remove<'g>(&'g self, key: &'g K, guard: &'g Guard) -> Option<&'g V> {
         self.remove(key, guard)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
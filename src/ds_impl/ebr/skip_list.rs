@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::sync::atomic::{fence, AtomicUsize, Ordering};
 
 use crossbeam_ebr::{unprotected, Atomic, Guard, Owned, Shared};
@@ -34,12 +35,12 @@ impl<K, V> Node<K, V> {
 
     fn generate_height() -> usize {
         // returns 1 with probability 3/4
-        if rand::random::<usize>() % 4 < 3 {
+        if crate::utils::random::<usize>() % 4 < 3 {
             return 1;
         }
         // returns h with probability 2^(−(h+1))
         let mut height = 2;
-        while height < MAX_HEIGHT && rand::random::<bool>() {
+        while height < MAX_HEIGHT && crate::utils::random::<bool>() {
             height += 1;
         }
         height
@@ -266,6 +267,44 @@ where
         success
     }
 
+    /// Checks that each level is sorted and only links the unmarked nodes of the lower levels
+    /// that are tall enough, and returns the keys of the unmarked nodes. Marked nodes that are not
+    /// unlinked yet may remain.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let guard = unsafe { unprotected() };
+        let head = unsafe { self.head.load(Ordering::Acquire, guard).deref() };
+        let mut keys: Vec<&K> = Vec::new();
+        // The unmarked nodes of the level below.
+        let mut lower = HashSet::new();
+        for level in 0..MAX_HEIGHT {
+            let mut nodes = HashSet::new();
+            let mut last: Option<&K> = None;
+            let mut curr = head.next[level].load(Ordering::Acquire, guard);
+            while let Some(node) = unsafe { curr.with_tag(0).as_ref() } {
+                if last.map_or(false, |last| *last > node.key) {
+                    return Err(format!("level {level} is out of order"));
+                }
+                last = Some(&node.key);
+                curr = node.next[level].load(Ordering::Acquire, guard);
+                if node.next[0].load(Ordering::Acquire, guard).tag() & 1 != 0 {
+                    continue;
+                }
+                if level >= node.height || (level > 0 && !lower.contains(&(node as *const _))) {
+                    return Err(format!("level {level} links a node not in the lower level"));
+                }
+                if level == 0 {
+                    if keys.last().map_or(false, |last| **last == node.key) {
+                        return Err("duplicate keys".to_string());
+                    }
+                    keys.push(&node.key);
+                }
+                nodes.insert(node as *const Node<K, V>);
+            }
+            lower = nodes;
+        }
+        Ok(keys)
+    }
+
     pub fn insert(&self, key: K, value: V, guard: &Guard) -> bool {
         let mut cursor = self.find(&key, guard);
         if cursor.found.is_some() {
@@ -415,6 +454,11 @@ where
     fn remove<'g>(&'g self, key: &'g K, guard: &'g Guard) -> Option<&'g V> {
         self.remove(key, guard)
     }
+
+    #[inline(always)]
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
@@ -643,6 +643,41 @@ where
         }
     }
 
+    /// Checks that the tree is a BST with correct sizes and without retired nodes, and returns its
+    /// keys in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        /// Checks the subtree at `node` whose keys are in `(lo, hi)`, and returns its size.
+        fn check<'g, K: Ord + Clone, V: Clone + 'g>(
+            node: *mut Node<K, V>,
+            lo: Option<&K>,
+            hi: Option<&K>,
+            keys: &mut Vec<&'g K>,
+        ) -> Result<usize, String> {
+            if Node::is_retired(node) {
+                return Err("a retired node remains".to_string());
+            }
+            let node_ref: &'g Node<K, V> = some_or!(unsafe { node.as_ref() }, return Ok(0));
+            if lo.map_or(false, |lo| node_ref.key <= *lo)
+                || hi.map_or(false, |hi| node_ref.key >= *hi)
+            {
+                return Err("a node is in the wrong subtree".to_string());
+            }
+            let left = node_ref.left.load(Ordering::Acquire);
+            let right = node_ref.right.load(Ordering::Acquire);
+            let left_size = check(left, lo, Some(&node_ref.key), keys)?;
+            keys.push(&node_ref.key);
+            let right_size = check(right, Some(&node_ref.key), hi, keys)?;
+            if node_ref.size != left_size + right_size + 1 {
+                return Err("a node has a wrong size".to_string());
+            }
+            Ok(node_ref.size)
+        }
+
+        let mut keys = Vec::new();
+        check(self.root.load(Ordering::Acquire), None, None, &mut keys)?;
+        Ok(keys)
+    }
+
     #[inline]
     pub fn protect_root(&self, state: &mut State<'_, K, V>) {
         state.curr_root = Self::protect_link(&self.root, &mut state.root_h);
@@ -800,6 +835,10 @@ where
     fn remove<'hp>(&self, handle: &'hp mut Self::Handle<'_>, key: &K) -> Option<&'hp V> {
         self.remove(key, handle)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
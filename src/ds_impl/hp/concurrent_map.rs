@@ -10,6 +10,10 @@ pub trait ConcurrentMap<K, V> {
     fn insert(&self, handle: &mut Self::Handle<'_>, key: K, value: V) -> bool;
 
     fn remove<'hp>(&self, handle: &'hp mut Self::Handle<'_>, key: &K) -> Option<&'hp V>;
+
+    /// Checks the structural invariants of the map and returns its keys. It must not run
+    /// concurrently with the other operations.
+    fn validate(&self) -> Result<Vec<&K>, String>;
 }
 
 #[cfg(test)]
@@ -72,5 +76,13 @@ pub mod tests {
             }
         })
         .unwrap();
+
+        let mut keys = map.validate().unwrap();
+        keys.sort();
+        let mut expected = ((THREADS / 2)..THREADS)
+            .flat_map(|t| (0..ELEMENTS_PER_THREADS).map(move |k| k * THREADS + t))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert!(keys.into_iter().eq(expected.iter()));
     }
 }
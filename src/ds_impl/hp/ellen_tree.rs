@@ -390,6 +390,41 @@ where
         }
     }
 
+    /// Checks that the tree is a leaf-oriented BST whose internal nodes are all clean, and returns
+    /// the keys of its leaves in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys = Vec::new();
+        // Nodes with the bounds of the keys of their leaves, in the order of a DFS.
+        let mut stack = vec![(self.root.load(Ordering::Acquire), None, None)];
+        while let Some((node, lo, hi)) = stack.pop() {
+            let node_ref = unsafe { &*node };
+            let left = node_ref.left.load(Ordering::Acquire);
+            let right = node_ref.right.load(Ordering::Acquire);
+            match (left.is_null(), right.is_null()) {
+                (true, true) => {
+                    if let Key::Fin(key) = &node_ref.key {
+                        if lo.map_or(false, |lo: &Key<K>| node_ref.key < *lo)
+                            || hi.map_or(false, |hi: &Key<K>| node_ref.key >= *hi)
+                        {
+                            return Err("a leaf is in the wrong subtree".to_string());
+                        }
+                        keys.push(key);
+                    }
+                }
+                (false, false) => {
+                    let update = node_ref.update.load(Ordering::Acquire);
+                    if UpdateTag::from_bits_truncate(tag(update)) != UpdateTag::CLEAN {
+                        return Err("an internal node is not clean".to_string());
+                    }
+                    stack.push((right, Some(&node_ref.key), hi));
+                    stack.push((left, lo, Some(&node_ref.key)));
+                }
+                _ => return Err("an internal node has a single child".to_string()),
+            }
+        }
+        Ok(keys)
+    }
+
     /// Used by Insert, Delete and Find to traverse a branch of the BST.
     ///
     /// # Safety
@@ -864,6 +899,10 @@ where
     fn remove<'hp>(&self, handle: &'hp mut Self::Handle<'_>, key: &K) -> Option<&'hp V> {
         self.delete(key, handle)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
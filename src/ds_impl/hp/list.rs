@@ -317,6 +317,29 @@ where
         }
     }
 
+    /// Checks that the nodes are sorted and the unmarked ones have distinct keys, and returns the
+    /// keys of the unmarked nodes. Marked nodes that are not unlinked yet may remain.
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys: Vec<&K> = Vec::new();
+        let mut last: Option<&K> = None;
+        let mut curr = self.head.load(Ordering::Acquire);
+        while let Some(curr_node) = unsafe { untagged(curr).as_ref() } {
+            if last.map_or(false, |last| *last > curr_node.key) {
+                return Err(format!("the node #{} is out of order", keys.len()));
+            }
+            let next = curr_node.next.load(Ordering::Acquire);
+            if tag(next) == 0 {
+                if keys.last().map_or(false, |last| **last == curr_node.key) {
+                    return Err(format!("the node #{} has a duplicate key", keys.len()));
+                }
+                keys.push(&curr_node.key);
+            }
+            last = Some(&curr_node.key);
+            curr = next;
+        }
+        Ok(keys)
+    }
+
     pub fn harris_michael_get<'hp>(&self, key: &K, handle: &'hp mut Handle<'_>) -> Option<&'hp V> {
         self.get(key, Cursor::find_harris_michael, handle)
     }
@@ -375,6 +398,9 @@ where
     fn remove<'hp>(&self, handle: &'hp mut Self::Handle<'_>, key: &K) -> Option<&'hp V> {
         self.inner.harris_michael_remove(key, handle)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 #[cfg(test)]
@@ -48,6 +48,22 @@ where
         let i = Self::hash(k);
         self.get_bucket(i).remove(handle, k)
     }
+
+    /// Checks each bucket and that its keys belong to it.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys = Vec::new();
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            let bucket_keys = bucket.validate().map_err(|e| format!("bucket {i}: {e}"))?;
+            if bucket_keys
+                .iter()
+                .any(|k| Self::hash(k) % self.buckets.len() != i)
+            {
+                return Err(format!("bucket {i} has a key of another bucket"));
+            }
+            keys.extend(bucket_keys);
+        }
+        Ok(keys)
+    }
 }
 
 impl<K, V> ConcurrentMap<K, V> for HashMap<K, V>
@@ -77,6 +93,9 @@ where
     fn remove<'hp>(&self, handle: &'hp mut Self::Handle<'_>, key: &K) -> Option<&'hp V> {
         self.remove(handle, key)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::mem::transmute;
 use std::ptr;
 use std::sync::atomic::{fence, AtomicPtr, AtomicUsize, Ordering};
@@ -40,12 +41,12 @@ impl<K, V> Node<K, V> {
 
     fn generate_height() -> usize {
         // returns 1 with probability 3/4
-        if rand::random::<usize>() % 4 < 3 {
+        if crate::utils::random::<usize>() % 4 < 3 {
             return 1;
         }
         // returns h with probability 2^(−(h+1))
         let mut height = 2;
-        while height < MAX_HEIGHT && rand::random::<bool>() {
+        while height < MAX_HEIGHT && crate::utils::random::<bool>() {
             height += 1;
         }
         height
@@ -159,6 +160,42 @@ where
         }
     }
 
+    /// Checks that each level is sorted and only links the unmarked nodes of the lower levels
+    /// that are tall enough, and returns the keys of the unmarked nodes. Marked nodes that are not
+    /// unlinked yet may remain.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys: Vec<&K> = Vec::new();
+        // The unmarked nodes of the level below.
+        let mut lower = HashSet::new();
+        for level in 0..MAX_HEIGHT {
+            let mut nodes = HashSet::new();
+            let mut last: Option<&K> = None;
+            let mut curr = self.head[level].load(Ordering::Acquire);
+            while let Some(node) = unsafe { untagged(curr).as_ref() } {
+                if last.map_or(false, |last| *last > node.key) {
+                    return Err(format!("level {level} is out of order"));
+                }
+                last = Some(&node.key);
+                curr = node.next[level].load(Ordering::Acquire);
+                if tag(node.next[0].load(Ordering::Acquire)) & 1 != 0 {
+                    continue;
+                }
+                if level >= node.height || (level > 0 && !lower.contains(&(node as *const _))) {
+                    return Err(format!("level {level} links a node not in the lower level"));
+                }
+                if level == 0 {
+                    if keys.last().map_or(false, |last| **last == node.key) {
+                        return Err("duplicate keys".to_string());
+                    }
+                    keys.push(&node.key);
+                }
+                nodes.insert(node as *const Node<K, V>);
+            }
+            lower = nodes;
+        }
+        Ok(keys)
+    }
+
     fn find(&self, key: &K, handle: &mut Handle<'_>) -> Cursor<K, V> {
         'search: loop {
             let mut cursor = Cursor::new(&self.head);
@@ -333,40 +370,42 @@ where
         key: &K,
         handle: &'hp mut Handle<'domain>,
     ) -> Option<&'hp V> {
-        let cursor = self.find(key, handle);
-        let node_ptr = cursor.found?;
-        let node = unsafe { &*node_ptr };
-        handle
-            .removed_h
-            .protect_raw(node as *const _ as *mut Node<K, V>);
-        light_membarrier();
-
-        // Try removing the node by marking its tower.
-        if node.mark_tower() {
-            for level in (0..node.height).rev() {
-                let succ = node.next[level].load(Ordering::SeqCst);
-                if (tag(succ) & 2) != 0 {
-                    continue;
-                }
+        loop {
+            let cursor = self.find(key, handle);
+            let node_ptr = cursor.found?;
+            let node = unsafe { &*node_ptr };
+            handle
+                .removed_h
+                .protect_raw(node as *const _ as *mut Node<K, V>);
+            light_membarrier();
 
-                // Try linking the predecessor and successor at this level.
-                if unsafe { &*cursor.preds[level] }.next[level]
-                    .compare_exchange(
-                        node as *const _ as _,
-                        untagged(succ),
-                        Ordering::SeqCst,
-                        Ordering::SeqCst,
-                    )
-                    .is_ok()
-                {
-                    node.decrement(handle);
-                } else {
-                    self.find(key, handle);
-                    break;
+            // Try removing the node by marking its tower.
+            if node.mark_tower() {
+                for level in (0..node.height).rev() {
+                    let succ = node.next[level].load(Ordering::SeqCst);
+                    if (tag(succ) & 2) != 0 {
+                        continue;
+                    }
+
+                    // Try linking the predecessor and successor at this level.
+                    if unsafe { &*cursor.preds[level] }.next[level]
+                        .compare_exchange(
+                            node as *const _ as _,
+                            untagged(succ),
+                            Ordering::SeqCst,
+                            Ordering::SeqCst,
+                        )
+                        .is_ok()
+                    {
+                        node.decrement(handle);
+                    } else {
+                        self.find(key, handle);
+                        break;
+                    }
                 }
+                return Some(unsafe { transmute::<&V, &'hp V>(&node.value) });
             }
         }
-        Some(unsafe { transmute::<&V, &'hp V>(&node.value) })
     }
 }
 
@@ -405,6 +444,10 @@ where
     fn remove<'hp>(&self, handle: &'hp mut Self::Handle<'_>, key: &K) -> Option<&'hp V> {
         self.remove(key, handle)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,855 @@
+use hp_brcu::{Atomic, Handle, Owned, RaGuard, RollbackProof, Shared, Shield, Thread, Unprotected};
+
+use super::concurrent_map::{ConcurrentMap, OutputHolder};
+
+use std::cmp;
+use std::sync::atomic::Ordering;
+
+static WEIGHT: usize = 2;
+
+// TODO: optimization from the paper? IBR paper doesn't do that
+
+bitflags! {
+    /// TODO
+    struct Retired: usize {
+        const RETIRED = 1usize;
+    }
+}
+
+impl Retired {
+    fn new(retired: bool) -> Self {
+        if retired {
+            Retired::RETIRED
+        } else {
+            Retired::empty()
+        }
+    }
+
+    fn retired(self) -> bool {
+        !(self & Retired::RETIRED).is_empty()
+    }
+}
+
+/// a real node in tree or a wrapper of State node
+/// Retired node if Shared ptr of Node has RETIRED tag.
+struct Node<K, V> {
+    key: K,
+    value: V,
+    size: usize,
+    left: Atomic<Node<K, V>>,
+    right: Atomic<Node<K, V>>,
+}
+
+impl<K, V> Node<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn retired_node<'g>() -> Shared<'g, Self> {
+        Shared::null().with_tag(Retired::new(true).bits())
+    }
+
+    fn is_retired(node: Shared<Self>) -> bool {
+        Retired::from_bits_truncate(node.tag()).retired()
+    }
+
+    fn is_retired_spot<G: Handle>(node: Shared<Self>, guard: &G) -> bool {
+        if Self::is_retired(node) {
+            return true;
+        }
+
+        if let Some(node_ref) = unsafe { node.as_ref() } {
+            Self::is_retired(node_ref.left.load(Ordering::Acquire, guard))
+                || Self::is_retired(node_ref.right.load(Ordering::Acquire, guard))
+        } else {
+            false
+        }
+    }
+
+    fn node_size(node: Shared<Self>) -> usize {
+        debug_assert!(!Self::is_retired(node));
+        if let Some(node_ref) = unsafe { node.as_ref() } {
+            node_ref.size
+        } else {
+            0
+        }
+    }
+}
+
+pub struct Output<K, V> {
+    found: Shield<Node<K, V>>,
+}
+
+impl<K, V> OutputHolder<V> for Output<K, V> {
+    fn default(thread: &mut Thread) -> Self {
+        Self {
+            found: Shield::null(thread),
+        }
+    }
+
+    fn output(&self) -> &V {
+        &self.found.as_ref().unwrap().value
+    }
+}
+
+/// Each op creates a new local state and tries to update (CAS) the tree with it.
+///
+/// A state is built inside a non-crashable (`mask`) section, so every node it touches is protected
+/// by the epoch of the enclosing critical section.
+struct State<'g, K, V> {
+    root_link: &'g Atomic<Node<K, V>>,
+    curr_root: Shared<'g, Node<K, V>>,
+    /// Nodes that current op wants to remove from the tree. Should be retired if CAS succeeds.
+    /// (`retire`). If not, ignore.
+    retired_nodes: Vec<Shared<'g, Node<K, V>>>,
+    /// Nodes newly constructed by the op. Should be destroyed if CAS fails. (`destroy`)
+    new_nodes: Vec<Shared<'g, Node<K, V>>>,
+}
+
+impl<'g, K, V> State<'g, K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn new(root_link: &'g Atomic<Node<K, V>>) -> Self {
+        Self {
+            root_link,
+            curr_root: Shared::null(),
+            retired_nodes: Vec::new(),
+            new_nodes: Vec::new(),
+        }
+    }
+
+    fn load_root(&mut self, guard: &RaGuard) {
+        self.curr_root = self.root_link.load(Ordering::Acquire, guard);
+    }
+
+    /// Destroy the newly created state (self) that lost the race (reclaim_state)
+    fn abort(&mut self) {
+        self.retired_nodes.clear();
+
+        for node in self.new_nodes.drain(..) {
+            drop(unsafe { node.into_owned() });
+        }
+    }
+
+    /// Retire the old state replaced by the new_state and the new_state.retired_nodes
+    fn commit(&mut self, guard: &mut RaGuard) {
+        self.new_nodes.clear();
+
+        for node in self.retired_nodes.drain(..) {
+            unsafe {
+                node.deref()
+                    .left
+                    .store(Node::retired_node(), Ordering::Release, guard);
+                node.deref()
+                    .right
+                    .store(Node::retired_node(), Ordering::Release, guard);
+                guard.retire(node);
+            }
+        }
+    }
+
+    fn retire_node(&mut self, node: Shared<'g, Node<K, V>>) {
+        self.retired_nodes.push(node);
+    }
+
+    fn add_new_node(&mut self, node: Shared<'g, Node<K, V>>) {
+        self.new_nodes.push(node);
+    }
+
+    // TODO get ref of K, V and clone here
+    fn mk_node(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &RaGuard,
+    ) -> Shared<'g, Node<K, V>> {
+        if Node::is_retired_spot(left, guard) || Node::is_retired_spot(right, guard) {
+            return Node::retired_node();
+        }
+
+        let left_size = Node::node_size(left);
+        let right_size = Node::node_size(right);
+        let new_node = Owned::new(Node {
+            key,
+            value,
+            size: left_size + right_size + 1,
+            left: Atomic::null(),
+            right: Atomic::null(),
+        });
+        new_node.left.store(left, Ordering::Relaxed, guard);
+        new_node.right.store(right, Ordering::Relaxed, guard);
+        let new_node = new_node.into_shared();
+        self.add_new_node(new_node);
+        new_node
+    }
+
+    /// Make a new balanced tree from cur (the root of a subtree) and newly constructed left and right subtree
+    fn mk_balanced(
+        &mut self,
+        cur: Shared<'g, Node<K, V>>,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        guard: &RaGuard,
+    ) -> Shared<'g, Node<K, V>> {
+        if Node::is_retired_spot(cur, guard)
+            || Node::is_retired_spot(left, guard)
+            || Node::is_retired_spot(right, guard)
+        {
+            return Node::retired_node();
+        }
+
+        let cur_ref = unsafe { cur.deref() };
+        let key = cur_ref.key.clone();
+        let value = cur_ref.value.clone();
+
+        let l_size = Node::node_size(left);
+        let r_size = Node::node_size(right);
+        let res = if r_size > 0
+            && ((l_size > 0 && r_size > WEIGHT * l_size) || (l_size == 0 && r_size > WEIGHT))
+        {
+            self.mk_balanced_left(left, right, key, value, guard)
+        } else if l_size > 0
+            && ((r_size > 0 && l_size > WEIGHT * r_size) || (r_size == 0 && l_size > WEIGHT))
+        {
+            self.mk_balanced_right(left, right, key, value, guard)
+        } else {
+            self.mk_node(left, right, key, value, guard)
+        };
+        self.retire_node(cur);
+        res
+    }
+
+    #[inline]
+    fn mk_balanced_left(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &RaGuard,
+    ) -> Shared<'g, Node<K, V>> {
+        let right_ref = unsafe { right.deref() };
+        let right_left = right_ref.left.load(Ordering::Acquire, guard);
+        let right_right = right_ref.right.load(Ordering::Acquire, guard);
+
+        if !self.check_root(guard)
+            || Node::is_retired_spot(right_left, guard)
+            || Node::is_retired_spot(right_right, guard)
+        {
+            return Node::retired_node();
+        }
+
+        if Node::node_size(right_left) < Node::node_size(right_right) {
+            // single left rotation
+            return self.single_left(left, right, right_left, right_right, key, value, guard);
+        }
+
+        // double left rotation
+        return self.double_left(left, right, right_left, right_right, key, value, guard);
+    }
+
+    #[inline]
+    fn single_left(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        right_left: Shared<'g, Node<K, V>>,
+        right_right: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &RaGuard,
+    ) -> Shared<'g, Node<K, V>> {
+        let right_ref = unsafe { right.deref() };
+        let new_left = self.mk_node(left, right_left, key, value, guard);
+        let res = self.mk_node(
+            new_left,
+            right_right,
+            right_ref.key.clone(),
+            right_ref.value.clone(),
+            guard,
+        );
+        self.retire_node(right);
+        res
+    }
+
+    #[inline]
+    fn double_left(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        right_left: Shared<'g, Node<K, V>>,
+        right_right: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &RaGuard,
+    ) -> Shared<'g, Node<K, V>> {
+        let right_ref = unsafe { right.deref() };
+        let right_left_ref = unsafe { right_left.deref() };
+        let right_left_left = right_left_ref.left.load(Ordering::Acquire, guard);
+        let right_left_right = right_left_ref.right.load(Ordering::Acquire, guard);
+
+        if !self.check_root(guard)
+            || Node::is_retired_spot(right_left_left, guard)
+            || Node::is_retired_spot(right_left_right, guard)
+        {
+            return Node::retired_node();
+        }
+
+        let new_left = self.mk_node(left, right_left_left, key, value, guard);
+        let new_right = self.mk_node(
+            right_left_right,
+            right_right,
+            right_ref.key.clone(),
+            right_ref.value.clone(),
+            guard,
+        );
+        let res = self.mk_node(
+            new_left,
+            new_right,
+            right_left_ref.key.clone(),
+            right_left_ref.value.clone(),
+            guard,
+        );
+        self.retire_node(right_left);
+        self.retire_node(right);
+        res
+    }
+
+    #[inline]
+    fn mk_balanced_right(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &RaGuard,
+    ) -> Shared<'g, Node<K, V>> {
+        let left_ref = unsafe { left.deref() };
+        let left_right = left_ref.right.load(Ordering::Acquire, guard);
+        let left_left = left_ref.left.load(Ordering::Acquire, guard);
+
+        if !self.check_root(guard)
+            || Node::is_retired_spot(left_right, guard)
+            || Node::is_retired_spot(left_left, guard)
+        {
+            return Node::retired_node();
+        }
+
+        if Node::node_size(left_right) < Node::node_size(left_left) {
+            // single right rotation (fig 3)
+            return self.single_right(left, right, left_right, left_left, key, value, guard);
+        }
+        // double right rotation
+        return self.double_right(left, right, left_right, left_left, key, value, guard);
+    }
+
+    #[inline]
+    fn single_right(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        left_right: Shared<'g, Node<K, V>>,
+        left_left: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &RaGuard,
+    ) -> Shared<'g, Node<K, V>> {
+        let left_ref = unsafe { left.deref() };
+        let new_right = self.mk_node(left_right, right, key, value, guard);
+        let res = self.mk_node(
+            left_left,
+            new_right,
+            left_ref.key.clone(),
+            left_ref.value.clone(),
+            guard,
+        );
+        self.retire_node(left);
+        res
+    }
+
+    #[inline]
+    fn double_right(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        left_right: Shared<'g, Node<K, V>>,
+        left_left: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &RaGuard,
+    ) -> Shared<'g, Node<K, V>> {
+        let left_ref = unsafe { left.deref() };
+        let left_right_ref = unsafe { left_right.deref() };
+        let left_right_left = left_right_ref.left.load(Ordering::Acquire, guard);
+        let left_right_right = left_right_ref.right.load(Ordering::Acquire, guard);
+
+        if !self.check_root(guard)
+            || Node::is_retired_spot(left_right_left, guard)
+            || Node::is_retired_spot(left_right_right, guard)
+        {
+            return Node::retired_node();
+        }
+
+        let new_left = self.mk_node(
+            left_left,
+            left_right_left,
+            left_ref.key.clone(),
+            left_ref.value.clone(),
+            guard,
+        );
+        let new_right = self.mk_node(left_right_right, right, key, value, guard);
+        let res = self.mk_node(
+            new_left,
+            new_right,
+            left_right_ref.key.clone(),
+            left_right_ref.value.clone(),
+            guard,
+        );
+        self.retire_node(left_right);
+        self.retire_node(left);
+        res
+    }
+
+    #[inline]
+    fn do_insert(
+        &mut self,
+        node: Shared<'g, Node<K, V>>,
+        key: &K,
+        value: &V,
+        guard: &RaGuard,
+    ) -> (Shared<'g, Node<K, V>>, bool) {
+        if Node::is_retired_spot(node, guard) {
+            return (Node::retired_node(), false);
+        }
+
+        if node.is_null() {
+            return (
+                self.mk_node(
+                    Shared::null(),
+                    Shared::null(),
+                    key.clone(),
+                    value.clone(),
+                    guard,
+                ),
+                true,
+            );
+        }
+
+        let node_ref = unsafe { node.deref() };
+        let left = node_ref.left.load(Ordering::Acquire, guard);
+        let right = node_ref.right.load(Ordering::Acquire, guard);
+
+        if !self.check_root(guard)
+            || Node::is_retired_spot(left, guard)
+            || Node::is_retired_spot(right, guard)
+        {
+            return (Node::retired_node(), false);
+        }
+
+        match node_ref.key.cmp(key) {
+            cmp::Ordering::Equal => (node, false),
+            cmp::Ordering::Less => {
+                let (new_right, inserted) = self.do_insert(right, key, value, guard);
+                (self.mk_balanced(node, left, new_right, guard), inserted)
+            }
+            cmp::Ordering::Greater => {
+                let (new_left, inserted) = self.do_insert(left, key, value, guard);
+                (self.mk_balanced(node, new_left, right, guard), inserted)
+            }
+        }
+    }
+
+    /// Returns the new root and the removed node, if any.
+    #[inline]
+    fn do_remove(
+        &mut self,
+        node: Shared<'g, Node<K, V>>,
+        key: &K,
+        guard: &RaGuard,
+    ) -> (Shared<'g, Node<K, V>>, Option<Shared<'g, Node<K, V>>>) {
+        if Node::is_retired_spot(node, guard) {
+            return (Node::retired_node(), None);
+        }
+
+        if node.is_null() {
+            return (Shared::null(), None);
+        }
+
+        let node_ref = unsafe { node.deref() };
+        let left = node_ref.left.load(Ordering::Acquire, guard);
+        let right = node_ref.right.load(Ordering::Acquire, guard);
+
+        if !self.check_root(guard)
+            || Node::is_retired_spot(left, guard)
+            || Node::is_retired_spot(right, guard)
+        {
+            return (Node::retired_node(), None);
+        }
+
+        match node_ref.key.cmp(key) {
+            cmp::Ordering::Equal => {
+                let removed = Some(node);
+                self.retire_node(node);
+                if node_ref.size == 1 {
+                    return (Shared::null(), removed);
+                }
+
+                if !left.is_null() {
+                    let (new_left, succ) = self.pull_rightmost(left, guard);
+                    return (self.mk_balanced(succ, new_left, right, guard), removed);
+                }
+                let (new_right, succ) = self.pull_leftmost(right, guard);
+                (self.mk_balanced(succ, left, new_right, guard), removed)
+            }
+            cmp::Ordering::Less => {
+                let (new_right, removed) = self.do_remove(right, key, guard);
+                (self.mk_balanced(node, left, new_right, guard), removed)
+            }
+            cmp::Ordering::Greater => {
+                let (new_left, removed) = self.do_remove(left, key, guard);
+                (self.mk_balanced(node, new_left, right, guard), removed)
+            }
+        }
+    }
+
+    fn pull_leftmost(
+        &mut self,
+        node: Shared<'g, Node<K, V>>,
+        guard: &RaGuard,
+    ) -> (Shared<'g, Node<K, V>>, Shared<'g, Node<K, V>>) {
+        if Node::is_retired_spot(node, guard) {
+            return (Node::retired_node(), Node::retired_node());
+        }
+
+        let node_ref = unsafe { node.deref() };
+        let left = node_ref.left.load(Ordering::Acquire, guard);
+        let right = node_ref.right.load(Ordering::Acquire, guard);
+
+        if !self.check_root(guard)
+            || Node::is_retired_spot(left, guard)
+            || Node::is_retired_spot(right, guard)
+        {
+            return (Node::retired_node(), Node::retired_node());
+        }
+
+        if !left.is_null() {
+            let (new_left, succ) = self.pull_leftmost(left, guard);
+            return (self.mk_balanced(node, new_left, right, guard), succ);
+        }
+        // node is the leftmost
+        let succ = self.mk_node(
+            Shared::null(),
+            Shared::null(),
+            node_ref.key.clone(),
+            node_ref.value.clone(),
+            guard,
+        );
+        self.retire_node(node);
+        (right, succ)
+    }
+
+    fn pull_rightmost(
+        &mut self,
+        node: Shared<'g, Node<K, V>>,
+        guard: &RaGuard,
+    ) -> (Shared<'g, Node<K, V>>, Shared<'g, Node<K, V>>) {
+        if Node::is_retired_spot(node, guard) {
+            return (Node::retired_node(), Node::retired_node());
+        }
+
+        let node_ref = unsafe { node.deref() };
+        let left = node_ref.left.load(Ordering::Acquire, guard);
+        let right = node_ref.right.load(Ordering::Acquire, guard);
+
+        if !self.check_root(guard)
+            || Node::is_retired_spot(left, guard)
+            || Node::is_retired_spot(right, guard)
+        {
+            return (Node::retired_node(), Node::retired_node());
+        }
+
+        if !right.is_null() {
+            let (new_right, succ) = self.pull_rightmost(right, guard);
+            return (self.mk_balanced(node, left, new_right, guard), succ);
+        }
+        // node is the rightmost
+        let succ = self.mk_node(
+            Shared::null(),
+            Shared::null(),
+            node_ref.key.clone(),
+            node_ref.value.clone(),
+            guard,
+        );
+        self.retire_node(node);
+        (left, succ)
+    }
+
+    pub fn check_root(&self, guard: &RaGuard) -> bool {
+        self.curr_root == self.root_link.load(Ordering::Acquire, guard)
+    }
+}
+
+pub struct BonsaiTreeMap<K, V> {
+    root: Atomic<Node<K, V>>,
+}
+
+impl<K, V> Default for BonsaiTreeMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> BonsaiTreeMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            root: Atomic::null(),
+        }
+    }
+
+    /// Checks that the tree is a BST with correct sizes and without retired nodes, and returns its
+    /// keys in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        /// Checks the subtree at `node` whose keys are in `(lo, hi)`, and returns its size.
+        fn check<'g, K: Ord + Clone, V: Clone + 'g>(
+            node: Shared<'g, Node<K, V>>,
+            lo: Option<&K>,
+            hi: Option<&K>,
+            keys: &mut Vec<&'g K>,
+        ) -> Result<usize, String> {
+            if Node::is_retired(node) {
+                return Err("a retired node remains".to_string());
+            }
+            let node_ref = some_or!(unsafe { node.as_ref() }, return Ok(0));
+            if lo.map_or(false, |lo| node_ref.key <= *lo)
+                || hi.map_or(false, |hi| node_ref.key >= *hi)
+            {
+                return Err("a node is in the wrong subtree".to_string());
+            }
+            let guard = unsafe { &Unprotected::new() };
+            let left = node_ref.left.load(Ordering::Acquire, guard);
+            let right = node_ref.right.load(Ordering::Acquire, guard);
+            let left_size = check(left, lo, Some(&node_ref.key), keys)?;
+            keys.push(&node_ref.key);
+            let right_size = check(right, Some(&node_ref.key), hi, keys)?;
+            if node_ref.size != left_size + right_size + 1 {
+                return Err("a node has a wrong size".to_string());
+            }
+            Ok(node_ref.size)
+        }
+
+        let mut keys = Vec::new();
+        let root = self
+            .root
+            .load(Ordering::Acquire, unsafe { &Unprotected::new() });
+        check(root, None, None, &mut keys)?;
+        Ok(keys)
+    }
+
+    pub fn get(&self, key: &K, output: &mut Output<K, V>, handle: &mut Thread) -> bool {
+        unsafe {
+            handle.critical_section(|guard| loop {
+                let mut node = self.root.load(Ordering::Acquire, guard);
+                while !node.is_null() && !Node::is_retired(node) {
+                    let node_ref = node.deref();
+                    match key.cmp(&node_ref.key) {
+                        cmp::Ordering::Equal => break,
+                        cmp::Ordering::Less => node = node_ref.left.load(Ordering::Acquire, guard),
+                        cmp::Ordering::Greater => {
+                            node = node_ref.right.load(Ordering::Acquire, guard)
+                        }
+                    }
+                }
+
+                if Node::is_retired_spot(node, guard) {
+                    continue;
+                }
+
+                if node.is_null() {
+                    return false;
+                }
+
+                output.found.protect(node);
+                return true;
+            })
+        }
+    }
+
+    /// Applies an update to the tree with a single root CAS.
+    ///
+    /// The copy-on-write, the CAS and the commit all allocate or retire nodes, so an attempt runs
+    /// entirely in a `mask` section. If the critical section is rolled back after a successful
+    /// attempt, the recorded result is returned instead of applying the update again.
+    fn update<F>(&self, mut attempt: F, handle: &mut Thread) -> bool
+    where
+        F: FnMut(&mut State<'_, K, V>, &mut RaGuard) -> Option<bool>,
+    {
+        let mut state = State::new(&self.root);
+        let mut done = None;
+        loop {
+            let result = unsafe {
+                handle.critical_section(|guard| {
+                    if done.is_some() {
+                        return done;
+                    }
+                    guard.mask(|guard| {
+                        done = attempt(&mut state, guard);
+                        done
+                    })
+                })
+            };
+            if let Some(result) = result {
+                return result;
+            }
+        }
+    }
+
+    pub fn insert(
+        &self,
+        key: K,
+        value: V,
+        _output: &mut Output<K, V>,
+        handle: &mut Thread,
+    ) -> bool {
+        self.update(
+            |state, guard| {
+                state.load_root(guard);
+                let old_root = state.curr_root;
+                let (new_root, inserted) = state.do_insert(old_root, &key, &value, guard);
+
+                if Node::is_retired(new_root) {
+                    state.abort();
+                    return None;
+                }
+
+                if self
+                    .root
+                    .compare_exchange(
+                        old_root,
+                        new_root,
+                        Ordering::AcqRel,
+                        Ordering::Acquire,
+                        guard,
+                    )
+                    .is_ok()
+                {
+                    state.commit(guard);
+                    return Some(inserted);
+                }
+
+                state.abort();
+                None
+            },
+            handle,
+        )
+    }
+
+    pub fn remove(&self, key: &K, output: &mut Output<K, V>, handle: &mut Thread) -> bool {
+        self.update(
+            |state, guard| {
+                state.load_root(guard);
+                let old_root = state.curr_root;
+                let (new_root, removed) = state.do_remove(old_root, key, guard);
+
+                if Node::is_retired(new_root) {
+                    state.abort();
+                    return None;
+                }
+
+                if self
+                    .root
+                    .compare_exchange(
+                        old_root,
+                        new_root,
+                        Ordering::AcqRel,
+                        Ordering::Acquire,
+                        guard,
+                    )
+                    .is_ok()
+                {
+                    if let Some(removed) = removed {
+                        output.found.protect(removed);
+                    }
+                    state.commit(guard);
+                    return Some(removed.is_some());
+                }
+
+                state.abort();
+                None
+            },
+            handle,
+        )
+    }
+}
+
+impl<K, V> Drop for BonsaiTreeMap<K, V> {
+    fn drop(&mut self) {
+        unsafe {
+            let guard = &Unprotected::new();
+            let mut stack = vec![self.root.load(Ordering::Relaxed, guard)];
+
+            while let Some(node) = stack.pop() {
+                if node.is_null() {
+                    continue;
+                }
+
+                let node_ref = node.deref();
+
+                stack.push(node_ref.left.load(Ordering::Relaxed, guard));
+                stack.push(node_ref.right.load(Ordering::Relaxed, guard));
+                drop(node.into_owned());
+            }
+        }
+    }
+}
+
+impl<K, V> ConcurrentMap<K, V> for BonsaiTreeMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    type Output = Output<K, V>;
+
+    fn new() -> Self {
+        Self::new()
+    }
+
+    #[inline(always)]
+    fn get(&self, key: &K, output: &mut Self::Output, handle: &mut Thread) -> bool {
+        self.get(key, output, handle)
+    }
+    #[inline(always)]
+    fn insert(&self, key: K, value: V, output: &mut Self::Output, handle: &mut Thread) -> bool {
+        self.insert(key, value, output, handle)
+    }
+    #[inline(always)]
+    fn remove(&self, key: &K, output: &mut Self::Output, handle: &mut Thread) -> bool {
+        self.remove(key, output, handle)
+    }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BonsaiTreeMap;
+    use crate::ds_impl::hp_brcu::concurrent_map;
+
+    #[test]
+    fn smoke_bonsai_tree() {
+        concurrent_map::tests::smoke::<BonsaiTreeMap<i32, String>>();
+    }
+}
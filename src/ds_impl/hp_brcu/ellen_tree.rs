@@ -0,0 +1,667 @@
+//! The search runs in a critical section, and the write phase only touches the nodes and Info
+//! records which were protected at the end of the section.
+//!
+//! To make the protection of an Info record's fields sound, the tree nodes referred by a flagged
+//! Info record are retired only after the corresponding unflag CAS. Thus, if a critical section
+//! observes a flagged update, the fields of the update were not retired before the section began.
+
+use std::{cmp, sync::atomic::Ordering};
+
+use hp_brcu::{Atomic, Owned, Pointer, RollbackProof, Shared, Shield, Thread, Unprotected};
+
+use super::concurrent_map::{ConcurrentMap, OutputHolder};
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    struct UpdateTag: usize {
+        const CLEAN = 0b00;
+        const DFLAG = 0b01;
+        const IFLAG = 0b10;
+        const MARKED = 0b11;
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Key<K> {
+    Fin(K),
+    Inf1,
+    Inf2,
+}
+
+impl<K> PartialOrd for Key<K>
+where
+    K: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        match (self, other) {
+            (Key::Fin(k1), Key::Fin(k2)) => k1.partial_cmp(k2),
+            (Key::Fin(_), Key::Inf1) => Some(cmp::Ordering::Less),
+            (Key::Fin(_), Key::Inf2) => Some(cmp::Ordering::Less),
+            (Key::Inf1, Key::Fin(_)) => Some(cmp::Ordering::Greater),
+            (Key::Inf1, Key::Inf1) => Some(cmp::Ordering::Equal),
+            (Key::Inf1, Key::Inf2) => Some(cmp::Ordering::Less),
+            (Key::Inf2, Key::Fin(_)) => Some(cmp::Ordering::Greater),
+            (Key::Inf2, Key::Inf1) => Some(cmp::Ordering::Greater),
+            (Key::Inf2, Key::Inf2) => Some(cmp::Ordering::Equal),
+        }
+    }
+}
+
+impl<K> PartialEq<K> for Key<K>
+where
+    K: PartialEq,
+{
+    fn eq(&self, rhs: &K) -> bool {
+        match self {
+            Key::Fin(k) => k == rhs,
+            _ => false,
+        }
+    }
+}
+
+impl<K> PartialOrd<K> for Key<K>
+where
+    K: PartialOrd,
+{
+    fn partial_cmp(&self, rhs: &K) -> Option<cmp::Ordering> {
+        match self {
+            Key::Fin(k) => k.partial_cmp(rhs),
+            _ => Some(cmp::Ordering::Greater),
+        }
+    }
+}
+
+impl<K> Key<K>
+where
+    K: Ord,
+{
+    fn cmp(&self, rhs: &K) -> cmp::Ordering {
+        match self {
+            Key::Fin(k) => k.cmp(rhs),
+            _ => cmp::Ordering::Greater,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    L,
+    R,
+}
+
+struct Node<K, V> {
+    key: Key<K>,
+    value: Option<V>,
+    // tag on low bits: {Clean, DFlag, IFlag, Mark}
+    update: Atomic<Update<K, V>>,
+    left: Atomic<Node<K, V>>,
+    right: Atomic<Node<K, V>>,
+    is_leaf: bool,
+}
+
+struct Update<K, V> {
+    gp: Shared<'static, Node<K, V>>,
+    p: Shared<'static, Node<K, V>>,
+    l: Shared<'static, Node<K, V>>,
+    l_other: Shared<'static, Node<K, V>>,
+    gp_p_dir: Direction,
+    p_l_dir: Direction,
+    pupdate: Shared<'static, Update<K, V>>,
+    new_internal: Shared<'static, Node<K, V>>,
+}
+
+impl<K, V> Node<K, V> {
+    fn internal(key: Key<K>, value: Option<V>, left: Self, right: Self) -> Self {
+        Self {
+            key,
+            value,
+            update: Atomic::null(),
+            left: Atomic::new(left),
+            right: Atomic::new(right),
+            is_leaf: false,
+        }
+    }
+
+    fn leaf(key: Key<K>, value: Option<V>) -> Self {
+        Self {
+            key,
+            value,
+            update: Atomic::null(),
+            left: Atomic::null(),
+            right: Atomic::null(),
+            is_leaf: true,
+        }
+    }
+
+    #[inline]
+    fn child(&self, dir: Direction) -> &Atomic<Self> {
+        match dir {
+            Direction::L => &self.left,
+            Direction::R => &self.right,
+        }
+    }
+}
+
+impl<K, V> OutputHolder<V> for Cursor<K, V> {
+    fn default(handle: &mut Thread) -> Self {
+        Self::empty(handle)
+    }
+
+    fn output(&self) -> &V {
+        self.l.as_ref().unwrap().value.as_ref().unwrap()
+    }
+}
+
+pub struct Cursor<K, V> {
+    gp: Shield<Node<K, V>>,
+    p: Shield<Node<K, V>>,
+    l: Shield<Node<K, V>>,
+    l_other: Shared<'static, Node<K, V>>,
+    gp_p_dir: Direction,
+    p_l_dir: Direction,
+    pupdate: Shield<Update<K, V>>,
+    gpupdate: Shield<Update<K, V>>,
+    /// A flagged update of `gp` or `p` (in this order), whose nodes are protected by `help_*`.
+    help: Shared<'static, Update<K, V>>,
+    /// Protects a new update of this thread.
+    aux_update: Shield<Update<K, V>>,
+    help_gp: Shield<Node<K, V>>,
+    help_p: Shield<Node<K, V>>,
+    help_l: Shield<Node<K, V>>,
+}
+
+impl<K, V> Cursor<K, V> {
+    fn empty(handle: &mut Thread) -> Self {
+        Self {
+            gp: Shield::null(handle),
+            p: Shield::null(handle),
+            l: Shield::null(handle),
+            l_other: Shared::null(),
+            gp_p_dir: Direction::L,
+            p_l_dir: Direction::L,
+            pupdate: Shield::null(handle),
+            gpupdate: Shield::null(handle),
+            help: Shared::null(),
+            aux_update: Shield::null(handle),
+            help_gp: Shield::null(handle),
+            help_p: Shield::null(handle),
+            help_l: Shield::null(handle),
+        }
+    }
+}
+
+pub struct EFRBTree<K, V> {
+    root: Atomic<Node<K, V>>,
+}
+
+impl<K, V> Default for EFRBTree<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Drop for EFRBTree<K, V> {
+    fn drop(&mut self) {
+        unsafe {
+            let guard = &Unprotected::new();
+            let mut stack = vec![self.root.load(Ordering::Relaxed, guard)];
+
+            while let Some(node) = stack.pop() {
+                if node.is_null() {
+                    continue;
+                }
+
+                let node_ref = node.deref();
+
+                stack.push(node_ref.left.load(Ordering::Relaxed, guard));
+                stack.push(node_ref.right.load(Ordering::Relaxed, guard));
+                let update = node_ref.update.load(Ordering::Relaxed, guard);
+                if !update.is_null() {
+                    drop(update.into_owned());
+                }
+                drop(node.into_owned());
+            }
+        }
+    }
+}
+
+impl<K, V> EFRBTree<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            root: Atomic::new(Node::internal(
+                Key::Inf2,
+                None,
+                Node::leaf(Key::Inf1, None),
+                Node::leaf(Key::Inf2, None),
+            )),
+        }
+    }
+
+    /// Checks that the tree is a leaf-oriented BST whose internal nodes are all clean, and returns
+    /// the keys of its leaves in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let guard = unsafe { &Unprotected::new() };
+        let mut keys = Vec::new();
+        // Nodes with the bounds of the keys of their leaves, in the order of a DFS.
+        let mut stack = vec![(self.root.load(Ordering::Acquire, guard), None, None)];
+        while let Some((node, lo, hi)) = stack.pop() {
+            let node_ref = unsafe { node.as_ref().unwrap() };
+            let left = node_ref.left.load(Ordering::Acquire, guard);
+            let right = node_ref.right.load(Ordering::Acquire, guard);
+            match (left.is_null(), right.is_null()) {
+                (true, true) => {
+                    if let Key::Fin(key) = &node_ref.key {
+                        if lo.map_or(false, |lo: &Key<K>| node_ref.key < *lo)
+                            || hi.map_or(false, |hi: &Key<K>| node_ref.key >= *hi)
+                        {
+                            return Err("a leaf is in the wrong subtree".to_string());
+                        }
+                        keys.push(key);
+                    }
+                }
+                (false, false) => {
+                    let update = node_ref.update.load(Ordering::Acquire, guard);
+                    if UpdateTag::from_bits_truncate(update.tag()) != UpdateTag::CLEAN {
+                        return Err("an internal node is not clean".to_string());
+                    }
+                    stack.push((right, Some(&node_ref.key), hi));
+                    stack.push((left, lo, Some(&node_ref.key)));
+                }
+                _ => return Err("an internal node has a single child".to_string()),
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Used by Insert, Delete and Find to traverse a branch of the BST.
+    ///
+    /// On return, `gp`, `p`, `l`, `pupdate` and `gpupdate` of the cursor are protected, and so
+    /// are the nodes of `help` if it is not null.
+    fn search(&self, key: &K, cursor: &mut Cursor<K, V>, handle: &mut Thread) {
+        unsafe {
+            handle.critical_section(|guard| {
+                let mut gp = Shared::null();
+                let mut p = Shared::null();
+                let mut l = self.root.load(Ordering::Relaxed, guard);
+                let mut l_other = Shared::null();
+                let mut gp_p_dir = Direction::L;
+                let mut p_l_dir = Direction::L;
+                let mut pupdate = Shared::null();
+                let mut gpupdate = Shared::null();
+
+                loop {
+                    let l_node = l.deref();
+                    if l_node.is_leaf {
+                        break;
+                    }
+                    gp = p;
+                    p = l;
+                    gp_p_dir = p_l_dir;
+                    gpupdate = pupdate;
+                    // The children must be read after the update, so that they are consistent
+                    // with `pupdate`.
+                    pupdate = l_node.update.load(Ordering::Acquire, guard);
+                    let left = l_node.left.load(Ordering::Acquire, guard);
+                    let right = l_node.right.load(Ordering::Acquire, guard);
+                    if l_node.key.cmp(key) == cmp::Ordering::Greater {
+                        (l, l_other, p_l_dir) = (left, right, Direction::L);
+                    } else {
+                        (l, l_other, p_l_dir) = (right, left, Direction::R);
+                    }
+                }
+
+                let help = if gpupdate.tag() != UpdateTag::CLEAN.bits() {
+                    gpupdate
+                } else if pupdate.tag() != UpdateTag::CLEAN.bits() {
+                    pupdate
+                } else {
+                    Shared::null()
+                };
+
+                // The nodes of a flagged update are not retired yet, as they are retired only
+                // after the update is unflagged.
+                if let Some(op) = help.as_ref() {
+                    cursor.help_gp.protect(op.gp);
+                    cursor.help_p.protect(op.p);
+                    cursor.help_l.protect(op.l);
+                }
+
+                cursor.gp.protect(gp);
+                cursor.p.protect(p);
+                cursor.l.protect(l);
+                cursor.pupdate.protect(pupdate);
+                cursor.gpupdate.protect(gpupdate);
+                cursor.l_other = l_other;
+                cursor.gp_p_dir = gp_p_dir;
+                cursor.p_l_dir = p_l_dir;
+                cursor.help = help;
+            });
+        }
+    }
+
+    pub fn get(&self, key: &K, cursor: &mut Cursor<K, V>, handle: &mut Thread) -> bool {
+        self.search(key, cursor, handle);
+        let l_node = unsafe { cursor.l.deref() };
+        l_node.key.eq(key)
+    }
+
+    pub fn insert(
+        &self,
+        key: &K,
+        value: V,
+        cursor: &mut Cursor<K, V>,
+        handle: &mut Thread,
+    ) -> bool {
+        loop {
+            self.search(key, cursor, handle);
+            let p_node = unsafe { cursor.p.deref() };
+            let l_node = unsafe { cursor.l.deref() };
+
+            if l_node.key == *key {
+                return false;
+            } else if cursor.pupdate.tag() != UpdateTag::CLEAN.bits() {
+                self.help(cursor.help, handle);
+            } else {
+                let new = Node::leaf(Key::Fin(key.clone()), Some(value.clone()));
+                let new_sibling = Node::leaf(l_node.key.clone(), l_node.value.clone());
+
+                let (left, right) = match new.key.partial_cmp(&new_sibling.key) {
+                    Some(cmp::Ordering::Less) => (new, new_sibling),
+                    _ => (new_sibling, new),
+                };
+
+                let new_internal = Owned::new(Node::internal(
+                    // key field max(k, l → key)
+                    right.key.clone(),
+                    None,
+                    // two child fields equal to new and newSibling
+                    // (the one with the smaller key is the left child)
+                    left,
+                    right,
+                ))
+                .into_shared();
+
+                let op = Update {
+                    gp: Shared::null(),
+                    p: unsafe { Shared::from_usize(cursor.p.as_raw()) },
+                    l: unsafe { Shared::from_usize(cursor.l.as_raw()) },
+                    l_other: cursor.l_other,
+                    gp_p_dir: cursor.gp_p_dir,
+                    p_l_dir: cursor.p_l_dir,
+                    pupdate: Shared::null(),
+                    new_internal,
+                };
+                let new_pupdate = Owned::new(op)
+                    .with_tag(UpdateTag::IFLAG.bits())
+                    .into_shared();
+                cursor.aux_update.protect(new_pupdate);
+
+                // iflag CAS
+                match p_node.update.compare_exchange(
+                    cursor.pupdate.shared(),
+                    new_pupdate,
+                    Ordering::Release,
+                    Ordering::Acquire,
+                    handle,
+                ) {
+                    Ok(_) => {
+                        if !cursor.pupdate.is_null() {
+                            unsafe { handle.retire(cursor.pupdate.shared()) };
+                        }
+                        self.help_insert(new_pupdate, handle);
+                        return true;
+                    }
+                    Err(_) => unsafe {
+                        let guard = &Unprotected::new();
+                        drop(new_pupdate.into_owned());
+                        let new_internal = new_internal.into_owned();
+                        drop(
+                            new_internal
+                                .left
+                                .load(Ordering::Relaxed, guard)
+                                .into_owned(),
+                        );
+                        drop(
+                            new_internal
+                                .right
+                                .load(Ordering::Relaxed, guard)
+                                .into_owned(),
+                        );
+                    },
+                }
+            }
+        }
+    }
+
+    pub fn delete(&self, key: &K, cursor: &mut Cursor<K, V>, handle: &mut Thread) -> bool {
+        loop {
+            self.search(key, cursor, handle);
+
+            if cursor.gp.is_null() {
+                // The tree is empty. There's no more things to do.
+                return false;
+            }
+            let gp_node = unsafe { cursor.gp.deref() };
+            let l_node = unsafe { cursor.l.deref() };
+
+            if l_node.key != *key {
+                return false;
+            }
+            if !cursor.help.is_null() {
+                self.help(cursor.help, handle);
+                continue;
+            }
+
+            let op = Update {
+                gp: unsafe { Shared::from_usize(cursor.gp.as_raw()) },
+                p: unsafe { Shared::from_usize(cursor.p.as_raw()) },
+                l: unsafe { Shared::from_usize(cursor.l.as_raw()) },
+                l_other: cursor.l_other,
+                gp_p_dir: cursor.gp_p_dir,
+                p_l_dir: cursor.p_l_dir,
+                pupdate: unsafe { Shared::from_usize(cursor.pupdate.as_raw()) },
+                new_internal: Shared::null(),
+            };
+            let new_update = Owned::new(op)
+                .with_tag(UpdateTag::DFLAG.bits())
+                .into_shared();
+            cursor.aux_update.protect(new_update);
+
+            // dflag CAS
+            match gp_node.update.compare_exchange(
+                cursor.gpupdate.shared(),
+                new_update,
+                Ordering::Release,
+                Ordering::Acquire,
+                handle,
+            ) {
+                Ok(_) => {
+                    if !cursor.gpupdate.is_null() {
+                        unsafe { handle.retire(cursor.gpupdate.shared()) };
+                    }
+                    if self.help_delete(new_update, handle) {
+                        return true;
+                    }
+                }
+                Err(_) => unsafe { drop(new_update.into_owned()) },
+            }
+        }
+    }
+
+    /// Precondition: the nodes of `op` must be protected.
+    #[inline]
+    fn help(&self, op: Shared<Update<K, V>>, handle: &mut Thread) {
+        match UpdateTag::from_bits_truncate(op.tag()) {
+            UpdateTag::IFLAG => self.help_insert(op, handle),
+            UpdateTag::DFLAG => {
+                let _ = self.help_delete(op, handle);
+            }
+            UpdateTag::MARKED => self.help_marked(op, handle),
+            _ => {}
+        }
+    }
+
+    fn help_delete(&self, op: Shared<Update<K, V>>, handle: &mut Thread) -> bool {
+        // Precondition: op points to a DInfo record (i.e., it is not ⊥)
+        let op_ref = unsafe { op.deref() };
+        let gp_ref = unsafe { op_ref.gp.deref() };
+        let p_ref = unsafe { op_ref.p.deref() };
+        let new_op = op.with_tag(UpdateTag::MARKED.bits());
+
+        // mark CAS
+        match p_ref.update.compare_exchange(
+            op_ref.pupdate,
+            new_op,
+            Ordering::Release,
+            Ordering::Acquire,
+            handle,
+        ) {
+            Ok(_) => {
+                // (prev value) = op → pupdate
+                if !op_ref.pupdate.is_null() {
+                    unsafe { handle.retire(op_ref.pupdate) };
+                }
+                self.help_marked(new_op, handle);
+                true
+            }
+            Err(e) if e.actual == new_op => {
+                // (prev value) = <Mark, op>
+                self.help_marked(new_op, handle);
+                true
+            }
+            Err(_) => {
+                // backtrack CAS
+                let _ = gp_ref.update.compare_exchange(
+                    op.with_tag(UpdateTag::DFLAG.bits()),
+                    op.with_tag(UpdateTag::CLEAN.bits()),
+                    Ordering::Release,
+                    Ordering::Relaxed,
+                    handle,
+                );
+                false
+            }
+        }
+    }
+
+    fn help_marked(&self, op: Shared<Update<K, V>>, handle: &mut Thread) {
+        // Precondition: op points to a DInfo record (i.e., it is not ⊥)
+        let op_ref = unsafe { op.deref() };
+        let gp_node = unsafe { op_ref.gp.deref() };
+
+        // dchild CAS
+        let spliced = gp_node
+            .child(op_ref.gp_p_dir)
+            .compare_exchange(
+                op_ref.p,
+                op_ref.l_other,
+                Ordering::Release,
+                Ordering::Relaxed,
+                handle,
+            )
+            .is_ok();
+
+        // dunflag CAS
+        let _ = gp_node.update.compare_exchange(
+            op.with_tag(UpdateTag::DFLAG.bits()),
+            op.with_tag(UpdateTag::CLEAN.bits()),
+            Ordering::Release,
+            Ordering::Relaxed,
+            handle,
+        );
+
+        // Helpers may still read `p` and `l` until the dunflag CAS.
+        if spliced {
+            unsafe {
+                handle.retire(op_ref.l);
+                handle.retire(op_ref.p);
+            }
+        }
+    }
+
+    fn help_insert(&self, op: Shared<Update<K, V>>, handle: &mut Thread) {
+        // Precondition: op points to an IInfo record (i.e., it is not ⊥)
+        let op_ref = unsafe { op.deref() };
+        let p_node = unsafe { op_ref.p.deref() };
+
+        // ichild CAS
+        let replaced = p_node
+            .child(op_ref.p_l_dir)
+            .compare_exchange(
+                op_ref.l,
+                op_ref.new_internal,
+                Ordering::Release,
+                Ordering::Relaxed,
+                handle,
+            )
+            .is_ok();
+
+        // iunflag CAS
+        let _ = p_node.update.compare_exchange(
+            op.with_tag(UpdateTag::IFLAG.bits()),
+            op.with_tag(UpdateTag::CLEAN.bits()),
+            Ordering::Release,
+            Ordering::Relaxed,
+            handle,
+        );
+
+        // Helpers may still read `l` until the iunflag CAS.
+        if replaced {
+            unsafe { handle.retire(op_ref.l) };
+        }
+    }
+}
+
+impl<K, V> ConcurrentMap<K, V> for EFRBTree<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    type Output = Cursor<K, V>;
+
+    fn new() -> Self {
+        Self::new()
+    }
+
+    #[inline(always)]
+    fn get(&self, key: &K, output: &mut Self::Output, handle: &mut Thread) -> bool {
+        self.get(key, output, handle)
+    }
+
+    #[inline(always)]
+    fn insert(&self, key: K, value: V, output: &mut Self::Output, handle: &mut Thread) -> bool {
+        self.insert(&key, value, output, handle)
+    }
+
+    #[inline(always)]
+    fn remove<'domain, 'hp>(
+        &self,
+        key: &K,
+        output: &mut Self::Output,
+        handle: &mut Thread,
+    ) -> bool {
+        self.delete(key, output, handle)
+    }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EFRBTree;
+    use crate::ds_impl::hp_brcu::concurrent_map;
+
+    #[test]
+    fn smoke_efrb_tree() {
+        concurrent_map::tests::smoke::<EFRBTree<i32, String>>();
+    }
+}
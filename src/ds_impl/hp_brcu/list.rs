@@ -119,6 +119,31 @@ where
         }
     }
 
+    /// Checks that the nodes are sorted and the unmarked ones have distinct keys, and returns the
+    /// keys of the unmarked nodes. Marked nodes that are not unlinked yet may remain.
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        let guard = unsafe { &Unprotected::new() };
+        let mut keys: Vec<&K> = Vec::new();
+        let mut last: Option<&K> = None;
+        let head = unsafe { self.head.load(Ordering::Acquire, guard).as_ref().unwrap() };
+        let mut curr = head.next.load(Ordering::Acquire, guard);
+        while let Some(curr_node) = unsafe { curr.with_tag(0).as_ref() } {
+            if last.map_or(false, |last| *last > curr_node.key) {
+                return Err(format!("the node #{} is out of order", keys.len()));
+            }
+            let next = curr_node.next.load(Ordering::Acquire, guard);
+            if next.tag() == 0 {
+                if keys.last().map_or(false, |last| **last == curr_node.key) {
+                    return Err(format!("the node #{} has a duplicate key", keys.len()));
+                }
+                keys.push(&curr_node.key);
+            }
+            last = Some(&curr_node.key);
+            curr = next;
+        }
+        Ok(keys)
+    }
+
     /// Clean up a chain of logically removed nodes in each traversal.
     #[inline]
     fn harris_traverse(
@@ -355,7 +380,7 @@ where
     {
         loop {
             if !self.get(&find, &key, cursor, thread) {
-                return true;
+                return false;
             }
 
             let curr_node = cursor.curr.as_ref().unwrap();
@@ -429,6 +454,10 @@ where
         self.inner
             .remove(&List::harris_traverse, key, output, thread)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HMList<K, V> {
@@ -475,6 +504,10 @@ where
         self.inner
             .remove(&List::harris_michael_traverse, key, output, thread)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HHSList<K, V> {
@@ -521,6 +554,10 @@ where
         self.inner
             .remove(&List::harris_traverse, key, output, thread)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 #[test]
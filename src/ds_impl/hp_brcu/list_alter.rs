@@ -183,6 +183,34 @@ where
         }
     }
 
+    /// Checks that the nodes are sorted and the unmarked ones have distinct keys, and returns the
+    /// keys of the unmarked nodes. Marked nodes that are not unlinked yet may remain.
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        let guard = unsafe { &Unprotected::new() };
+        let mut keys: Vec<&K> = Vec::new();
+        let mut last: Option<&Option<K>> = None;
+        let head = unsafe { self.head.load(Ordering::Acquire, guard).as_ref().unwrap() };
+        let mut curr = head.next.load(Ordering::Acquire, guard);
+        while let Some(curr_node) = unsafe { curr.with_tag(0).as_ref() } {
+            if last.map_or(false, |last| *last > curr_node.key) {
+                return Err(format!("the node #{} is out of order", keys.len()));
+            }
+            let next = curr_node.next.load(Ordering::Acquire, guard);
+            if next.tag() == 0 {
+                if keys
+                    .last()
+                    .map_or(false, |last| Some(*last) == curr_node.key.as_ref())
+                {
+                    return Err(format!("the node #{} has a duplicate key", keys.len()));
+                }
+                keys.push(curr_node.key.as_ref().unwrap());
+            }
+            last = Some(&curr_node.key);
+            curr = next;
+        }
+        Ok(keys)
+    }
+
     #[inline]
     fn harris_traverse(
         &self,
@@ -422,7 +450,7 @@ where
     {
         loop {
             if !self.get(&find, &key, output, thread) {
-                return true;
+                return false;
             }
             let cursor = &mut output.0;
 
@@ -539,6 +567,10 @@ where
         self.inner
             .remove(&List::harris_traverse, key, output, thread)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HMList<K, V> {
@@ -585,6 +617,10 @@ where
         self.inner
             .remove(&List::harris_michael_traverse, key, output, thread)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HHSList<K, V> {
@@ -642,6 +678,10 @@ where
         self.inner
             .remove(&List::harris_traverse, key, output, thread)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 #[test]
@@ -658,3 +698,16 @@ fn smoke_hm_list() {
 fn smoke_hhs_list() {
     super::concurrent_map::tests::smoke::<HHSList<i32, String>>();
 }
+
+#[test]
+fn remove_absent() {
+    hp_brcu::THREAD.with(|thread| {
+        let thread = &mut **thread.borrow_mut();
+        let map = &HHSList::<i32, String>::new();
+        let output = &mut HHSList::<i32, String>::empty_output(thread);
+        assert!(!map.remove(&0, output, thread));
+        assert!(map.insert(0, 0.to_string(), output, thread));
+        assert!(map.remove(&0, output, thread));
+        assert!(!map.remove(&0, output, thread));
+    });
+}
@@ -1,5 +1,7 @@
 pub mod concurrent_map;
 
+mod bonsai_tree;
+mod ellen_tree;
 mod list;
 pub mod list_alter;
 mod michael_hash_map;
@@ -7,6 +9,8 @@ mod natarajan_mittal_tree;
 mod skip_list;
 
 pub use self::concurrent_map::ConcurrentMap;
+pub use bonsai_tree::BonsaiTreeMap;
+pub use ellen_tree::EFRBTree;
 pub use list::{HHSList, HList, HMList};
 pub use michael_hash_map::HashMap;
 pub use natarajan_mittal_tree::NMTreeMap;
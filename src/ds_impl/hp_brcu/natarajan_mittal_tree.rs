@@ -251,6 +251,52 @@ where
         NMTreeMap { r }
     }
 
+    /// Checks that the tree is a leaf-oriented BST without marked edges, and returns the keys of
+    /// its leaves in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let guard = unsafe { &Unprotected::new() };
+        let mut keys = Vec::new();
+        // Nodes with the bounds of the keys of their leaves, in the order of a DFS.
+        let mut stack = vec![
+            (
+                self.r.right.load(Ordering::Acquire, guard),
+                Some(&self.r.key),
+                None,
+            ),
+            (
+                self.r.left.load(Ordering::Acquire, guard),
+                None,
+                Some(&self.r.key),
+            ),
+        ];
+        while let Some((node, lo, hi)) = stack.pop() {
+            if node.tag() != 0 {
+                return Err("a marked edge remains".to_string());
+            }
+            let node_ref = unsafe { node.as_ref().unwrap() };
+            let left = node_ref.left.load(Ordering::Acquire, guard);
+            let right = node_ref.right.load(Ordering::Acquire, guard);
+            match (left.is_null(), right.is_null()) {
+                (true, true) => {
+                    if let Key::Fin(key) = &node_ref.key {
+                        if lo.map_or(false, |lo: &Key<K>| node_ref.key < *lo)
+                            || hi.map_or(false, |hi: &Key<K>| node_ref.key >= *hi)
+                        {
+                            return Err("a leaf is in the wrong subtree".to_string());
+                        }
+                        keys.push(key);
+                    }
+                }
+                (false, false) => {
+                    stack.push((right, Some(&node_ref.key), hi));
+                    stack.push((left, lo, Some(&node_ref.key)));
+                }
+                _ => return Err("an internal node has a single child".to_string()),
+            }
+        }
+        Ok(keys)
+    }
+
     /// All `Shared<_>` fields are unmarked.
     fn seek(&self, key: &K, output: &mut SeekRecord<K, V>, handle: &mut Thread) {
         unsafe {
@@ -553,6 +599,10 @@ where
     ) -> bool {
         self.remove(key, output, handle)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
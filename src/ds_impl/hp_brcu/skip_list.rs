@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     mem::swap,
     sync::atomic::{fence, AtomicUsize, Ordering},
 };
@@ -44,12 +45,12 @@ impl<K, V> Node<K, V> {
 
     fn generate_height() -> usize {
         // returns 1 with probability 3/4
-        if rand::random::<usize>() % 4 < 3 || MAX_HEIGHT == 1 {
+        if crate::utils::random::<usize>() % 4 < 3 || MAX_HEIGHT == 1 {
             return 1;
         }
         // returns h with probability 2^(−(h+1))
         let mut height = 2;
-        while height < MAX_HEIGHT && rand::random::<bool>() {
+        while height < MAX_HEIGHT && crate::utils::random::<bool>() {
             height += 1;
         }
         height
@@ -145,6 +146,43 @@ where
         }
     }
 
+    /// Checks that each level is sorted and only links the unmarked nodes of the lower levels
+    /// that are tall enough, and returns the keys of the unmarked nodes. Marked nodes that are not
+    /// unlinked yet may remain.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let guard = unsafe { &Unprotected::new() };
+        let mut keys: Vec<&K> = Vec::new();
+        // The unmarked nodes of the level below.
+        let mut lower = HashSet::new();
+        for level in 0..MAX_HEIGHT {
+            let mut nodes = HashSet::new();
+            let mut last: Option<&K> = None;
+            let mut curr = self.head[level].load(Ordering::Acquire, guard);
+            while let Some(node) = unsafe { curr.with_tag(0).as_ref() } {
+                if last.map_or(false, |last| *last > node.key) {
+                    return Err(format!("level {level} is out of order"));
+                }
+                last = Some(&node.key);
+                curr = node.next[level].load(Ordering::Acquire, guard);
+                if node.next[0].load(Ordering::Acquire, guard).tag() & 1 != 0 {
+                    continue;
+                }
+                if level >= node.height || (level > 0 && !lower.contains(&(node as *const _))) {
+                    return Err(format!("level {level} links a node not in the lower level"));
+                }
+                if level == 0 {
+                    if keys.last().map_or(false, |last| **last == node.key) {
+                        return Err("duplicate keys".to_string());
+                    }
+                    keys.push(&node.key);
+                }
+                nodes.insert(node as *const Node<K, V>);
+            }
+            lower = nodes;
+        }
+        Ok(keys)
+    }
+
     fn find_optimistic_inner<'r>(
         &self,
         key: &K,
@@ -445,6 +483,10 @@ where
     ) -> bool {
         self.remove(key, output, handle)
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
@@ -415,7 +415,7 @@ where
             let curr_node = unsafe { &*cursor.curr };
             let next = curr_node.next.fetch_or(1, Ordering::AcqRel);
             let next_tag = tag(next);
-            if next_tag == 1 {
+            if (next_tag & 1) != 0 {
                 continue;
             }
 
@@ -510,6 +510,29 @@ where
         self.remove(key, Cursor::find_harris, handle)
     }
 
+    /// Checks that the nodes are sorted and the unmarked ones have distinct keys, and returns the
+    /// keys of the unmarked nodes. Marked nodes that are not unlinked yet may remain.
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys: Vec<&K> = Vec::new();
+        let mut last: Option<&K> = None;
+        let mut curr = self.head.load(Ordering::Acquire);
+        while let Some(curr_node) = unsafe { untagged(curr).as_ref() } {
+            if last.map_or(false, |last| *last > curr_node.key) {
+                return Err(format!("the node #{} is out of order", keys.len()));
+            }
+            let next = curr_node.next.load(Ordering::Acquire);
+            if tag(next) == 0 {
+                if keys.last().map_or(false, |last| **last == curr_node.key) {
+                    return Err(format!("the node #{} has a duplicate key", keys.len()));
+                }
+                keys.push(&curr_node.key);
+            }
+            last = Some(&curr_node.key);
+            curr = next;
+        }
+        Ok(keys)
+    }
+
     pub fn harris_michael_get<'domain, 'hp>(
         &self,
         key: &K,
@@ -583,6 +606,9 @@ where
     ) -> Option<&'hp V> {
         self.inner.harris_remove(key, handle)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HMList<K, V> {
@@ -639,6 +665,9 @@ where
     ) -> Option<&'hp V> {
         self.inner.harris_michael_remove(key, handle)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HHSList<K, V> {
@@ -691,6 +720,9 @@ where
     ) -> Option<&'hp V> {
         self.inner.harris_remove(key, handle)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,661 @@
+//! An update clones keys and values and allocates nodes, which are not permitted in a read phase,
+//! and it touches too many nodes to protect all of them. So it reads the old version in a number
+//! of short read phases, each of which begins by checking that the root is still the one loaded
+//! at the beginning of the update.
+//!
+//! The loaded root is protected, so it is not reused while the update runs. Thus, if a read phase
+//! observes the same root, no update has been committed since, and no node of the version is
+//! retired yet. A node is cloned in a write phase after it is protected in such a read phase.
+//!
+//! As the nodes of a version are not modified, the children of a retired node are not marked.
+
+use super::concurrent_map::ConcurrentMap;
+use nbr::{read_phase, Guard, Shield};
+use std::cmp;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+static WEIGHT: usize = 2;
+
+// TODO: optimization from the paper? IBR paper doesn't do that
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    size: usize,
+    left: AtomicPtr<Node<K, V>>,
+    right: AtomicPtr<Node<K, V>>,
+}
+
+impl<K, V> Node<K, V> {
+    fn node_size(node: *mut Self) -> usize {
+        if let Some(node_ref) = unsafe { node.as_ref() } {
+            node_ref.size
+        } else {
+            0
+        }
+    }
+}
+
+pub struct Handle {
+    /// Protects the root loaded by the current update.
+    root: Shield,
+    /// Protects the node whose key and value are being cloned.
+    node: Shield,
+    /// Protects the node whose value is returned.
+    output: Shield,
+}
+
+/// Each op creates a new local state and tries to update (CAS) the tree with it.
+///
+/// Every step returns `Err(())` if the root has changed. Then the op aborts the state and retries.
+struct State<'g, K, V> {
+    root_link: &'g AtomicPtr<Node<K, V>>,
+    curr_root: *mut Node<K, V>,
+    shield: &'g Shield,
+    /// Nodes that current op wants to remove from the tree. Should be retired if CAS succeeds.
+    /// (`retire`). If not, ignore.
+    retired_nodes: Vec<*mut Node<K, V>>,
+    /// Nodes newly constructed by the op. Should be destroyed if CAS fails. (`destroy`)
+    new_nodes: Vec<*mut Node<K, V>>,
+}
+
+impl<'g, K, V> State<'g, K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn new(root_link: &'g AtomicPtr<Node<K, V>>, shield: &'g Shield) -> Self {
+        Self {
+            root_link,
+            curr_root: ptr::null_mut(),
+            shield,
+            retired_nodes: Vec::new(),
+            new_nodes: Vec::new(),
+        }
+    }
+
+    fn load_root(&mut self, shield: &Shield, guard: &Guard) {
+        let mut root;
+        read_phase!(guard => {
+            root = self.root_link.load(Ordering::Acquire);
+            shield.protect(root);
+        });
+        self.curr_root = root;
+    }
+
+    /// Runs `f` in a read phase if the root has not changed since `load_root`.
+    ///
+    /// `f` may read the nodes of the loaded version, but must not allocate.
+    fn read<R: Copy>(&self, guard: &Guard, f: impl Fn() -> R) -> Result<R, ()> {
+        let mut result;
+        read_phase!(guard => {
+            result = if self.root_link.load(Ordering::Acquire) == self.curr_root {
+                Some(f())
+            } else {
+                None
+            };
+        });
+        result.ok_or(())
+    }
+
+    /// Reads the children of `node`.
+    fn children(
+        &self,
+        node: *mut Node<K, V>,
+        guard: &Guard,
+    ) -> Result<(*mut Node<K, V>, *mut Node<K, V>), ()> {
+        self.read(guard, || {
+            let node_ref = unsafe { &*node };
+            (
+                node_ref.left.load(Ordering::Acquire),
+                node_ref.right.load(Ordering::Acquire),
+            )
+        })
+    }
+
+    /// Clones the key and the value of `node` after protecting it.
+    fn clone_node(&self, node: *mut Node<K, V>, guard: &Guard) -> Result<(K, V), ()> {
+        self.read(guard, || self.shield.protect(node))?;
+        let node_ref = unsafe { &*node };
+        Ok((node_ref.key.clone(), node_ref.value.clone()))
+    }
+
+    /// Destroy the newly created state (self) that lost the race (reclaim_state)
+    fn abort(&mut self) {
+        self.retired_nodes.clear();
+
+        for node in self.new_nodes.drain(..) {
+            drop(unsafe { Box::from_raw(node) });
+        }
+    }
+
+    /// Retire the old state replaced by the new_state and the new_state.retired_nodes
+    fn commit(&mut self, guard: &Guard) {
+        self.new_nodes.clear();
+
+        for node in self.retired_nodes.drain(..) {
+            unsafe { guard.retire(node) };
+        }
+    }
+
+    fn retire_node(&mut self, node: *mut Node<K, V>) {
+        self.retired_nodes.push(node);
+    }
+
+    fn add_new_node(&mut self, node: *mut Node<K, V>) {
+        self.new_nodes.push(node);
+    }
+
+    fn mk_node(
+        &mut self,
+        left: *mut Node<K, V>,
+        right: *mut Node<K, V>,
+        key: K,
+        value: V,
+        guard: &Guard,
+    ) -> Result<*mut Node<K, V>, ()> {
+        let size = self.read(guard, || Node::node_size(left) + Node::node_size(right) + 1)?;
+        let new_node = Box::into_raw(Box::new(Node {
+            key,
+            value,
+            size,
+            left: AtomicPtr::new(left),
+            right: AtomicPtr::new(right),
+        }));
+        self.add_new_node(new_node);
+        Ok(new_node)
+    }
+
+    /// Make a new balanced tree from cur (the root of a subtree) and newly constructed left and right subtree
+    fn mk_balanced(
+        &mut self,
+        cur: *mut Node<K, V>,
+        left: *mut Node<K, V>,
+        right: *mut Node<K, V>,
+        guard: &Guard,
+    ) -> Result<*mut Node<K, V>, ()> {
+        let (key, value) = self.clone_node(cur, guard)?;
+
+        let (l_size, r_size) =
+            self.read(guard, || (Node::node_size(left), Node::node_size(right)))?;
+        let res = if r_size > 0
+            && ((l_size > 0 && r_size > WEIGHT * l_size) || (l_size == 0 && r_size > WEIGHT))
+        {
+            self.mk_balanced_left(left, right, key, value, guard)?
+        } else if l_size > 0
+            && ((r_size > 0 && l_size > WEIGHT * r_size) || (r_size == 0 && l_size > WEIGHT))
+        {
+            self.mk_balanced_right(left, right, key, value, guard)?
+        } else {
+            self.mk_node(left, right, key, value, guard)?
+        };
+        self.retire_node(cur);
+        Ok(res)
+    }
+
+    #[inline]
+    fn mk_balanced_left(
+        &mut self,
+        left: *mut Node<K, V>,
+        right: *mut Node<K, V>,
+        key: K,
+        value: V,
+        guard: &Guard,
+    ) -> Result<*mut Node<K, V>, ()> {
+        let (right_left, right_right) = self.children(right, guard)?;
+        let (right_left_size, right_right_size) = self.read(guard, || {
+            (Node::node_size(right_left), Node::node_size(right_right))
+        })?;
+
+        if right_left_size < right_right_size {
+            // single left rotation
+            return self.single_left(left, right, right_left, right_right, key, value, guard);
+        }
+
+        // double left rotation
+        self.double_left(left, right, right_left, right_right, key, value, guard)
+    }
+
+    #[inline]
+    fn single_left(
+        &mut self,
+        left: *mut Node<K, V>,
+        right: *mut Node<K, V>,
+        right_left: *mut Node<K, V>,
+        right_right: *mut Node<K, V>,
+        key: K,
+        value: V,
+        guard: &Guard,
+    ) -> Result<*mut Node<K, V>, ()> {
+        let new_left = self.mk_node(left, right_left, key, value, guard)?;
+        let (right_key, right_value) = self.clone_node(right, guard)?;
+        let res = self.mk_node(new_left, right_right, right_key, right_value, guard)?;
+        self.retire_node(right);
+        Ok(res)
+    }
+
+    #[inline]
+    fn double_left(
+        &mut self,
+        left: *mut Node<K, V>,
+        right: *mut Node<K, V>,
+        right_left: *mut Node<K, V>,
+        right_right: *mut Node<K, V>,
+        key: K,
+        value: V,
+        guard: &Guard,
+    ) -> Result<*mut Node<K, V>, ()> {
+        let (right_left_left, right_left_right) = self.children(right_left, guard)?;
+
+        let new_left = self.mk_node(left, right_left_left, key, value, guard)?;
+        let (right_key, right_value) = self.clone_node(right, guard)?;
+        let new_right =
+            self.mk_node(right_left_right, right_right, right_key, right_value, guard)?;
+        let (right_left_key, right_left_value) = self.clone_node(right_left, guard)?;
+        let res = self.mk_node(new_left, new_right, right_left_key, right_left_value, guard)?;
+        self.retire_node(right_left);
+        self.retire_node(right);
+        Ok(res)
+    }
+
+    #[inline]
+    fn mk_balanced_right(
+        &mut self,
+        left: *mut Node<K, V>,
+        right: *mut Node<K, V>,
+        key: K,
+        value: V,
+        guard: &Guard,
+    ) -> Result<*mut Node<K, V>, ()> {
+        let (left_left, left_right) = self.children(left, guard)?;
+        let (left_right_size, left_left_size) = self.read(guard, || {
+            (Node::node_size(left_right), Node::node_size(left_left))
+        })?;
+
+        if left_right_size < left_left_size {
+            // single right rotation (fig 3)
+            return self.single_right(left, right, left_right, left_left, key, value, guard);
+        }
+        // double right rotation
+        self.double_right(left, right, left_right, left_left, key, value, guard)
+    }
+
+    #[inline]
+    fn single_right(
+        &mut self,
+        left: *mut Node<K, V>,
+        right: *mut Node<K, V>,
+        left_right: *mut Node<K, V>,
+        left_left: *mut Node<K, V>,
+        key: K,
+        value: V,
+        guard: &Guard,
+    ) -> Result<*mut Node<K, V>, ()> {
+        let new_right = self.mk_node(left_right, right, key, value, guard)?;
+        let (left_key, left_value) = self.clone_node(left, guard)?;
+        let res = self.mk_node(left_left, new_right, left_key, left_value, guard)?;
+        self.retire_node(left);
+        Ok(res)
+    }
+
+    #[inline]
+    fn double_right(
+        &mut self,
+        left: *mut Node<K, V>,
+        right: *mut Node<K, V>,
+        left_right: *mut Node<K, V>,
+        left_left: *mut Node<K, V>,
+        key: K,
+        value: V,
+        guard: &Guard,
+    ) -> Result<*mut Node<K, V>, ()> {
+        let (left_right_left, left_right_right) = self.children(left_right, guard)?;
+
+        let (left_key, left_value) = self.clone_node(left, guard)?;
+        let new_left = self.mk_node(left_left, left_right_left, left_key, left_value, guard)?;
+        let new_right = self.mk_node(left_right_right, right, key, value, guard)?;
+        let (left_right_key, left_right_value) = self.clone_node(left_right, guard)?;
+        let res = self.mk_node(new_left, new_right, left_right_key, left_right_value, guard)?;
+        self.retire_node(left_right);
+        self.retire_node(left);
+        Ok(res)
+    }
+
+    #[inline]
+    fn do_insert(
+        &mut self,
+        node: *mut Node<K, V>,
+        key: &K,
+        value: &V,
+        guard: &Guard,
+    ) -> Result<(*mut Node<K, V>, bool), ()> {
+        if node.is_null() {
+            return Ok((
+                self.mk_node(
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    key.clone(),
+                    value.clone(),
+                    guard,
+                )?,
+                true,
+            ));
+        }
+
+        let (left, right, ord) = self.read(guard, || {
+            let node_ref = unsafe { &*node };
+            (
+                node_ref.left.load(Ordering::Acquire),
+                node_ref.right.load(Ordering::Acquire),
+                node_ref.key.cmp(key),
+            )
+        })?;
+
+        match ord {
+            cmp::Ordering::Equal => Ok((node, false)),
+            cmp::Ordering::Less => {
+                let (new_right, inserted) = self.do_insert(right, key, value, guard)?;
+                Ok((self.mk_balanced(node, left, new_right, guard)?, inserted))
+            }
+            cmp::Ordering::Greater => {
+                let (new_left, inserted) = self.do_insert(left, key, value, guard)?;
+                Ok((self.mk_balanced(node, new_left, right, guard)?, inserted))
+            }
+        }
+    }
+
+    /// Returns the new root and the removed node, if any.
+    #[inline]
+    fn do_remove(
+        &mut self,
+        node: *mut Node<K, V>,
+        key: &K,
+        guard: &Guard,
+    ) -> Result<(*mut Node<K, V>, Option<*mut Node<K, V>>), ()> {
+        if node.is_null() {
+            return Ok((ptr::null_mut(), None));
+        }
+
+        let (left, right, ord, size) = self.read(guard, || {
+            let node_ref = unsafe { &*node };
+            (
+                node_ref.left.load(Ordering::Acquire),
+                node_ref.right.load(Ordering::Acquire),
+                node_ref.key.cmp(key),
+                node_ref.size,
+            )
+        })?;
+
+        match ord {
+            cmp::Ordering::Equal => {
+                let removed = Some(node);
+                self.retire_node(node);
+                if size == 1 {
+                    return Ok((ptr::null_mut(), removed));
+                }
+
+                if !left.is_null() {
+                    let (new_left, succ) = self.pull_rightmost(left, guard)?;
+                    return Ok((self.mk_balanced(succ, new_left, right, guard)?, removed));
+                }
+                let (new_right, succ) = self.pull_leftmost(right, guard)?;
+                Ok((self.mk_balanced(succ, left, new_right, guard)?, removed))
+            }
+            cmp::Ordering::Less => {
+                let (new_right, removed) = self.do_remove(right, key, guard)?;
+                Ok((self.mk_balanced(node, left, new_right, guard)?, removed))
+            }
+            cmp::Ordering::Greater => {
+                let (new_left, removed) = self.do_remove(left, key, guard)?;
+                Ok((self.mk_balanced(node, new_left, right, guard)?, removed))
+            }
+        }
+    }
+
+    fn pull_leftmost(
+        &mut self,
+        node: *mut Node<K, V>,
+        guard: &Guard,
+    ) -> Result<(*mut Node<K, V>, *mut Node<K, V>), ()> {
+        let (left, right) = self.children(node, guard)?;
+
+        if !left.is_null() {
+            let (new_left, succ) = self.pull_leftmost(left, guard)?;
+            return Ok((self.mk_balanced(node, new_left, right, guard)?, succ));
+        }
+        // node is the leftmost
+        let (key, value) = self.clone_node(node, guard)?;
+        let succ = self.mk_node(ptr::null_mut(), ptr::null_mut(), key, value, guard)?;
+        self.retire_node(node);
+        Ok((right, succ))
+    }
+
+    fn pull_rightmost(
+        &mut self,
+        node: *mut Node<K, V>,
+        guard: &Guard,
+    ) -> Result<(*mut Node<K, V>, *mut Node<K, V>), ()> {
+        let (left, right) = self.children(node, guard)?;
+
+        if !right.is_null() {
+            let (new_right, succ) = self.pull_rightmost(right, guard)?;
+            return Ok((self.mk_balanced(node, left, new_right, guard)?, succ));
+        }
+        // node is the rightmost
+        let (key, value) = self.clone_node(node, guard)?;
+        let succ = self.mk_node(ptr::null_mut(), ptr::null_mut(), key, value, guard)?;
+        self.retire_node(node);
+        Ok((left, succ))
+    }
+}
+
+pub struct BonsaiTreeMap<K, V> {
+    root: AtomicPtr<Node<K, V>>,
+}
+
+impl<K, V> Default for BonsaiTreeMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> BonsaiTreeMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            root: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Checks that the tree is a BST with correct sizes, and returns its
+    /// keys in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        /// Checks the subtree at `node` whose keys are in `(lo, hi)`, and returns its size.
+        fn check<'g, K: Ord + Clone, V: Clone + 'g>(
+            node: *mut Node<K, V>,
+            lo: Option<&K>,
+            hi: Option<&K>,
+            keys: &mut Vec<&'g K>,
+        ) -> Result<usize, String> {
+            let node_ref: &'g Node<K, V> = some_or!(unsafe { node.as_ref() }, return Ok(0));
+            if lo.map_or(false, |lo| node_ref.key <= *lo)
+                || hi.map_or(false, |hi| node_ref.key >= *hi)
+            {
+                return Err("a node is in the wrong subtree".to_string());
+            }
+            let left = node_ref.left.load(Ordering::Acquire);
+            let right = node_ref.right.load(Ordering::Acquire);
+            let left_size = check(left, lo, Some(&node_ref.key), keys)?;
+            keys.push(&node_ref.key);
+            let right_size = check(right, Some(&node_ref.key), hi, keys)?;
+            if node_ref.size != left_size + right_size + 1 {
+                return Err("a node has a wrong size".to_string());
+            }
+            Ok(node_ref.size)
+        }
+
+        let mut keys = Vec::new();
+        check(self.root.load(Ordering::Acquire), None, None, &mut keys)?;
+        Ok(keys)
+    }
+
+    pub fn get<'g>(&'g self, key: &'g K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
+        let mut found;
+        read_phase!(guard => {
+            let mut node = self.root.load(Ordering::Acquire);
+            while let Some(node_ref) = unsafe { node.as_ref() } {
+                match key.cmp(&node_ref.key) {
+                    cmp::Ordering::Equal => break,
+                    cmp::Ordering::Less => node = node_ref.left.load(Ordering::Acquire),
+                    cmp::Ordering::Greater => node = node_ref.right.load(Ordering::Acquire),
+                }
+            }
+            handle.output.protect(node);
+            found = node;
+        });
+        unsafe { found.as_ref() }.map(|node| &node.value)
+    }
+
+    pub fn insert(&self, key: K, value: V, handle: &mut Handle, guard: &Guard) -> bool {
+        let mut state = State::new(&self.root, &handle.node);
+        loop {
+            state.load_root(&handle.root, guard);
+            let old_root = state.curr_root;
+            let (new_root, inserted) = ok_or!(state.do_insert(old_root, &key, &value, guard), {
+                state.abort();
+                continue;
+            });
+
+            if self
+                .root
+                .compare_exchange(old_root, new_root, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                state.commit(guard);
+                return inserted;
+            }
+
+            state.abort();
+        }
+    }
+
+    pub fn remove<'g>(
+        &'g self,
+        key: &'g K,
+        handle: &mut Handle,
+        guard: &'g Guard,
+    ) -> Option<&'g V> {
+        let mut state = State::new(&self.root, &handle.node);
+        loop {
+            state.load_root(&handle.root, guard);
+            let old_root = state.curr_root;
+            let (new_root, removed) = ok_or!(state.do_remove(old_root, key, guard), {
+                state.abort();
+                continue;
+            });
+
+            // Protect the removed node before it is retired by the commit.
+            if let Some(removed) = removed {
+                if state
+                    .read(guard, || handle.output.protect(removed))
+                    .is_err()
+                {
+                    state.abort();
+                    continue;
+                }
+            }
+
+            if self
+                .root
+                .compare_exchange(old_root, new_root, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                state.commit(guard);
+                return removed.map(|node| &unsafe { &*node }.value);
+            }
+
+            state.abort();
+        }
+    }
+}
+
+impl<K, V> Drop for BonsaiTreeMap<K, V> {
+    fn drop(&mut self) {
+        unsafe {
+            let mut stack = vec![self.root.load(Ordering::Relaxed)];
+
+            while let Some(node) = stack.pop() {
+                if node.is_null() {
+                    continue;
+                }
+
+                let node_ref = &*node;
+
+                stack.push(node_ref.left.load(Ordering::Relaxed));
+                stack.push(node_ref.right.load(Ordering::Relaxed));
+                drop(Box::from_raw(node));
+            }
+        }
+    }
+}
+
+impl<K, V> ConcurrentMap<K, V> for BonsaiTreeMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    type Handle = Handle;
+
+    fn new() -> Self {
+        Self::new()
+    }
+
+    fn handle(guard: &mut Guard) -> Self::Handle {
+        Self::Handle {
+            root: guard.acquire_shield().unwrap(),
+            node: guard.acquire_shield().unwrap(),
+            output: guard.acquire_shield().unwrap(),
+        }
+    }
+
+    #[inline(always)]
+    fn get<'g>(&'g self, key: &'g K, handle: &mut Self::Handle, guard: &'g Guard) -> Option<&'g V> {
+        self.get(key, handle, guard)
+    }
+    #[inline(always)]
+    fn insert(&self, key: K, value: V, handle: &mut Self::Handle, guard: &Guard) -> bool {
+        self.insert(key, value, handle, guard)
+    }
+    #[inline(always)]
+    fn remove<'g>(
+        &'g self,
+        key: &'g K,
+        handle: &mut Self::Handle,
+        guard: &'g Guard,
+    ) -> Option<&'g V> {
+        self.remove(key, handle, guard)
+    }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BonsaiTreeMap;
+    use crate::ds_impl::nbr::concurrent_map;
+
+    #[test]
+    fn smoke_bonsai_tree() {
+        concurrent_map::tests::smoke::<BonsaiTreeMap<i32, String>>();
+    }
+}
@@ -13,6 +13,10 @@ pub trait ConcurrentMap<K, V> {
         handle: &mut Self::Handle,
         guard: &'g Guard,
     ) -> Option<&'g V>;
+
+    /// Checks the structural invariants of the map and returns its keys. It must not run
+    /// concurrently with the other operations.
+    fn validate(&self) -> Result<Vec<&K>, String>;
 }
 
 #[cfg(test)]
@@ -26,11 +30,13 @@ pub mod tests {
 
     const THREADS: i32 = 30;
     const ELEMENTS_PER_THREADS: i32 = 1000;
+    /// Enough for the predecessors and the successors of every level of the skip list.
+    const MAX_HAZPTRS: usize = 65;
 
     /// `max_hazptr_per_thread` depends on the data structure.
     pub fn smoke<M: ConcurrentMap<i32, String> + Send + Sync>() {
         let map = &M::new();
-        let collector = Arc::new(Collector::new(THREADS as usize, 256, 32, 16));
+        let collector = Arc::new(Collector::new(THREADS as usize, 256, 32, MAX_HAZPTRS));
 
         thread::scope(|s| {
             for t in 0..THREADS {
@@ -80,5 +86,13 @@ pub mod tests {
             }
         })
         .unwrap();
+
+        let mut keys = map.validate().unwrap();
+        keys.sort();
+        let mut expected = ((THREADS / 2)..THREADS)
+            .flat_map(|t| (0..ELEMENTS_PER_THREADS).map(move |k| k * THREADS + t))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert!(keys.into_iter().eq(expected.iter()));
     }
 }
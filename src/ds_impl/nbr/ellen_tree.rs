@@ -0,0 +1,641 @@
+//! The search runs in a read phase, and the write phase only touches the nodes and Info records
+//! which were protected at the end of the read phase.
+//!
+//! To make the protection of an Info record's fields sound, the tree nodes referred by a flagged
+//! Info record are retired only after the corresponding unflag CAS. Thus, if a read phase observes
+//! a flagged update, the fields of the update were not retired before the read phase began.
+
+use super::concurrent_map::ConcurrentMap;
+use hp_pp::{tag, tagged, untagged};
+use nbr::{read_phase, Guard, Shield};
+use std::cmp;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    struct UpdateTag: usize {
+        const CLEAN = 0b00;
+        const DFLAG = 0b01;
+        const IFLAG = 0b10;
+        const MARKED = 0b11;
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Key<K> {
+    Fin(K),
+    Inf1,
+    Inf2,
+}
+
+impl<K> PartialOrd for Key<K>
+where
+    K: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        match (self, other) {
+            (Key::Fin(k1), Key::Fin(k2)) => k1.partial_cmp(k2),
+            (Key::Fin(_), Key::Inf1) => Some(cmp::Ordering::Less),
+            (Key::Fin(_), Key::Inf2) => Some(cmp::Ordering::Less),
+            (Key::Inf1, Key::Fin(_)) => Some(cmp::Ordering::Greater),
+            (Key::Inf1, Key::Inf1) => Some(cmp::Ordering::Equal),
+            (Key::Inf1, Key::Inf2) => Some(cmp::Ordering::Less),
+            (Key::Inf2, Key::Fin(_)) => Some(cmp::Ordering::Greater),
+            (Key::Inf2, Key::Inf1) => Some(cmp::Ordering::Greater),
+            (Key::Inf2, Key::Inf2) => Some(cmp::Ordering::Equal),
+        }
+    }
+}
+
+impl<K> PartialEq<K> for Key<K>
+where
+    K: PartialEq,
+{
+    fn eq(&self, rhs: &K) -> bool {
+        match self {
+            Key::Fin(k) => k == rhs,
+            _ => false,
+        }
+    }
+}
+
+impl<K> PartialOrd<K> for Key<K>
+where
+    K: PartialOrd,
+{
+    fn partial_cmp(&self, rhs: &K) -> Option<cmp::Ordering> {
+        match self {
+            Key::Fin(k) => k.partial_cmp(rhs),
+            _ => Some(cmp::Ordering::Greater),
+        }
+    }
+}
+
+impl<K> Key<K>
+where
+    K: Ord,
+{
+    fn cmp(&self, rhs: &K) -> cmp::Ordering {
+        match self {
+            Key::Fin(k) => k.cmp(rhs),
+            _ => cmp::Ordering::Greater,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Direction {
+    L,
+    R,
+}
+
+pub struct Node<K, V> {
+    key: Key<K>,
+    value: Option<V>,
+    // tag on low bits: {Clean, DFlag, IFlag, Mark}
+    update: AtomicPtr<Update<K, V>>,
+    left: AtomicPtr<Node<K, V>>,
+    right: AtomicPtr<Node<K, V>>,
+    is_leaf: bool,
+}
+
+pub struct Update<K, V> {
+    gp: *mut Node<K, V>,
+    p: *mut Node<K, V>,
+    l: *mut Node<K, V>,
+    l_other: *mut Node<K, V>,
+    gp_p_dir: Direction,
+    p_l_dir: Direction,
+    pupdate: *mut Update<K, V>,
+    new_internal: *mut Node<K, V>,
+}
+
+impl<K, V> Node<K, V> {
+    pub fn internal(key: Key<K>, value: Option<V>, left: Self, right: Self) -> Self {
+        Self {
+            key,
+            value,
+            update: AtomicPtr::new(ptr::null_mut()),
+            left: AtomicPtr::new(Box::into_raw(Box::new(left))),
+            right: AtomicPtr::new(Box::into_raw(Box::new(right))),
+            is_leaf: false,
+        }
+    }
+
+    pub fn leaf(key: Key<K>, value: Option<V>) -> Self {
+        Self {
+            key,
+            value,
+            update: AtomicPtr::new(ptr::null_mut()),
+            left: AtomicPtr::new(ptr::null_mut()),
+            right: AtomicPtr::new(ptr::null_mut()),
+            is_leaf: true,
+        }
+    }
+
+    #[inline]
+    fn child(&self, dir: Direction) -> &AtomicPtr<Self> {
+        match dir {
+            Direction::L => &self.left,
+            Direction::R => &self.right,
+        }
+    }
+}
+
+pub struct Handle {
+    gp: Shield,
+    p: Shield,
+    l: Shield,
+    pupdate: Shield,
+    gpupdate: Shield,
+    // Protect a new update of this thread.
+    aux_update: Shield,
+    // Protect the nodes of an update which is currently being helped. (`gp` of the update is
+    // protected by `gp`, as the cursor's `gp` is not used when there is an update to help.)
+    help_p: Shield,
+    help_l: Shield,
+}
+
+struct Cursor<K, V> {
+    gp: *mut Node<K, V>,
+    p: *mut Node<K, V>,
+    l: *mut Node<K, V>,
+    l_other: *mut Node<K, V>,
+    gp_p_dir: Direction,
+    p_l_dir: Direction,
+    pupdate: *mut Update<K, V>,
+    gpupdate: *mut Update<K, V>,
+    /// A flagged update of `gp` or `p` (in this order), whose nodes are protected instead of
+    /// `gp`.
+    help: *mut Update<K, V>,
+}
+
+pub struct EFRBTree<K, V> {
+    root: AtomicPtr<Node<K, V>>,
+}
+
+impl<K, V> Default for EFRBTree<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Drop for EFRBTree<K, V> {
+    fn drop(&mut self) {
+        unsafe {
+            let root = Box::from_raw(self.root.load(Ordering::Relaxed));
+            let mut stack = vec![
+                root.left.load(Ordering::Relaxed),
+                root.right.load(Ordering::Relaxed),
+            ];
+
+            while let Some(node) = stack.pop() {
+                if node.is_null() {
+                    continue;
+                }
+
+                let node_ref = &*node;
+
+                stack.push(node_ref.left.load(Ordering::Relaxed));
+                stack.push(node_ref.right.load(Ordering::Relaxed));
+                let update = untagged(node_ref.update.load(Ordering::Relaxed));
+                if !update.is_null() {
+                    drop(Box::from_raw(update));
+                }
+                drop(Box::from_raw(node));
+            }
+            let update = untagged(root.update.load(Ordering::Relaxed));
+            if !update.is_null() {
+                drop(Box::from_raw(update));
+            }
+        }
+    }
+}
+
+impl<K, V> EFRBTree<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            root: AtomicPtr::new(Box::into_raw(Box::new(Node::internal(
+                Key::Inf2,
+                None,
+                Node::leaf(Key::Inf1, None),
+                Node::leaf(Key::Inf2, None),
+            )))),
+        }
+    }
+
+    /// Checks that the tree is a leaf-oriented BST whose internal nodes are all clean, and returns
+    /// the keys of its leaves in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys = Vec::new();
+        // Nodes with the bounds of the keys of their leaves, in the order of a DFS.
+        let mut stack = vec![(self.root.load(Ordering::Acquire), None, None)];
+        while let Some((node, lo, hi)) = stack.pop() {
+            let node_ref = unsafe { &*node };
+            let left = node_ref.left.load(Ordering::Acquire);
+            let right = node_ref.right.load(Ordering::Acquire);
+            match (left.is_null(), right.is_null()) {
+                (true, true) => {
+                    if let Key::Fin(key) = &node_ref.key {
+                        if lo.map_or(false, |lo: &Key<K>| node_ref.key < *lo)
+                            || hi.map_or(false, |hi: &Key<K>| node_ref.key >= *hi)
+                        {
+                            return Err("a leaf is in the wrong subtree".to_string());
+                        }
+                        keys.push(key);
+                    }
+                }
+                (false, false) => {
+                    let update = node_ref.update.load(Ordering::Acquire);
+                    if UpdateTag::from_bits_truncate(tag(update)) != UpdateTag::CLEAN {
+                        return Err("an internal node is not clean".to_string());
+                    }
+                    stack.push((right, Some(&node_ref.key), hi));
+                    stack.push((left, lo, Some(&node_ref.key)));
+                }
+                _ => return Err("an internal node has a single child".to_string()),
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Used by Insert, Delete and Find to traverse a branch of the BST.
+    ///
+    /// On return, `p`, `l`, `pupdate` and `gpupdate` of the cursor are protected. `gp` is also
+    /// protected if `help` is null, and otherwise the nodes of `help` are protected.
+    fn search(&self, key: &K, handle: &mut Handle, guard: &Guard) -> Cursor<K, V> {
+        let mut cursor;
+
+        read_phase!(guard => {
+            cursor = {
+                // Declaring inner cursor is important to let the compiler to conduct register
+                // optimization.
+                let mut cursor = Cursor {
+                    gp: ptr::null_mut(),
+                    p: ptr::null_mut(),
+                    l: self.root.load(Ordering::Relaxed),
+                    l_other: ptr::null_mut(),
+                    gp_p_dir: Direction::L,
+                    p_l_dir: Direction::L,
+                    pupdate: ptr::null_mut(),
+                    gpupdate: ptr::null_mut(),
+                    help: ptr::null_mut(),
+                };
+
+                loop {
+                    let l_node = unsafe { &*cursor.l };
+                    if l_node.is_leaf {
+                        break;
+                    }
+                    cursor.gp = cursor.p;
+                    cursor.p = cursor.l;
+                    cursor.gp_p_dir = cursor.p_l_dir;
+                    cursor.gpupdate = cursor.pupdate;
+                    // The children must be read after the update, so that they are consistent
+                    // with `pupdate`.
+                    cursor.pupdate = l_node.update.load(Ordering::Acquire);
+                    let left = l_node.left.load(Ordering::Acquire);
+                    let right = l_node.right.load(Ordering::Acquire);
+                    if l_node.key.cmp(key) == cmp::Ordering::Greater {
+                        (cursor.l, cursor.l_other, cursor.p_l_dir) = (left, right, Direction::L);
+                    } else {
+                        (cursor.l, cursor.l_other, cursor.p_l_dir) = (right, left, Direction::R);
+                    }
+                }
+
+                if tag(cursor.gpupdate) != UpdateTag::CLEAN.bits() {
+                    cursor.help = cursor.gpupdate;
+                } else if tag(cursor.pupdate) != UpdateTag::CLEAN.bits() {
+                    cursor.help = cursor.pupdate;
+                }
+                cursor
+            };
+
+            handle.p.protect(cursor.p);
+            handle.l.protect(cursor.l);
+            handle.pupdate.protect(untagged(cursor.pupdate));
+            handle.gpupdate.protect(untagged(cursor.gpupdate));
+
+            // The fields of a flagged update are not retired yet, as they are retired only after
+            // the update is unflagged.
+            if cursor.help.is_null() {
+                handle.gp.protect(cursor.gp);
+            } else {
+                let op = unsafe { &*untagged(cursor.help) };
+                handle.gp.protect(op.gp);
+                handle.help_p.protect(op.p);
+                handle.help_l.protect(op.l);
+            }
+        });
+
+        cursor
+    }
+
+    pub fn find<'g>(&'g self, key: &K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
+        let cursor = self.search(key, handle, guard);
+        let l_node = unsafe { &*cursor.l };
+        if l_node.key.eq(key) {
+            l_node.value.as_ref()
+        } else {
+            None
+        }
+    }
+
+    pub fn insert(&self, key: &K, value: V, handle: &mut Handle, guard: &Guard) -> bool {
+        loop {
+            let cursor = self.search(key, handle, guard);
+            let p_node = unsafe { &*cursor.p };
+            let l_node = unsafe { &*cursor.l };
+
+            if l_node.key == *key {
+                return false;
+            } else if tag(cursor.pupdate) != UpdateTag::CLEAN.bits() {
+                self.help(cursor.help, guard);
+            } else {
+                let new = Node::leaf(Key::Fin(key.clone()), Some(value.clone()));
+                let new_sibling = Node::leaf(l_node.key.clone(), l_node.value.clone());
+
+                let (left, right) = match new.key.partial_cmp(&new_sibling.key) {
+                    Some(cmp::Ordering::Less) => (new, new_sibling),
+                    _ => (new_sibling, new),
+                };
+
+                let new_internal = Box::into_raw(Box::new(Node::internal(
+                    // key field max(k, l → key)
+                    right.key.clone(),
+                    None,
+                    // two child fields equal to new and newSibling
+                    // (the one with the smaller key is the left child)
+                    left,
+                    right,
+                )));
+
+                let op = Update {
+                    gp: ptr::null_mut(),
+                    p: cursor.p,
+                    l: cursor.l,
+                    l_other: cursor.l_other,
+                    gp_p_dir: cursor.gp_p_dir,
+                    p_l_dir: cursor.p_l_dir,
+                    pupdate: ptr::null_mut(),
+                    new_internal,
+                };
+                let new_pupdate = tagged(Box::into_raw(Box::new(op)), UpdateTag::IFLAG.bits());
+                handle.aux_update.protect(untagged(new_pupdate));
+
+                // iflag CAS
+                match p_node.update.compare_exchange(
+                    cursor.pupdate,
+                    new_pupdate,
+                    Ordering::Release,
+                    Ordering::Acquire,
+                ) {
+                    Ok(_) => {
+                        if !cursor.pupdate.is_null() {
+                            unsafe { guard.retire(untagged(cursor.pupdate)) };
+                        }
+                        self.help_insert(new_pupdate, guard);
+                        return true;
+                    }
+                    Err(_) => unsafe {
+                        drop(Box::from_raw(untagged(new_pupdate)));
+                        let new_internal = Box::from_raw(new_internal);
+                        drop(Box::from_raw(new_internal.left.load(Ordering::Relaxed)));
+                        drop(Box::from_raw(new_internal.right.load(Ordering::Relaxed)));
+                    },
+                }
+            }
+        }
+    }
+
+    pub fn delete<'g>(&'g self, key: &K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
+        loop {
+            let cursor = self.search(key, handle, guard);
+
+            if cursor.gp.is_null() {
+                // The tree is empty. There's no more things to do.
+                return None;
+            }
+            let l_node = unsafe { &*cursor.l };
+
+            if l_node.key != *key {
+                return None;
+            }
+            if !cursor.help.is_null() {
+                self.help(cursor.help, guard);
+                continue;
+            }
+            let gp_node = unsafe { &*cursor.gp };
+
+            let op = Update {
+                gp: cursor.gp,
+                p: cursor.p,
+                l: cursor.l,
+                l_other: cursor.l_other,
+                gp_p_dir: cursor.gp_p_dir,
+                p_l_dir: cursor.p_l_dir,
+                pupdate: cursor.pupdate,
+                new_internal: ptr::null_mut(),
+            };
+            let new_update = tagged(Box::into_raw(Box::new(op)), UpdateTag::DFLAG.bits());
+            handle.aux_update.protect(untagged(new_update));
+
+            // dflag CAS
+            match gp_node.update.compare_exchange(
+                cursor.gpupdate,
+                new_update,
+                Ordering::Release,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    if !cursor.gpupdate.is_null() {
+                        unsafe { guard.retire(untagged(cursor.gpupdate)) };
+                    }
+                    if self.help_delete(new_update, guard) {
+                        // The leaf is protected by `handle.l` until the next operation.
+                        return l_node.value.as_ref();
+                    }
+                }
+                Err(_) => unsafe { drop(Box::from_raw(untagged(new_update))) },
+            }
+        }
+    }
+
+    /// Precondition: the fields of `op` must be protected.
+    #[inline]
+    fn help(&self, op: *mut Update<K, V>, guard: &Guard) {
+        match UpdateTag::from_bits_truncate(tag(op)) {
+            UpdateTag::IFLAG => self.help_insert(op, guard),
+            UpdateTag::DFLAG => {
+                let _ = self.help_delete(op, guard);
+            }
+            UpdateTag::MARKED => self.help_marked(op, guard),
+            _ => {}
+        }
+    }
+
+    fn help_delete(&self, op: *mut Update<K, V>, guard: &Guard) -> bool {
+        // Precondition: op points to a DInfo record (i.e., it is not ⊥)
+        let op_ref = unsafe { &*untagged(op) };
+        let gp_ref = unsafe { &*op_ref.gp };
+        let p_ref = unsafe { &*op_ref.p };
+        let new_op = tagged(untagged(op), UpdateTag::MARKED.bits());
+
+        // mark CAS
+        match p_ref.update.compare_exchange(
+            op_ref.pupdate,
+            new_op,
+            Ordering::Release,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => {
+                // (prev value) = op → pupdate
+                if !op_ref.pupdate.is_null() {
+                    unsafe { guard.retire(untagged(op_ref.pupdate)) };
+                }
+                self.help_marked(new_op, guard);
+                true
+            }
+            Err(current) if current == new_op => {
+                // (prev value) = <Mark, op>
+                self.help_marked(new_op, guard);
+                true
+            }
+            Err(_) => {
+                // backtrack CAS
+                let _ = gp_ref.update.compare_exchange(
+                    tagged(untagged(op), UpdateTag::DFLAG.bits()),
+                    tagged(untagged(op), UpdateTag::CLEAN.bits()),
+                    Ordering::Release,
+                    Ordering::Relaxed,
+                );
+                false
+            }
+        }
+    }
+
+    fn help_marked(&self, op: *mut Update<K, V>, guard: &Guard) {
+        // Precondition: op points to a DInfo record (i.e., it is not ⊥)
+        let op_ref = unsafe { &*untagged(op) };
+        let gp_node = unsafe { &*op_ref.gp };
+
+        // dchild CAS
+        let spliced = gp_node
+            .child(op_ref.gp_p_dir)
+            .compare_exchange(
+                op_ref.p,
+                op_ref.l_other,
+                Ordering::Release,
+                Ordering::Relaxed,
+            )
+            .is_ok();
+
+        // dunflag CAS
+        let _ = gp_node.update.compare_exchange(
+            tagged(untagged(op), UpdateTag::DFLAG.bits()),
+            tagged(untagged(op), UpdateTag::CLEAN.bits()),
+            Ordering::Release,
+            Ordering::Relaxed,
+        );
+
+        // Helpers may still read `p` and `l` until the dunflag CAS.
+        if spliced {
+            unsafe {
+                guard.retire(op_ref.l);
+                guard.retire(op_ref.p);
+            }
+        }
+    }
+
+    fn help_insert(&self, op: *mut Update<K, V>, guard: &Guard) {
+        // Precondition: op points to an IInfo record (i.e., it is not ⊥)
+        let op_ref = unsafe { &*untagged(op) };
+        let p_node = unsafe { &*op_ref.p };
+
+        // ichild CAS
+        let replaced = p_node
+            .child(op_ref.p_l_dir)
+            .compare_exchange(
+                op_ref.l,
+                op_ref.new_internal,
+                Ordering::Release,
+                Ordering::Relaxed,
+            )
+            .is_ok();
+
+        // iunflag CAS
+        let _ = p_node.update.compare_exchange(
+            tagged(untagged(op), UpdateTag::IFLAG.bits()),
+            tagged(untagged(op), UpdateTag::CLEAN.bits()),
+            Ordering::Release,
+            Ordering::Relaxed,
+        );
+
+        // Helpers may still read `l` until the iunflag CAS.
+        if replaced {
+            unsafe { guard.retire(op_ref.l) };
+        }
+    }
+}
+
+impl<K, V> ConcurrentMap<K, V> for EFRBTree<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    type Handle = Handle;
+
+    fn handle(guard: &mut Guard) -> Self::Handle {
+        Self::Handle {
+            gp: guard.acquire_shield().unwrap(),
+            p: guard.acquire_shield().unwrap(),
+            l: guard.acquire_shield().unwrap(),
+            pupdate: guard.acquire_shield().unwrap(),
+            gpupdate: guard.acquire_shield().unwrap(),
+            aux_update: guard.acquire_shield().unwrap(),
+            help_p: guard.acquire_shield().unwrap(),
+            help_l: guard.acquire_shield().unwrap(),
+        }
+    }
+
+    fn new() -> Self {
+        Self::new()
+    }
+
+    #[inline(always)]
+    fn get<'g>(&'g self, key: &'g K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
+        self.find(key, handle, guard)
+    }
+    #[inline(always)]
+    fn insert(&self, key: K, value: V, handle: &mut Handle, guard: &Guard) -> bool {
+        self.insert(&key, value, handle, guard)
+    }
+    #[inline(always)]
+    fn remove<'g>(&'g self, key: &K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
+        self.delete(key, handle, guard)
+    }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EFRBTree;
+    use crate::ds_impl::nbr::concurrent_map;
+
+    #[test]
+    fn smoke_efrb_tree() {
+        concurrent_map::tests::smoke::<EFRBTree<i32, String>>();
+    }
+}
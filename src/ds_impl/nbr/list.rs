@@ -147,6 +147,72 @@ where
         }
     }
 
+    /// Unlink a logically removed node whenever it is met, as in Michael's list.
+    ///
+    /// As writes are not permitted in a read phase, the read phase stops at the first removed
+    /// node, which is unlinked in the write phase before searching again from the head.
+    #[inline]
+    fn find_harris_michael(&self, key: &K, handle: &mut Handle, guard: &Guard) -> Cursor<K, V> {
+        let mut cursor;
+        let mut next;
+
+        loop {
+            read_phase!(guard => {
+                (cursor, next) = {
+                    // Declaring inner cursor is important to let the compiler to conduct register
+                    // optimization.
+                    let mut cursor = Cursor {
+                        prev: &self.head as *const _ as *mut Node<K, V>,
+                        curr: self.head.load(Ordering::Acquire),
+                        found: false,
+                    };
+                    let mut next = ptr::null_mut();
+
+                    cursor.found = loop {
+                        let curr_node = some_or!(unsafe { cursor.curr.as_ref() }, break false);
+                        next = curr_node.next.load(Ordering::Acquire);
+
+                        // Stop at the removed node to unlink it.
+                        if tag(next) != 0 {
+                            break false;
+                        }
+
+                        match curr_node.key.cmp(key) {
+                            Less => {
+                                cursor.prev = cursor.curr;
+                                cursor.curr = next;
+                            }
+                            Equal => break true,
+                            Greater => break false,
+                        }
+                    };
+                    (cursor, next)
+                };
+                handle.prev.protect(cursor.prev);
+                handle.curr.protect(cursor.curr);
+            });
+
+            if tag(next) == 0 {
+                return cursor;
+            }
+
+            // `cursor.curr` is logically removed. Unlink it and search again.
+            let prev_ref = unsafe { &*cursor.prev };
+            if prev_ref
+                .next
+                .compare_exchange(
+                    cursor.curr,
+                    untagged(next),
+                    Ordering::Release,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+            {
+                unsafe { guard.retire(cursor.curr) };
+            }
+        }
+    }
+
     /// Gotta go fast. Doesn't fail.
     #[inline]
     fn find_harris_herlihy_shavit(
@@ -342,6 +408,63 @@ where
         self.remove(key, Self::find_harris, handle, guard)
     }
 
+    /// Omitted
+    #[inline]
+    pub fn harris_michael_get<'g>(
+        &'g self,
+        key: &K,
+        handle: &mut Handle,
+        guard: &'g Guard,
+    ) -> Option<&'g V> {
+        self.get(key, Self::find_harris_michael, handle, guard)
+    }
+
+    /// Omitted
+    #[inline]
+    pub fn harris_michael_insert<'g>(
+        &'g self,
+        key: K,
+        value: V,
+        handle: &mut Handle,
+        guard: &'g Guard,
+    ) -> bool {
+        self.insert(key, value, Self::find_harris_michael, handle, guard)
+    }
+
+    /// Omitted
+    #[inline]
+    pub fn harris_michael_remove<'g>(
+        &'g self,
+        key: &K,
+        handle: &mut Handle,
+        guard: &'g Guard,
+    ) -> Option<&'g V> {
+        self.remove(key, Self::find_harris_michael, handle, guard)
+    }
+
+    /// Checks that the nodes are sorted and the unmarked ones have distinct keys, and returns the
+    /// keys of the unmarked nodes. Marked nodes that are not unlinked yet may remain.
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys: Vec<&K> = Vec::new();
+        let mut last: Option<&K> = None;
+        let mut curr = self.head.load(Ordering::Acquire);
+        while let Some(curr_node) = unsafe { untagged(curr).as_ref() } {
+            if last.map_or(false, |last| *last > curr_node.key) {
+                return Err(format!("the node #{} is out of order", keys.len()));
+            }
+            let next = curr_node.next.load(Ordering::Acquire);
+            if tag(next) == 0 {
+                if keys.last().map_or(false, |last| **last == curr_node.key) {
+                    return Err(format!("the node #{} has a duplicate key", keys.len()));
+                }
+                keys.push(&curr_node.key);
+            }
+            last = Some(&curr_node.key);
+            curr = next;
+        }
+        Ok(keys)
+    }
+
     /// Omitted
     #[inline]
     pub fn harris_herlihy_shavit_get<'g>(
@@ -392,6 +515,9 @@ where
     fn remove<'g>(&'g self, key: &K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
         self.inner.harris_remove(key, handle, guard)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HMList<K, V> {
@@ -414,6 +540,40 @@ where
     }
 }
 
+impl<K, V> ConcurrentMap<K, V> for HMList<K, V>
+where
+    K: Ord,
+{
+    type Handle = Handle;
+
+    fn handle(guard: &mut Guard) -> Self::Handle {
+        Self::Handle {
+            prev: guard.acquire_shield().unwrap(),
+            curr: guard.acquire_shield().unwrap(),
+        }
+    }
+
+    fn new() -> Self {
+        HMList { inner: List::new() }
+    }
+
+    #[inline(always)]
+    fn get<'g>(&'g self, key: &K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
+        self.inner.harris_michael_get(key, handle, guard)
+    }
+    #[inline(always)]
+    fn insert(&self, key: K, value: V, handle: &mut Handle, guard: &Guard) -> bool {
+        self.inner.harris_michael_insert(key, value, handle, guard)
+    }
+    #[inline(always)]
+    fn remove<'g>(&'g self, key: &K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
+        self.inner.harris_michael_remove(key, handle, guard)
+    }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
+}
+
 pub struct HHSList<K, V> {
     inner: List<K, V>,
 }
@@ -456,11 +616,14 @@ where
     fn remove<'g>(&'g self, key: &K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
         self.inner.harris_remove(key, handle, guard)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{HHSList, HList};
+    use super::{HHSList, HList, HMList};
     use crate::ds_impl::nbr::concurrent_map;
 
     #[test]
@@ -468,6 +631,11 @@ mod tests {
         concurrent_map::tests::smoke::<HList<i32, String>>();
     }
 
+    #[test]
+    fn smoke_hm_list() {
+        concurrent_map::tests::smoke::<HMList<i32, String>>();
+    }
+
     #[test]
     fn smoke_hhs_list() {
         concurrent_map::tests::smoke::<HHSList<i32, String>>();
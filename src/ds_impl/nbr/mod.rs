@@ -1,13 +1,19 @@
 pub mod concurrent_map;
 
+pub mod bonsai_tree;
+pub mod ellen_tree;
 pub mod list;
 pub mod michael_hash_map;
 pub mod natarajan_mittal_tree;
+pub mod skip_list;
 
 pub use self::concurrent_map::ConcurrentMap;
 
+pub use self::bonsai_tree::BonsaiTreeMap;
+pub use self::ellen_tree::EFRBTree;
 pub use self::list::HHSList;
 pub use self::list::HList;
 pub use self::list::HMList;
 pub use self::michael_hash_map::HashMap;
 pub use self::natarajan_mittal_tree::NMTreeMap;
+pub use self::skip_list::SkipList;
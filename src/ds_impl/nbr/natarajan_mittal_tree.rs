@@ -238,6 +238,47 @@ where
         NMTreeMap { r }
     }
 
+    /// Checks that the tree is a leaf-oriented BST without marked edges, and returns the keys of
+    /// its leaves in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys = Vec::new();
+        // Nodes with the bounds of the keys of their leaves, in the order of a DFS.
+        let mut stack = vec![
+            (
+                self.r.right.load(Ordering::Acquire),
+                Some(&self.r.key),
+                None,
+            ),
+            (self.r.left.load(Ordering::Acquire), None, Some(&self.r.key)),
+        ];
+        while let Some((node, lo, hi)) = stack.pop() {
+            if tag(node) != 0 {
+                return Err("a marked edge remains".to_string());
+            }
+            let node_ref = unsafe { &*node };
+            let left = node_ref.left.load(Ordering::Acquire);
+            let right = node_ref.right.load(Ordering::Acquire);
+            match (left.is_null(), right.is_null()) {
+                (true, true) => {
+                    if let Key::Fin(key) = &node_ref.key {
+                        if lo.map_or(false, |lo: &Key<K>| node_ref.key < *lo)
+                            || hi.map_or(false, |hi: &Key<K>| node_ref.key >= *hi)
+                        {
+                            return Err("a leaf is in the wrong subtree".to_string());
+                        }
+                        keys.push(key);
+                    }
+                }
+                (false, false) => {
+                    stack.push((right, Some(&node_ref.key), hi));
+                    stack.push((left, lo, Some(&node_ref.key)));
+                }
+                _ => return Err("an internal node has a single child".to_string()),
+            }
+        }
+        Ok(keys)
+    }
+
     // All `*mut Node<K, V>` fields are unmarked.
     fn seek<'g>(&'g self, key: &K, handle: &mut Handle, guard: &'g Guard) -> SeekRecord<K, V> {
         let mut record;
@@ -552,6 +593,9 @@ where
     fn remove<'g>(&'g self, key: &K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
         self.remove(key, handle, guard)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,470 @@
+use std::collections::HashSet;
+use std::ptr;
+use std::sync::atomic::{fence, AtomicPtr, AtomicUsize, Ordering};
+
+use hp_pp::{tag, tagged, untagged};
+use nbr::{read_phase, Guard, Shield};
+
+use super::concurrent_map::ConcurrentMap;
+
+const MAX_HEIGHT: usize = 32;
+
+type Tower<K, V> = [AtomicPtr<Node<K, V>>; MAX_HEIGHT];
+
+// `#[repr(C)]` is used to ensure the first field
+// is also the first data in the memory alignment.
+#[repr(C)]
+struct Node<K, V> {
+    next: Tower<K, V>,
+    key: K,
+    value: V,
+    height: usize,
+    refs: AtomicUsize,
+}
+
+impl<K, V> Node<K, V> {
+    pub fn new(key: K, value: V) -> Self {
+        let height = Self::generate_height();
+        let next: [AtomicPtr<Node<K, V>>; MAX_HEIGHT] = Default::default();
+        for link in next.iter().take(height) {
+            link.store(tagged(ptr::null_mut(), 2), Ordering::Relaxed);
+        }
+        Self {
+            next,
+            key,
+            value,
+            height,
+            refs: AtomicUsize::new(height + 1),
+        }
+    }
+
+    fn generate_height() -> usize {
+        // returns 1 with probability 3/4
+        if crate::utils::random::<usize>() % 4 < 3 {
+            return 1;
+        }
+        // returns h with probability 2^(−(h+1))
+        let mut height = 2;
+        while height < MAX_HEIGHT && crate::utils::random::<bool>() {
+            height += 1;
+        }
+        height
+    }
+
+    pub fn decrement(&self, guard: &Guard) {
+        if self.refs.fetch_sub(1, Ordering::Release) == 1 {
+            fence(Ordering::Acquire);
+            unsafe { guard.retire(self as *const _ as *mut Node<K, V>) };
+        }
+    }
+
+    pub fn mark_tower(&self) -> bool {
+        for level in (0..self.height).rev() {
+            let tag = tag(self.next[level].fetch_or(1, Ordering::SeqCst));
+            // If the level 0 pointer was already marked, somebody else removed the node.
+            if level == 0 && (tag & 1) != 0 {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub struct Handle {
+    preds: [Shield; MAX_HEIGHT],
+    succs: [Shield; MAX_HEIGHT],
+    found: Shield,
+}
+
+struct Cursor<K, V> {
+    preds: [*mut Node<K, V>; MAX_HEIGHT],
+    succs: [*mut Node<K, V>; MAX_HEIGHT],
+    found: bool,
+}
+
+impl<K, V> Cursor<K, V> {
+    fn new(head: &Tower<K, V>) -> Self {
+        Self {
+            preds: [head as *const _ as *mut _; MAX_HEIGHT],
+            succs: [ptr::null_mut(); MAX_HEIGHT],
+            found: false,
+        }
+    }
+}
+
+pub struct SkipList<K, V> {
+    head: Tower<K, V>,
+}
+
+impl<K, V> Drop for SkipList<K, V> {
+    fn drop(&mut self) {
+        let mut node = self.head[0].load(Ordering::Relaxed);
+
+        while let Some(node_ref) = unsafe { untagged(node).as_ref() } {
+            let next = node_ref.next[0].load(Ordering::Relaxed);
+            drop(unsafe { Box::from_raw(untagged(node)) });
+            node = next;
+        }
+    }
+}
+
+impl<K, V> Default for SkipList<K, V>
+where
+    K: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> SkipList<K, V>
+where
+    K: Ord,
+{
+    pub fn new() -> Self {
+        Self {
+            head: Default::default(),
+        }
+    }
+
+    /// Checks that each level is sorted and only links the unmarked nodes of the lower levels
+    /// that are tall enough, and returns the keys of the unmarked nodes. Marked nodes that are not
+    /// unlinked yet may remain.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys: Vec<&K> = Vec::new();
+        // The unmarked nodes of the level below.
+        let mut lower = HashSet::new();
+        for level in 0..MAX_HEIGHT {
+            let mut nodes = HashSet::new();
+            let mut last: Option<&K> = None;
+            let mut curr = self.head[level].load(Ordering::Acquire);
+            while let Some(node) = unsafe { untagged(curr).as_ref() } {
+                if last.map_or(false, |last| *last > node.key) {
+                    return Err(format!("level {level} is out of order"));
+                }
+                last = Some(&node.key);
+                curr = node.next[level].load(Ordering::Acquire);
+                if tag(node.next[0].load(Ordering::Acquire)) & 1 != 0 {
+                    continue;
+                }
+                if level >= node.height || (level > 0 && !lower.contains(&(node as *const _))) {
+                    return Err(format!("level {level} links a node not in the lower level"));
+                }
+                if level == 0 {
+                    if keys.last().map_or(false, |last| **last == node.key) {
+                        return Err("duplicate keys".to_string());
+                    }
+                    keys.push(&node.key);
+                }
+                nodes.insert(node as *const Node<K, V>);
+            }
+            lower = nodes;
+        }
+        Ok(keys)
+    }
+
+    /// Searches the bottom level without helping, skipping the logically removed nodes.
+    fn find_optimistic(&self, key: &K, handle: &mut Handle, guard: &Guard) -> *mut Node<K, V> {
+        let mut found;
+
+        read_phase!(guard => {
+            found = {
+                let mut level = MAX_HEIGHT;
+                while level >= 1 && self.head[level - 1].load(Ordering::Relaxed).is_null() {
+                    level -= 1;
+                }
+
+                let mut pred = &self.head;
+                let mut curr = ptr::null_mut();
+                while level >= 1 {
+                    level -= 1;
+                    curr = untagged(pred[level].load(Ordering::Acquire));
+
+                    loop {
+                        let curr_node = some_or!(unsafe { curr.as_ref() }, break);
+                        let succ = curr_node.next[level].load(Ordering::Acquire);
+
+                        if tag(succ) != 0 {
+                            curr = untagged(succ);
+                            continue;
+                        }
+
+                        if curr_node.key < *key {
+                            pred = &curr_node.next;
+                            curr = succ;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+
+                match unsafe { curr.as_ref() } {
+                    Some(curr_node) if curr_node.key == *key => curr,
+                    _ => ptr::null_mut(),
+                }
+            };
+            handle.found.protect(found);
+        });
+
+        found
+    }
+
+    /// Finds the predecessors and the successors of `key` in every level.
+    ///
+    /// As writes are not permitted in a read phase, the read phase stops at the first logically
+    /// removed node, which is unlinked from that level in the write phase before searching again
+    /// from the head.
+    fn find(&self, key: &K, handle: &mut Handle, guard: &Guard) -> Cursor<K, V> {
+        let mut cursor;
+        // The level and the successor of a removed `cursor.succs[level]` to unlink.
+        let mut unlink;
+
+        loop {
+            read_phase!(guard => {
+                (cursor, unlink) = 'search: loop {
+                    // Declaring inner cursor is important to let the compiler to conduct register
+                    // optimization.
+                    let mut cursor = Cursor::new(&self.head);
+
+                    let mut level = MAX_HEIGHT;
+                    while level >= 1 && self.head[level - 1].load(Ordering::Relaxed).is_null() {
+                        level -= 1;
+                    }
+
+                    let mut pred = &self.head as *const _ as *mut Node<K, V>;
+                    while level >= 1 {
+                        level -= 1;
+                        let mut curr = unsafe { &*pred }.next[level].load(Ordering::Acquire);
+                        // If `curr` is marked, that means `pred` is removed and we have to
+                        // restart the search.
+                        if tag(curr) != 0 {
+                            continue 'search;
+                        }
+
+                        while let Some(curr_node) = unsafe { curr.as_ref() } {
+                            let succ = curr_node.next[level].load(Ordering::Acquire);
+
+                            if tag(succ) != 0 {
+                                cursor.preds[level] = pred;
+                                cursor.succs[level] = curr;
+                                break 'search (cursor, Some((level, untagged(succ))));
+                            }
+
+                            // If `curr` contains a key that is greater than or equal to `key`,
+                            // we're done with this level.
+                            match curr_node.key.cmp(key) {
+                                std::cmp::Ordering::Greater => break,
+                                std::cmp::Ordering::Equal => {
+                                    cursor.found = level == 0;
+                                    break;
+                                }
+                                std::cmp::Ordering::Less => {}
+                            }
+
+                            // Move one step forward.
+                            pred = curr;
+                            curr = succ;
+                        }
+
+                        cursor.preds[level] = pred;
+                        cursor.succs[level] = curr;
+                    }
+                    break (cursor, None);
+                };
+                for (shield, pred) in handle.preds.iter().zip(cursor.preds) {
+                    shield.protect(pred);
+                }
+                for (shield, succ) in handle.succs.iter().zip(cursor.succs) {
+                    shield.protect(succ);
+                }
+            });
+
+            let (level, succ) = some_or!(unlink, return cursor);
+            let pred = unsafe { &*cursor.preds[level] };
+            let curr = cursor.succs[level];
+            if pred.next[level]
+                .compare_exchange(curr, succ, Ordering::Release, Ordering::Relaxed)
+                .is_ok()
+            {
+                unsafe { &*curr }.decrement(guard);
+            }
+        }
+    }
+
+    pub fn insert(&self, key: K, value: V, handle: &mut Handle, guard: &Guard) -> bool {
+        let mut cursor = self.find(&key, handle, guard);
+        if cursor.found {
+            return false;
+        }
+
+        // The reference count is initially two to account for
+        // 1. The link at the level 0 of the tower.
+        // 2. The current reference in this function.
+        let new_node = Box::into_raw(Box::new(Node::new(key, value)));
+        let new_node_ref = unsafe { &*new_node };
+        let height = new_node_ref.height;
+
+        loop {
+            new_node_ref.next[0].store(cursor.succs[0], Ordering::Relaxed);
+
+            if unsafe { &*cursor.preds[0] }.next[0]
+                .compare_exchange(
+                    cursor.succs[0],
+                    new_node,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                )
+                .is_ok()
+            {
+                break;
+            }
+
+            // We failed. Let's search for the key and try again.
+            cursor = self.find(&new_node_ref.key, handle, guard);
+            if cursor.found {
+                drop(unsafe { Box::from_raw(new_node) });
+                return false;
+            }
+        }
+
+        // The new node was successfully installed.
+        // Build the rest of the tower above level 0.
+        'build: for level in 1..height {
+            loop {
+                let pred = cursor.preds[level];
+                let succ = cursor.succs[level];
+                let next = new_node_ref.next[level].load(Ordering::SeqCst);
+
+                // If the current pointer is marked, that means another thread is already
+                // removing the node we've just inserted. In that case, let's just stop
+                // building the tower.
+                if (tag(next) & 1) != 0 {
+                    new_node_ref
+                        .refs
+                        .fetch_sub(height - level, Ordering::SeqCst);
+                    break 'build;
+                }
+
+                if new_node_ref.next[level]
+                    .compare_exchange(
+                        tagged(ptr::null_mut(), 2),
+                        succ,
+                        Ordering::SeqCst,
+                        Ordering::SeqCst,
+                    )
+                    .is_err()
+                {
+                    new_node_ref
+                        .refs
+                        .fetch_sub(height - level, Ordering::SeqCst);
+                    break 'build;
+                }
+
+                // Try installing the new node at the current level.
+                if unsafe { &*pred }.next[level]
+                    .compare_exchange(succ, new_node, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_ok()
+                {
+                    // Success! Continue on the next level.
+                    break;
+                }
+
+                // Installation failed.
+                cursor = self.find(&new_node_ref.key, handle, guard);
+            }
+        }
+
+        new_node_ref.decrement(guard);
+        true
+    }
+
+    pub fn remove<'g>(&'g self, key: &K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
+        loop {
+            let cursor = self.find(key, handle, guard);
+            if !cursor.found {
+                return None;
+            }
+            let node_ptr = cursor.succs[0];
+            // Keep the node protected while it is unlinked, as `find` overwrites the successors.
+            handle.found.protect(node_ptr);
+            let node = unsafe { &*node_ptr };
+
+            // Try removing the node by marking its tower.
+            if node.mark_tower() {
+                for level in (0..node.height).rev() {
+                    let succ = node.next[level].load(Ordering::SeqCst);
+                    if (tag(succ) & 2) != 0 {
+                        continue;
+                    }
+
+                    // Try linking the predecessor and successor at this level.
+                    if unsafe { &*cursor.preds[level] }.next[level]
+                        .compare_exchange(
+                            node_ptr,
+                            untagged(succ),
+                            Ordering::SeqCst,
+                            Ordering::SeqCst,
+                        )
+                        .is_ok()
+                    {
+                        node.decrement(guard);
+                    } else {
+                        self.find(key, handle, guard);
+                        break;
+                    }
+                }
+                return Some(&node.value);
+            }
+        }
+    }
+}
+
+impl<K, V> ConcurrentMap<K, V> for SkipList<K, V>
+where
+    K: Ord,
+{
+    type Handle = Handle;
+
+    fn new() -> Self {
+        SkipList::new()
+    }
+
+    fn handle(guard: &mut Guard) -> Self::Handle {
+        Self::Handle {
+            preds: [(); MAX_HEIGHT].map(|_| guard.acquire_shield().unwrap()),
+            succs: [(); MAX_HEIGHT].map(|_| guard.acquire_shield().unwrap()),
+            found: guard.acquire_shield().unwrap(),
+        }
+    }
+
+    #[inline(always)]
+    fn get<'g>(&'g self, key: &'g K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
+        let found = self.find_optimistic(key, handle, guard);
+        unsafe { found.as_ref() }.map(|node| &node.value)
+    }
+
+    #[inline(always)]
+    fn insert(&self, key: K, value: V, handle: &mut Handle, guard: &Guard) -> bool {
+        self.insert(key, value, handle, guard)
+    }
+
+    #[inline(always)]
+    fn remove<'g>(&'g self, key: &'g K, handle: &mut Handle, guard: &'g Guard) -> Option<&'g V> {
+        self.remove(key, handle, guard)
+    }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SkipList;
+    use crate::ds_impl::nbr::concurrent_map;
+
+    #[test]
+    fn smoke_skip_list() {
+        concurrent_map::tests::smoke::<SkipList<i32, String>>();
+    }
+}
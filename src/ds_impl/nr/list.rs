@@ -266,6 +266,29 @@ where
         }
     }
 
+    /// Checks that the nodes are sorted and the unmarked ones have distinct keys, and returns the
+    /// keys of the unmarked nodes. Marked nodes that are not unlinked yet may remain.
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys: Vec<&K> = Vec::new();
+        let mut last: Option<&K> = None;
+        let mut curr = self.head.load(Ordering::Acquire);
+        while let Some(curr_node) = unsafe { curr.with_tag(0).as_ref() } {
+            if last.map_or(false, |last| *last > curr_node.key) {
+                return Err(format!("the node #{} is out of order", keys.len()));
+            }
+            let next = curr_node.next.load(Ordering::Acquire);
+            if next.tag() == 0 {
+                if keys.last().map_or(false, |last| **last == curr_node.key) {
+                    return Err(format!("the node #{} has a duplicate key", keys.len()));
+                }
+                keys.push(&curr_node.key);
+            }
+            last = Some(&curr_node.key);
+            curr = next;
+        }
+        Ok(keys)
+    }
+
     #[inline]
     pub fn harris_get(&self, key: &K) -> Option<&'static V> {
         self.get(key, Self::find_harris)
@@ -327,6 +350,9 @@ where
     fn remove(&self, key: &K) -> Option<&'static V> {
         self.inner.harris_remove(key)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HMList<K, V> {
@@ -354,6 +380,9 @@ where
     fn remove(&self, key: &K) -> Option<&'static V> {
         self.inner.harris_michael_remove(key)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 pub struct HHSList<K, V> {
@@ -393,6 +422,9 @@ where
     fn remove(&self, key: &K) -> Option<&'static V> {
         self.inner.harris_remove(key)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.inner.validate()
+    }
 }
 
 #[cfg(test)]
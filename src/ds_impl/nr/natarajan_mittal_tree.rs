@@ -313,6 +313,47 @@ where
             .is_ok()
     }
 
+    /// Checks that the tree is a leaf-oriented BST without marked edges, and returns the keys of
+    /// its leaves in order.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys = Vec::new();
+        // Nodes with the bounds of the keys of their leaves, in the order of a DFS.
+        let mut stack = vec![
+            (
+                self.r.right.load(Ordering::Acquire),
+                Some(&self.r.key),
+                None,
+            ),
+            (self.r.left.load(Ordering::Acquire), None, Some(&self.r.key)),
+        ];
+        while let Some((node, lo, hi)) = stack.pop() {
+            if node.tag() != 0 {
+                return Err("a marked edge remains".to_string());
+            }
+            let node_ref = unsafe { node.deref() };
+            let left = node_ref.left.load(Ordering::Acquire);
+            let right = node_ref.right.load(Ordering::Acquire);
+            match (left.is_null(), right.is_null()) {
+                (true, true) => {
+                    if let Key::Fin(key) = &node_ref.key {
+                        if lo.map_or(false, |lo: &Key<K>| node_ref.key < *lo)
+                            || hi.map_or(false, |hi: &Key<K>| node_ref.key >= *hi)
+                        {
+                            return Err("a leaf is in the wrong subtree".to_string());
+                        }
+                        keys.push(key);
+                    }
+                }
+                (false, false) => {
+                    stack.push((right, Some(&node_ref.key), hi));
+                    stack.push((left, lo, Some(&node_ref.key)));
+                }
+                _ => return Err("an internal node has a single child".to_string()),
+            }
+        }
+        Ok(keys)
+    }
+
     pub fn get(&self, key: &K) -> Option<&'static V> {
         let record = self.seek_leaf(key);
         let leaf_node = unsafe { record.leaf.deref() };
@@ -456,6 +497,9 @@ where
     fn remove(&self, key: &K) -> Option<&'static V> {
         self.remove(key)
     }
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::mem::transmute;
 use std::sync::atomic::Ordering;
 
@@ -32,12 +33,12 @@ impl<K, V> Node<K, V> {
 
     fn generate_height() -> usize {
         // returns 1 with probability 3/4
-        if rand::random::<usize>() % 4 < 3 {
+        if crate::utils::random::<usize>() % 4 < 3 {
             return 1;
         }
         // returns h with probability 2^(−(h+1))
         let mut height = 2;
-        while height < MAX_HEIGHT && rand::random::<bool>() {
+        while height < MAX_HEIGHT && crate::utils::random::<bool>() {
             height += 1;
         }
         height
@@ -98,6 +99,42 @@ where
         }
     }
 
+    /// Checks that each level is sorted and only links the unmarked nodes of the lower levels
+    /// that are tall enough, and returns the keys of the unmarked nodes. Marked nodes that are not
+    /// unlinked yet may remain.
+    pub fn validate(&self) -> Result<Vec<&K>, String> {
+        let mut keys: Vec<&K> = Vec::new();
+        // The unmarked nodes of the level below.
+        let mut lower = HashSet::new();
+        for level in 0..MAX_HEIGHT {
+            let mut nodes = HashSet::new();
+            let mut last: Option<&K> = None;
+            let mut curr = self.head[level].load(Ordering::Acquire);
+            while let Some(node) = unsafe { curr.with_tag(0).as_ref() } {
+                if last.map_or(false, |last| *last > node.key) {
+                    return Err(format!("level {level} is out of order"));
+                }
+                last = Some(&node.key);
+                curr = node.next[level].load(Ordering::Acquire);
+                if node.next[0].load(Ordering::Acquire).tag() & 1 != 0 {
+                    continue;
+                }
+                if level >= node.height || (level > 0 && !lower.contains(&(node as *const _))) {
+                    return Err(format!("level {level} links a node not in the lower level"));
+                }
+                if level == 0 {
+                    if keys.last().map_or(false, |last| **last == node.key) {
+                        return Err("duplicate keys".to_string());
+                    }
+                    keys.push(&node.key);
+                }
+                nodes.insert(node as *const Node<K, V>);
+            }
+            lower = nodes;
+        }
+        Ok(keys)
+    }
+
     fn find_optimistic<'g>(&'g self, key: &K) -> Cursor<'g, K, V> {
         let mut cursor = Cursor::new(&self.head);
 
@@ -291,33 +328,35 @@ where
     }
 
     pub fn remove<'g>(&'g self, key: &K) -> Option<&'g V> {
-        let cursor = self.find(key);
-        let node = cursor.found?;
-
-        // Try removing the node by marking its tower.
-        if node.mark_tower() {
-            for level in (0..node.height).rev() {
-                let succ = node.next[level].load(Ordering::SeqCst);
-                if (succ.tag() & 2) != 0 {
-                    continue;
-                }
+        loop {
+            let cursor = self.find(key);
+            let node = cursor.found?;
+
+            // Try removing the node by marking its tower.
+            if node.mark_tower() {
+                for level in (0..node.height).rev() {
+                    let succ = node.next[level].load(Ordering::SeqCst);
+                    if (succ.tag() & 2) != 0 {
+                        continue;
+                    }
 
-                // Try linking the predecessor and successor at this level.
-                if cursor.preds[level][level]
-                    .compare_exchange(
-                        Shared::from(node as *const _ as usize),
-                        succ.with_tag(0),
-                        Ordering::SeqCst,
-                        Ordering::SeqCst,
-                    )
-                    .is_err()
-                {
-                    self.find(key);
-                    break;
+                    // Try linking the predecessor and successor at this level.
+                    if cursor.preds[level][level]
+                        .compare_exchange(
+                            Shared::from(node as *const _ as usize),
+                            succ.with_tag(0),
+                            Ordering::SeqCst,
+                            Ordering::SeqCst,
+                        )
+                        .is_err()
+                    {
+                        self.find(key);
+                        break;
+                    }
                 }
+                return Some(&node.value);
             }
         }
-        Some(&node.value)
     }
 }
 
@@ -345,6 +384,10 @@ where
     fn remove(&self, key: &K) -> Option<&'static V> {
         unsafe { transmute(self.remove(key)) }
     }
+
+    fn validate(&self) -> Result<Vec<&K>, String> {
+        self.validate()
+    }
 }
 
 #[cfg(test)]
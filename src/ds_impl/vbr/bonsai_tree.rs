@@ -0,0 +1,705 @@
+use vbr::{Entry, Global, Guard, ImmAtomic, Local, MutAtomic, Shared};
+
+use super::concurrent_map::{epoch_advanced, ConcurrentMap};
+use smr_stats::{Pool, Stats};
+use std::cmp;
+use std::mem::zeroed;
+use std::sync::atomic::Ordering;
+
+static WEIGHT: usize = 2;
+
+// TODO: optimization from the paper? IBR paper doesn't do that
+
+/// A node of the tree.
+///
+/// Note: Unlike the other implementations, a node is never modified after its `allocate`, so the
+/// children of a retired node are not marked. Instead, every read is validated by the epoch of
+/// the guard, and a stale state is detected by `check_root` or the root CAS.
+pub struct Node<K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    key: ImmAtomic<K>,
+    value: ImmAtomic<V>,
+    size: ImmAtomic<usize>,
+    left: MutAtomic<Node<K, V>>,
+    right: MutAtomic<Node<K, V>>,
+}
+
+impl<K, V> Node<K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    fn new<'g>(
+        key: K,
+        value: V,
+        size: usize,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        guard.allocate(|node| unsafe {
+            let node_ref = node.deref();
+            node_ref.key.set(key);
+            node_ref.value.set(value);
+            node_ref.size.set(size);
+            node_ref.left.store(node, left);
+            node_ref.right.store(node, right);
+        })
+    }
+
+    fn node_size(node: Shared<Self>, guard: &Guard<Self>) -> Result<usize, ()> {
+        if let Some(node_ref) = node.as_ref() {
+            node_ref.size.get(guard)
+        } else {
+            Ok(0)
+        }
+    }
+}
+
+/// Each op creates a new local state and tries to update (CAS) the tree with it.
+///
+/// Every step returns `Err(())` if the guard has expired, an allocation failed, or the root has
+/// changed. Then the op aborts the state and retries with a new guard.
+struct State<'g, K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    /// The sentinel whose left child is the root.
+    sentinel: Shared<'g, Node<K, V>>,
+    curr_root: Shared<'g, Node<K, V>>,
+    /// Nodes that current op wants to remove from the tree. Should be retired if CAS succeeds.
+    /// (`retire`). If not, ignore.
+    retired_nodes: Vec<Shared<'g, Node<K, V>>>,
+    /// Nodes newly constructed by the op. Should be retired if CAS fails, as they return to the
+    /// pool only by retirement.
+    new_nodes: Vec<Shared<'g, Node<K, V>>>,
+}
+
+impl<'g, K, V> State<'g, K, V>
+where
+    K: 'static + Copy + Ord,
+    V: 'static + Copy,
+{
+    fn new(sentinel: Shared<'g, Node<K, V>>) -> Self {
+        Self {
+            sentinel,
+            curr_root: Shared::null(),
+            retired_nodes: Vec::new(),
+            new_nodes: Vec::new(),
+        }
+    }
+
+    fn root_link(&self) -> &'g MutAtomic<Node<K, V>> {
+        &unsafe { self.sentinel.deref() }.left
+    }
+
+    fn load_root(&mut self, guard: &'g Guard<Node<K, V>>) -> Result<(), ()> {
+        self.curr_root = self.root_link().load(Ordering::Acquire, guard)?;
+        Ok(())
+    }
+
+    /// Destroy the newly created state (self) that lost the race (reclaim_state)
+    fn abort(&mut self, guard: &Guard<Node<K, V>>) {
+        self.retired_nodes.clear();
+
+        for node in self.new_nodes.drain(..) {
+            unsafe { guard.retire(node) };
+        }
+    }
+
+    /// Retire the old state replaced by the new_state and the new_state.retired_nodes
+    fn commit(&mut self, guard: &Guard<Node<K, V>>) {
+        self.new_nodes.clear();
+
+        for node in self.retired_nodes.drain(..) {
+            unsafe { guard.retire(node) };
+        }
+    }
+
+    fn retire_node(&mut self, node: Shared<'g, Node<K, V>>) {
+        self.retired_nodes.push(node);
+    }
+
+    fn add_new_node(&mut self, node: Shared<'g, Node<K, V>>) {
+        self.new_nodes.push(node);
+    }
+
+    fn mk_node(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        let left_size = Node::node_size(left, guard)?;
+        let right_size = Node::node_size(right, guard)?;
+        let new_node = Node::new(key, value, left_size + right_size + 1, left, right, guard)?;
+        self.add_new_node(new_node);
+        Ok(new_node)
+    }
+
+    /// Make a new balanced tree from cur (the root of a subtree) and newly constructed left and right subtree
+    fn mk_balanced(
+        &mut self,
+        cur: Shared<'g, Node<K, V>>,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        let cur_ref = unsafe { cur.deref() };
+        let key = cur_ref.key.get(guard)?;
+        let value = cur_ref.value.get(guard)?;
+
+        let l_size = Node::node_size(left, guard)?;
+        let r_size = Node::node_size(right, guard)?;
+        let res = if r_size > 0
+            && ((l_size > 0 && r_size > WEIGHT * l_size) || (l_size == 0 && r_size > WEIGHT))
+        {
+            self.mk_balanced_left(left, right, key, value, guard)?
+        } else if l_size > 0
+            && ((r_size > 0 && l_size > WEIGHT * r_size) || (r_size == 0 && l_size > WEIGHT))
+        {
+            self.mk_balanced_right(left, right, key, value, guard)?
+        } else {
+            self.mk_node(left, right, key, value, guard)?
+        };
+        self.retire_node(cur);
+        Ok(res)
+    }
+
+    #[inline]
+    fn mk_balanced_left(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        let right_ref = unsafe { right.deref() };
+        let right_left = right_ref.left.load(Ordering::Acquire, guard)?;
+        let right_right = right_ref.right.load(Ordering::Acquire, guard)?;
+        self.check_root(guard)?;
+
+        if Node::node_size(right_left, guard)? < Node::node_size(right_right, guard)? {
+            // single left rotation
+            return self.single_left(left, right, right_left, right_right, key, value, guard);
+        }
+
+        // double left rotation
+        return self.double_left(left, right, right_left, right_right, key, value, guard);
+    }
+
+    #[inline]
+    fn single_left(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        right_left: Shared<'g, Node<K, V>>,
+        right_right: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        let right_ref = unsafe { right.deref() };
+        let new_left = self.mk_node(left, right_left, key, value, guard)?;
+        let res = self.mk_node(
+            new_left,
+            right_right,
+            right_ref.key.get(guard)?,
+            right_ref.value.get(guard)?,
+            guard,
+        )?;
+        self.retire_node(right);
+        Ok(res)
+    }
+
+    #[inline]
+    fn double_left(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        right_left: Shared<'g, Node<K, V>>,
+        right_right: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        let right_ref = unsafe { right.deref() };
+        let right_left_ref = unsafe { right_left.deref() };
+        let right_left_left = right_left_ref.left.load(Ordering::Acquire, guard)?;
+        let right_left_right = right_left_ref.right.load(Ordering::Acquire, guard)?;
+        self.check_root(guard)?;
+
+        let new_left = self.mk_node(left, right_left_left, key, value, guard)?;
+        let new_right = self.mk_node(
+            right_left_right,
+            right_right,
+            right_ref.key.get(guard)?,
+            right_ref.value.get(guard)?,
+            guard,
+        )?;
+        let res = self.mk_node(
+            new_left,
+            new_right,
+            right_left_ref.key.get(guard)?,
+            right_left_ref.value.get(guard)?,
+            guard,
+        )?;
+        self.retire_node(right_left);
+        self.retire_node(right);
+        Ok(res)
+    }
+
+    #[inline]
+    fn mk_balanced_right(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        let left_ref = unsafe { left.deref() };
+        let left_right = left_ref.right.load(Ordering::Acquire, guard)?;
+        let left_left = left_ref.left.load(Ordering::Acquire, guard)?;
+        self.check_root(guard)?;
+
+        if Node::node_size(left_right, guard)? < Node::node_size(left_left, guard)? {
+            // single right rotation (fig 3)
+            return self.single_right(left, right, left_right, left_left, key, value, guard);
+        }
+        // double right rotation
+        return self.double_right(left, right, left_right, left_left, key, value, guard);
+    }
+
+    #[inline]
+    fn single_right(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        left_right: Shared<'g, Node<K, V>>,
+        left_left: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        let left_ref = unsafe { left.deref() };
+        let new_right = self.mk_node(left_right, right, key, value, guard)?;
+        let res = self.mk_node(
+            left_left,
+            new_right,
+            left_ref.key.get(guard)?,
+            left_ref.value.get(guard)?,
+            guard,
+        )?;
+        self.retire_node(left);
+        Ok(res)
+    }
+
+    #[inline]
+    fn double_right(
+        &mut self,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        left_right: Shared<'g, Node<K, V>>,
+        left_left: Shared<'g, Node<K, V>>,
+        key: K,
+        value: V,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        let left_ref = unsafe { left.deref() };
+        let left_right_ref = unsafe { left_right.deref() };
+        let left_right_left = left_right_ref.left.load(Ordering::Acquire, guard)?;
+        let left_right_right = left_right_ref.right.load(Ordering::Acquire, guard)?;
+        self.check_root(guard)?;
+
+        let new_left = self.mk_node(
+            left_left,
+            left_right_left,
+            left_ref.key.get(guard)?,
+            left_ref.value.get(guard)?,
+            guard,
+        )?;
+        let new_right = self.mk_node(left_right_right, right, key, value, guard)?;
+        let res = self.mk_node(
+            new_left,
+            new_right,
+            left_right_ref.key.get(guard)?,
+            left_right_ref.value.get(guard)?,
+            guard,
+        )?;
+        self.retire_node(left_right);
+        self.retire_node(left);
+        Ok(res)
+    }
+
+    #[inline]
+    fn do_insert(
+        &mut self,
+        node: Shared<'g, Node<K, V>>,
+        key: &K,
+        value: &V,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<(Shared<'g, Node<K, V>>, bool), ()> {
+        if node.is_null() {
+            return Ok((
+                self.mk_node(Shared::null(), Shared::null(), *key, *value, guard)?,
+                true,
+            ));
+        }
+
+        let node_ref = unsafe { node.deref() };
+        let left = node_ref.left.load(Ordering::Acquire, guard)?;
+        let right = node_ref.right.load(Ordering::Acquire, guard)?;
+        self.check_root(guard)?;
+
+        match node_ref.key.get(guard)?.cmp(key) {
+            cmp::Ordering::Equal => Ok((node, false)),
+            cmp::Ordering::Less => {
+                let (new_right, inserted) = self.do_insert(right, key, value, guard)?;
+                Ok((self.mk_balanced(node, left, new_right, guard)?, inserted))
+            }
+            cmp::Ordering::Greater => {
+                let (new_left, inserted) = self.do_insert(left, key, value, guard)?;
+                Ok((self.mk_balanced(node, new_left, right, guard)?, inserted))
+            }
+        }
+    }
+
+    #[inline]
+    fn do_remove(
+        &mut self,
+        node: Shared<'g, Node<K, V>>,
+        key: &K,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<(Shared<'g, Node<K, V>>, Option<V>), ()> {
+        if node.is_null() {
+            return Ok((Shared::null(), None));
+        }
+
+        let node_ref = unsafe { node.deref() };
+        let left = node_ref.left.load(Ordering::Acquire, guard)?;
+        let right = node_ref.right.load(Ordering::Acquire, guard)?;
+        self.check_root(guard)?;
+
+        match node_ref.key.get(guard)?.cmp(key) {
+            cmp::Ordering::Equal => {
+                let value = Some(node_ref.value.get(guard)?);
+                self.retire_node(node);
+                if node_ref.size.get(guard)? == 1 {
+                    return Ok((Shared::null(), value));
+                }
+
+                if !left.is_null() {
+                    let (new_left, succ) = self.pull_rightmost(left, guard)?;
+                    return Ok((self.mk_balanced(succ, new_left, right, guard)?, value));
+                }
+                let (new_right, succ) = self.pull_leftmost(right, guard)?;
+                Ok((self.mk_balanced(succ, left, new_right, guard)?, value))
+            }
+            cmp::Ordering::Less => {
+                let (new_right, value) = self.do_remove(right, key, guard)?;
+                Ok((self.mk_balanced(node, left, new_right, guard)?, value))
+            }
+            cmp::Ordering::Greater => {
+                let (new_left, value) = self.do_remove(left, key, guard)?;
+                Ok((self.mk_balanced(node, new_left, right, guard)?, value))
+            }
+        }
+    }
+
+    fn pull_leftmost(
+        &mut self,
+        node: Shared<'g, Node<K, V>>,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<(Shared<'g, Node<K, V>>, Shared<'g, Node<K, V>>), ()> {
+        let node_ref = node.as_ref().ok_or(())?;
+        let left = node_ref.left.load(Ordering::Acquire, guard)?;
+        let right = node_ref.right.load(Ordering::Acquire, guard)?;
+        self.check_root(guard)?;
+
+        if !left.is_null() {
+            let (new_left, succ) = self.pull_leftmost(left, guard)?;
+            return Ok((self.mk_balanced(node, new_left, right, guard)?, succ));
+        }
+        // node is the leftmost
+        let succ = self.mk_node(
+            Shared::null(),
+            Shared::null(),
+            node_ref.key.get(guard)?,
+            node_ref.value.get(guard)?,
+            guard,
+        )?;
+        self.retire_node(node);
+        Ok((right, succ))
+    }
+
+    fn pull_rightmost(
+        &mut self,
+        node: Shared<'g, Node<K, V>>,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<(Shared<'g, Node<K, V>>, Shared<'g, Node<K, V>>), ()> {
+        let node_ref = node.as_ref().ok_or(())?;
+        let left = node_ref.left.load(Ordering::Acquire, guard)?;
+        let right = node_ref.right.load(Ordering::Acquire, guard)?;
+        self.check_root(guard)?;
+
+        if !right.is_null() {
+            let (new_right, succ) = self.pull_rightmost(right, guard)?;
+            return Ok((self.mk_balanced(node, left, new_right, guard)?, succ));
+        }
+        // node is the rightmost
+        let succ = self.mk_node(
+            Shared::null(),
+            Shared::null(),
+            node_ref.key.get(guard)?,
+            node_ref.value.get(guard)?,
+            guard,
+        )?;
+        self.retire_node(node);
+        Ok((left, succ))
+    }
+
+    fn check_root(&self, guard: &'g Guard<Node<K, V>>) -> Result<(), ()> {
+        if self.curr_root == self.root_link().load(Ordering::Acquire, guard)? {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+}
+
+pub struct BonsaiTreeMap<K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    /// A sentinel whose left child is the root, as the link of an `Entry` is immutable.
+    sentinel: Entry<Node<K, V>>,
+}
+
+impl<K, V> BonsaiTreeMap<K, V>
+where
+    K: 'static + Copy + Ord,
+    V: 'static + Copy,
+{
+    pub fn new(local: &Local<Node<K, V>>) -> Self {
+        let guard = &local.guard();
+        let sentinel =
+            unsafe { Node::new(zeroed(), zeroed(), 0, Shared::null(), Shared::null(), guard) }
+                .unwrap();
+        Self {
+            sentinel: Entry::new(sentinel),
+        }
+    }
+
+    /// Checks that the tree is a BST with correct sizes, and returns its keys in order.
+    pub fn validate(&self, local: &Local<Node<K, V>>) -> Result<Vec<K>, String> {
+        /// Checks the subtree at `node` whose keys are in `(lo, hi)`, and returns its size.
+        fn check<K: 'static + Copy + Ord, V: 'static + Copy>(
+            node: Shared<'_, Node<K, V>>,
+            lo: Option<K>,
+            hi: Option<K>,
+            keys: &mut Vec<K>,
+            guard: &Guard<Node<K, V>>,
+        ) -> Result<usize, String> {
+            let node_ref = some_or!(node.as_ref(), return Ok(0));
+            let key = node_ref.key.get(guard).map_err(epoch_advanced)?;
+            if lo.map_or(false, |lo| key <= lo) || hi.map_or(false, |hi| key >= hi) {
+                return Err("a node is in the wrong subtree".to_string());
+            }
+            let left = node_ref
+                .left
+                .load(Ordering::Acquire, guard)
+                .map_err(epoch_advanced)?;
+            let right = node_ref
+                .right
+                .load(Ordering::Acquire, guard)
+                .map_err(epoch_advanced)?;
+            let left_size = check(left, lo, Some(key), keys, guard)?;
+            keys.push(key);
+            let right_size = check(right, Some(key), hi, keys, guard)?;
+            let size = node_ref.size.get(guard).map_err(epoch_advanced)?;
+            if size != left_size + right_size + 1 {
+                return Err("a node has a wrong size".to_string());
+            }
+            Ok(size)
+        }
+
+        let guard = &local.guard();
+        let sentinel = self.sentinel.load(guard).map_err(epoch_advanced)?;
+        let root = unsafe { sentinel.deref() }
+            .left
+            .load(Ordering::Acquire, guard)
+            .map_err(epoch_advanced)?;
+        let mut keys = Vec::new();
+        check(root, None, None, &mut keys, guard)?;
+        Ok(keys)
+    }
+
+    fn find(&self, key: &K, guard: &Guard<Node<K, V>>) -> Result<Option<V>, ()> {
+        let sentinel = self.sentinel.load(guard)?;
+        let mut node = unsafe { sentinel.deref() }
+            .left
+            .load(Ordering::Acquire, guard)?;
+        while let Some(node_ref) = node.as_ref() {
+            match key.cmp(&node_ref.key.get(guard)?) {
+                cmp::Ordering::Equal => return Ok(Some(node_ref.value.get(guard)?)),
+                cmp::Ordering::Less => node = node_ref.left.load(Ordering::Acquire, guard)?,
+                cmp::Ordering::Greater => node = node_ref.right.load(Ordering::Acquire, guard)?,
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn get(&self, key: &K, local: &Local<Node<K, V>>) -> Option<V> {
+        loop {
+            let guard = &local.guard();
+            return ok_or!(self.find(key, guard), continue);
+        }
+    }
+
+    /// Tries to replace the root of `state` with `new_root`, and commits or aborts `state`.
+    fn try_commit<'g>(
+        &self,
+        state: &mut State<'g, K, V>,
+        new_root: Result<Shared<'g, Node<K, V>>, ()>,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<(), ()> {
+        let new_root = ok_or!(new_root, {
+            state.abort(guard);
+            return Err(());
+        });
+        if state
+            .root_link()
+            .compare_exchange(
+                state.sentinel,
+                state.curr_root,
+                new_root,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+                guard,
+            )
+            .success()
+            .is_ok()
+        {
+            state.commit(guard);
+            Ok(())
+        } else {
+            state.abort(guard);
+            Err(())
+        }
+    }
+
+    fn insert_inner(&self, key: K, value: V, guard: &Guard<Node<K, V>>) -> Result<bool, ()> {
+        let mut state = State::new(self.sentinel.load(guard)?);
+        state.load_root(guard)?;
+        let old_root = state.curr_root;
+        let mut inserted = false;
+        let new_root = state
+            .do_insert(old_root, &key, &value, guard)
+            .map(|(new_root, result)| {
+                inserted = result;
+                new_root
+            });
+        self.try_commit(&mut state, new_root, guard)?;
+        Ok(inserted)
+    }
+
+    pub fn insert(&self, key: K, value: V, local: &Local<Node<K, V>>) -> bool {
+        loop {
+            if let Ok(r) = self.insert_inner(key, value, &local.guard()) {
+                return r;
+            }
+        }
+    }
+
+    fn remove_inner(&self, key: &K, guard: &Guard<Node<K, V>>) -> Result<Option<V>, ()> {
+        let mut state = State::new(self.sentinel.load(guard)?);
+        state.load_root(guard)?;
+        let old_root = state.curr_root;
+        let mut value = None;
+        let new_root = state
+            .do_remove(old_root, key, guard)
+            .map(|(new_root, result)| {
+                value = result;
+                new_root
+            });
+        self.try_commit(&mut state, new_root, guard)?;
+        Ok(value)
+    }
+
+    pub fn remove(&self, key: &K, local: &Local<Node<K, V>>) -> Option<V> {
+        loop {
+            if let Ok(r) = self.remove_inner(key, &local.guard()) {
+                return r;
+            }
+        }
+    }
+}
+
+impl<K, V> ConcurrentMap<K, V> for BonsaiTreeMap<K, V>
+where
+    K: 'static + Copy + Ord,
+    V: 'static + Copy,
+{
+    type Global = Global<Node<K, V>>;
+
+    type Local = Local<Node<K, V>>;
+
+    fn global(key_range_hint: usize) -> Self::Global {
+        Global::new(key_range_hint)
+    }
+
+    fn local(global: &Self::Global) -> Self::Local {
+        Local::new(global)
+    }
+
+    fn stats(global: &Self::Global) -> Stats {
+        global.stats()
+    }
+
+    fn pool(global: &Self::Global) -> Pool {
+        global.pool()
+    }
+
+    fn new(local: &Self::Local) -> Self {
+        BonsaiTreeMap::new(local)
+    }
+
+    #[inline(always)]
+    fn get(&self, key: &K, local: &Self::Local) -> Option<V> {
+        self.get(key, local)
+    }
+
+    #[inline(always)]
+    fn insert(&self, key: K, value: V, local: &Self::Local) -> bool {
+        self.insert(key, value, local)
+    }
+
+    #[inline(always)]
+    fn remove(&self, key: &K, local: &Self::Local) -> Option<V> {
+        self.remove(key, local)
+    }
+
+    fn validate(&self, local: &Self::Local) -> Result<Vec<K>, String> {
+        self.validate(local)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BonsaiTreeMap;
+    use crate::ds_impl::vbr::concurrent_map;
+
+    #[test]
+    fn smoke_bonsai_tree() {
+        concurrent_map::tests::smoke::<BonsaiTreeMap<i32, i32>>();
+    }
+}
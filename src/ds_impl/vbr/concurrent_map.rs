@@ -1,13 +1,27 @@
+use smr_stats::{Pool, Stats};
+
 pub trait ConcurrentMap<K, V> {
     type Global: Sync;
     type Local;
 
     fn global(key_range_hint: usize) -> Self::Global;
     fn local(global: &Self::Global) -> Self::Local;
+    fn stats(global: &Self::Global) -> Stats;
+    fn pool(global: &Self::Global) -> Pool;
     fn new(local: &Self::Local) -> Self;
     fn get(&self, key: &K, local: &Self::Local) -> Option<V>;
     fn insert(&self, key: K, value: V, local: &Self::Local) -> bool;
     fn remove(&self, key: &K, local: &Self::Local) -> Option<V>;
+
+    /// Checks the structural invariants of the map and returns its keys. It must not run
+    /// concurrently with the other operations.
+    fn validate(&self, local: &Self::Local) -> Result<Vec<K>, String>;
+}
+
+/// The error of a read of `validate`, which fails only if the epoch advanced, i.e., another
+/// operation ran concurrently.
+pub(crate) fn epoch_advanced(_: ()) -> String {
+    "the epoch advanced during the validation".to_string()
 }
 
 #[cfg(test)]
@@ -72,5 +86,13 @@ pub mod tests {
             }
         })
         .unwrap();
+
+        let mut keys = map.validate(local).unwrap();
+        keys.sort();
+        let mut expected = ((THREADS / 2)..THREADS)
+            .flat_map(|t| (0..ELEMENTS_PER_THREADS).map(move |k| k * THREADS + t))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(keys, expected);
     }
 }
@@ -0,0 +1,752 @@
+use vbr::CompareExchangeError::*;
+use vbr::{Entry, Global, Guard, ImmAtomic, Local, MutAtomic, Shared};
+
+use super::concurrent_map::{epoch_advanced, ConcurrentMap};
+use smr_stats::{Pool, Stats};
+use std::mem::zeroed;
+use std::sync::atomic::Ordering;
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    struct UpdateTag: usize {
+        const CLEAN = 0usize;
+        const DFLAG = 1usize;
+        const IFLAG = 2usize;
+        const MARK = 3usize;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Key<K> {
+    Fin(K),
+    Inf1,
+    Inf2,
+}
+
+impl<K> PartialOrd for Key<K>
+where
+    K: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (Key::Fin(k1), Key::Fin(k2)) => k1.partial_cmp(k2),
+            (Key::Fin(_), Key::Inf1) => Some(std::cmp::Ordering::Less),
+            (Key::Fin(_), Key::Inf2) => Some(std::cmp::Ordering::Less),
+            (Key::Inf1, Key::Fin(_)) => Some(std::cmp::Ordering::Greater),
+            (Key::Inf1, Key::Inf1) => Some(std::cmp::Ordering::Equal),
+            (Key::Inf1, Key::Inf2) => Some(std::cmp::Ordering::Less),
+            (Key::Inf2, Key::Fin(_)) => Some(std::cmp::Ordering::Greater),
+            (Key::Inf2, Key::Inf1) => Some(std::cmp::Ordering::Greater),
+            (Key::Inf2, Key::Inf2) => Some(std::cmp::Ordering::Equal),
+        }
+    }
+}
+
+impl<K> PartialEq<K> for Key<K>
+where
+    K: PartialEq,
+{
+    fn eq(&self, rhs: &K) -> bool {
+        match self {
+            Key::Fin(k) => k == rhs,
+            _ => false,
+        }
+    }
+}
+
+impl<K> Key<K>
+where
+    K: Ord,
+{
+    fn cmp(&self, rhs: &K) -> std::cmp::Ordering {
+        match self {
+            Key::Fin(k) => k.cmp(rhs),
+            _ => std::cmp::Ordering::Greater,
+        }
+    }
+}
+
+/// A node of the tree, which also serves as the Info record of an update.
+///
+/// Note: A `Global` of VBR pools the objects of a single type, so an Info record is a node whose
+/// `gp`, `p`, `l`, `pupdate` and `new_internal` fields are used. An insert uses `p`,
+/// `new_internal` and `l`, and a delete uses `gp`, `p`, `l` and `pupdate`. The kind of a record
+/// is given by the tag of the `update` edge to it.
+pub struct Node<K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    key: ImmAtomic<Key<K>>,
+    value: ImmAtomic<V>,
+    // tag on low bits: {Clean, DFlag, IFlag, Mark}
+    update: MutAtomic<Node<K, V>>,
+    left: MutAtomic<Node<K, V>>,
+    right: MutAtomic<Node<K, V>>,
+    gp: MutAtomic<Node<K, V>>,
+    p: MutAtomic<Node<K, V>>,
+    l: MutAtomic<Node<K, V>>,
+    pupdate: MutAtomic<Node<K, V>>,
+    new_internal: MutAtomic<Node<K, V>>,
+}
+
+impl<K, V> Node<K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    /// Clears the edges of a (possibly reused) node.
+    ///
+    /// # Safety
+    ///
+    /// It is allowed only in an `allocate` of `node`.
+    unsafe fn clear(&self, node: Shared<Node<K, V>>) {
+        for link in [
+            &self.update,
+            &self.left,
+            &self.right,
+            &self.gp,
+            &self.p,
+            &self.l,
+            &self.pupdate,
+            &self.new_internal,
+        ] {
+            link.store(node, Shared::null());
+        }
+    }
+
+    fn new_leaf(
+        key: Key<K>,
+        value: V,
+        guard: &Guard<Node<K, V>>,
+    ) -> Result<Shared<'_, Node<K, V>>, ()> {
+        guard.allocate(|node| unsafe {
+            let node_ref = node.deref();
+            node_ref.key.set(key);
+            node_ref.value.set(value);
+            node_ref.clear(node);
+        })
+    }
+
+    fn new_internal<'g>(
+        key: Key<K>,
+        left: Shared<'g, Node<K, V>>,
+        right: Shared<'g, Node<K, V>>,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        guard.allocate(|node| unsafe {
+            let node_ref = node.deref();
+            node_ref.key.set(key);
+            node_ref.value.set(zeroed());
+            node_ref.clear(node);
+            node_ref.left.store(node, left);
+            node_ref.right.store(node, right);
+        })
+    }
+
+    fn new_insert<'g>(
+        record: &Insert<'g, K, V>,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        guard.allocate(|node| unsafe {
+            let node_ref = node.deref();
+            node_ref.clear(node);
+            node_ref.p.store(node, record.p);
+            node_ref.new_internal.store(node, record.new_internal);
+            node_ref.l.store(node, record.l);
+        })
+    }
+
+    fn new_delete<'g>(
+        record: &Delete<'g, K, V>,
+        guard: &'g Guard<Node<K, V>>,
+    ) -> Result<Shared<'g, Node<K, V>>, ()> {
+        guard.allocate(|node| unsafe {
+            let node_ref = node.deref();
+            node_ref.clear(node);
+            node_ref.gp.store(node, record.gp);
+            node_ref.p.store(node, record.p);
+            node_ref.l.store(node, record.l);
+            node_ref.pupdate.store(node, record.pupdate);
+        })
+    }
+}
+
+/// The fields of an insert record.
+struct Insert<'g, K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    p: Shared<'g, Node<K, V>>,
+    new_internal: Shared<'g, Node<K, V>>,
+    l: Shared<'g, Node<K, V>>,
+}
+
+impl<'g, K, V> Insert<'g, K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    fn load(op: Shared<'g, Node<K, V>>, guard: &'g Guard<Node<K, V>>) -> Result<Self, ()> {
+        let op_node = op.as_ref().ok_or(())?;
+        let record = Self {
+            p: op_node.p.load(Ordering::Relaxed, guard)?,
+            new_internal: op_node.new_internal.load(Ordering::Relaxed, guard)?,
+            l: op_node.l.load(Ordering::Relaxed, guard)?,
+        };
+        if record.p.is_null() || record.new_internal.is_null() || record.l.is_null() {
+            // `op` has been reused for another node.
+            return Err(());
+        }
+        Ok(record)
+    }
+}
+
+/// The fields of a delete record.
+struct Delete<'g, K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    gp: Shared<'g, Node<K, V>>,
+    p: Shared<'g, Node<K, V>>,
+    l: Shared<'g, Node<K, V>>,
+    pupdate: Shared<'g, Node<K, V>>,
+}
+
+impl<'g, K, V> Delete<'g, K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    fn load(op: Shared<'g, Node<K, V>>, guard: &'g Guard<Node<K, V>>) -> Result<Self, ()> {
+        let op_node = op.as_ref().ok_or(())?;
+        let record = Self {
+            gp: op_node.gp.load(Ordering::Relaxed, guard)?,
+            p: op_node.p.load(Ordering::Relaxed, guard)?,
+            l: op_node.l.load(Ordering::Relaxed, guard)?,
+            pupdate: op_node.pupdate.load(Ordering::Relaxed, guard)?,
+        };
+        if record.gp.is_null() || record.p.is_null() || record.l.is_null() {
+            // `op` has been reused for another node.
+            return Err(());
+        }
+        Ok(record)
+    }
+}
+
+struct Cursor<'g, K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    gp: Shared<'g, Node<K, V>>,
+    p: Shared<'g, Node<K, V>>,
+    l: Shared<'g, Node<K, V>>,
+    pupdate: Shared<'g, Node<K, V>>,
+    gpupdate: Shared<'g, Node<K, V>>,
+}
+
+impl<'g, K, V> Cursor<'g, K, V>
+where
+    K: 'static + Copy + Ord,
+    V: 'static + Copy,
+{
+    fn new(root: Shared<'g, Node<K, V>>) -> Self {
+        Self {
+            gp: Shared::null(),
+            p: Shared::null(),
+            l: root,
+            pupdate: Shared::null(),
+            gpupdate: Shared::null(),
+        }
+    }
+
+    /// Used by Insert, Delete and Find to traverse a branch of the BST.
+    ///
+    /// # Safety
+    /// It satisfies following postconditions:
+    ///
+    /// 1. l points to a Leaf node and p points to an Internal node
+    /// 2. Either p → left has contained l (if k<p → key) or p → right has contained l (if k ≥ p → key)
+    /// 3. p → update has contained pupdate
+    /// 4. if l → key != Inf1, then the following three statements hold:
+    ///     - gp points to an Internal node
+    ///     - either gp → left has contained p (if k < gp → key) or gp → right has contained p (if k ≥ gp → key)
+    ///     - gp → update has contained gpupdate
+    #[inline]
+    fn search(&mut self, key: &K, guard: &'g Guard<Node<K, V>>) -> Result<(), ()> {
+        loop {
+            let l_node = self.l.as_ref().ok_or(())?;
+            if l_node.left.load(Ordering::Acquire, guard)?.is_null() {
+                return Ok(());
+            }
+            self.gp = self.p;
+            self.p = self.l;
+            self.gpupdate = self.pupdate;
+            self.pupdate = l_node.update.load(Ordering::Acquire, guard)?;
+            self.l = match l_node.key.get(guard)?.cmp(key) {
+                std::cmp::Ordering::Greater => l_node.left.load(Ordering::Acquire, guard)?,
+                _ => l_node.right.load(Ordering::Acquire, guard)?,
+            }
+        }
+    }
+}
+
+pub struct EFRBTree<K, V>
+where
+    K: 'static + Copy,
+    V: 'static + Copy,
+{
+    root: Entry<Node<K, V>>,
+}
+
+impl<K, V> EFRBTree<K, V>
+where
+    K: 'static + Copy + Ord,
+    V: 'static + Copy,
+{
+    pub fn new(local: &Local<Node<K, V>>) -> Self {
+        let guard = &local.guard();
+        let inf1 = Node::new_leaf(Key::Inf1, unsafe { zeroed() }, guard).unwrap();
+        let inf2 = Node::new_leaf(Key::Inf2, unsafe { zeroed() }, guard).unwrap();
+        let root = Node::new_internal(Key::Inf2, inf1, inf2, guard).unwrap();
+        Self {
+            root: Entry::new(root),
+        }
+    }
+
+    /// Checks that the tree is a leaf-oriented BST whose internal nodes are all clean, and returns
+    /// the keys of its leaves in order.
+    pub fn validate(&self, local: &Local<Node<K, V>>) -> Result<Vec<K>, String> {
+        let guard = &local.guard();
+        let mut keys = Vec::new();
+        // Nodes with the bounds of the keys of their leaves, in the order of a DFS.
+        let root = self.root.load(guard).map_err(epoch_advanced)?;
+        let mut stack = vec![(root, None, None)];
+        while let Some((node, lo, hi)) = stack.pop() {
+            let node_ref = unsafe { node.deref() };
+            let key = node_ref.key.get(guard).map_err(epoch_advanced)?;
+            let left = node_ref
+                .left
+                .load(Ordering::Acquire, guard)
+                .map_err(epoch_advanced)?;
+            let right = node_ref
+                .right
+                .load(Ordering::Acquire, guard)
+                .map_err(epoch_advanced)?;
+            match (left.is_null(), right.is_null()) {
+                (true, true) => {
+                    if let Key::Fin(k) = key {
+                        if lo.map_or(false, |lo| key < lo) || hi.map_or(false, |hi| key >= hi) {
+                            return Err("a leaf is in the wrong subtree".to_string());
+                        }
+                        keys.push(k);
+                    }
+                }
+                (false, false) => {
+                    let update = node_ref
+                        .update
+                        .load(Ordering::Acquire, guard)
+                        .map_err(epoch_advanced)?;
+                    if UpdateTag::from_bits_truncate(update.tag()) != UpdateTag::CLEAN {
+                        return Err("an internal node is not clean".to_string());
+                    }
+                    stack.push((right, Some(key), hi));
+                    stack.push((left, lo, Some(key)));
+                }
+                _ => return Err("an internal node has a single child".to_string()),
+            }
+        }
+        Ok(keys)
+    }
+
+    fn find(&self, key: &K, guard: &Guard<Node<K, V>>) -> Result<Option<V>, ()> {
+        let mut cursor = Cursor::new(self.root.load(guard)?);
+        cursor.search(key, guard)?;
+        let l_node = unsafe { cursor.l.deref() };
+        if l_node.key.get(guard)? == *key {
+            Ok(Some(l_node.value.get(guard)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn get(&self, key: &K, local: &Local<Node<K, V>>) -> Option<V> {
+        loop {
+            let guard = &local.guard();
+            return ok_or!(self.find(key, guard), continue);
+        }
+    }
+
+    fn insert_inner(&self, key: K, value: V, guard: &Guard<Node<K, V>>) -> Result<bool, ()> {
+        loop {
+            let mut cursor = Cursor::new(self.root.load(guard)?);
+            cursor.search(&key, guard)?;
+            let l_node = unsafe { cursor.l.deref() };
+            let p_node = cursor.p.as_ref().ok_or(())?;
+            let l_key = l_node.key.get(guard)?;
+
+            if l_key == key {
+                return Ok(false);
+            } else if cursor.pupdate.tag() != UpdateTag::CLEAN.bits() {
+                self.help(cursor.pupdate, guard)?;
+                continue;
+            }
+
+            let l_value = l_node.value.get(guard)?;
+            let new = Node::new_leaf(Key::Fin(key), value, guard)?;
+            let new_sibling = ok_or!(Node::new_leaf(l_key, l_value, guard), {
+                unsafe { guard.retire(new) };
+                return Err(());
+            });
+
+            let (left, right, right_key) = if Key::Fin(key) < l_key {
+                (new, new_sibling, l_key)
+            } else {
+                (new_sibling, new, Key::Fin(key))
+            };
+
+            // key field max(k, l → key), and two child fields equal to new and newSibling
+            // (the one with the smaller key is the left child)
+            let new_internal = ok_or!(Node::new_internal(right_key, left, right, guard), {
+                unsafe {
+                    guard.retire(new);
+                    guard.retire(new_sibling);
+                }
+                return Err(());
+            });
+            let record = Insert {
+                p: cursor.p,
+                new_internal,
+                l: cursor.l,
+            };
+            let op = ok_or!(Node::new_insert(&record, guard), {
+                unsafe {
+                    guard.retire(new);
+                    guard.retire(new_sibling);
+                    guard.retire(new_internal);
+                }
+                return Err(());
+            });
+
+            let new_pupdate = op.with_tag(UpdateTag::IFLAG.bits());
+            match p_node.update.compare_exchange(
+                cursor.p,
+                cursor.pupdate,
+                new_pupdate,
+                Ordering::Release,
+                Ordering::Relaxed,
+                guard,
+            ) {
+                Success(_) => {
+                    if !cursor.pupdate.is_null() {
+                        unsafe { guard.retire(cursor.pupdate) };
+                    }
+                    self.help_insert(new_pupdate, &record, guard);
+                    return Ok(true);
+                }
+                Failure(_) | Reallocated => unsafe {
+                    // The conflicting update is helped on the next search.
+                    guard.retire(new);
+                    guard.retire(new_sibling);
+                    guard.retire(new_internal);
+                    guard.retire(op);
+                },
+            }
+        }
+    }
+
+    pub fn insert(&self, key: K, value: V, local: &Local<Node<K, V>>) -> bool {
+        loop {
+            if let Ok(r) = self.insert_inner(key, value, &local.guard()) {
+                return r;
+            }
+        }
+    }
+
+    fn delete_inner(&self, key: &K, guard: &Guard<Node<K, V>>) -> Result<Option<V>, ()> {
+        loop {
+            let mut cursor = Cursor::new(self.root.load(guard)?);
+            cursor.search(key, guard)?;
+
+            if cursor.gp.is_null() {
+                // The tree is empty. There's no more things to do.
+                return Ok(None);
+            }
+
+            let l_node = unsafe { cursor.l.deref() };
+            let gp_node = unsafe { cursor.gp.deref() };
+
+            if l_node.key.get(guard)? != *key {
+                return Ok(None);
+            }
+            if cursor.gpupdate.tag() != UpdateTag::CLEAN.bits() {
+                self.help(cursor.gpupdate, guard)?;
+                continue;
+            } else if cursor.pupdate.tag() != UpdateTag::CLEAN.bits() {
+                self.help(cursor.pupdate, guard)?;
+                continue;
+            }
+
+            let value = l_node.value.get(guard)?;
+            let record = Delete {
+                gp: cursor.gp,
+                p: cursor.p,
+                l: cursor.l,
+                pupdate: cursor.pupdate,
+            };
+            let op = Node::new_delete(&record, guard)?;
+            let new_update = op.with_tag(UpdateTag::DFLAG.bits());
+            match gp_node.update.compare_exchange(
+                cursor.gp,
+                cursor.gpupdate,
+                new_update,
+                Ordering::Release,
+                Ordering::Relaxed,
+                guard,
+            ) {
+                Success(_) => {
+                    if !cursor.gpupdate.is_null() {
+                        unsafe { guard.retire(cursor.gpupdate) };
+                    }
+                    if self.help_delete(new_update, &record, guard) {
+                        return Ok(Some(value));
+                    }
+                }
+                Failure(_) | Reallocated => unsafe { guard.retire(op) },
+            }
+        }
+    }
+
+    pub fn delete(&self, key: &K, local: &Local<Node<K, V>>) -> Option<V> {
+        loop {
+            if let Ok(r) = self.delete_inner(key, &local.guard()) {
+                return r;
+            }
+        }
+    }
+
+    #[inline]
+    fn help(&self, update: Shared<Node<K, V>>, guard: &Guard<Node<K, V>>) -> Result<(), ()> {
+        match UpdateTag::from_bits_truncate(update.tag()) {
+            UpdateTag::IFLAG => self.help_insert(update, &Insert::load(update, guard)?, guard),
+            UpdateTag::MARK => self.help_marked(update, &Delete::load(update, guard)?, guard),
+            UpdateTag::DFLAG => {
+                let _ = self.help_delete(update, &Delete::load(update, guard)?, guard);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // NOTE: The helping routines below do not validate the epoch. Each of their steps is a CAS,
+    // which fails if its owner or expected node is reused, so the updater completes its own
+    // operation even if its guard is expired.
+
+    /// Returns whether the delete `op` is committed, i.e., `p` is marked by `op`.
+    fn help_delete(
+        &self,
+        op: Shared<Node<K, V>>,
+        record: &Delete<K, V>,
+        guard: &Guard<Node<K, V>>,
+    ) -> bool {
+        // Precondition: op points to a DInfo record (i.e., it is not ⊥)
+        let p_node = unsafe { record.p.deref() };
+        let new_op = op.with_tag(UpdateTag::MARK.bits());
+
+        // `pupdate` is born before `op`, unless it has been reused after `p` is updated. Then the
+        // CAS must fail, but the reused one may be installed on `p` again.
+        let result = if record.pupdate.birth() <= op.birth() {
+            p_node.update.compare_exchange(
+                record.p,
+                record.pupdate,
+                new_op,
+                Ordering::Release,
+                Ordering::Acquire,
+                guard,
+            )
+        } else {
+            let current = unsafe { p_node.update.load_unchecked(Ordering::Acquire) };
+            Failure((current.birth(), current.as_raw()))
+        };
+
+        let marked = match result {
+            Success(_) => {
+                if !record.pupdate.is_null() {
+                    unsafe { guard.retire(record.pupdate) };
+                }
+                true
+            }
+            // (prev value) = <Mark, op>, or `p` is already spliced out and reused, which only `op`
+            // can do while it flags `gp`.
+            Failure(e) => e.1 == new_op.as_raw() || !record.p.validate_birth_epoch(),
+            Reallocated => true,
+        };
+
+        if marked {
+            self.help_marked(new_op, record, guard);
+            true
+        } else {
+            // Backtrack. The conflicting update is helped on the next search.
+            let _ = unsafe { record.gp.deref() }.update.compare_exchange(
+                record.gp,
+                op.with_tag(UpdateTag::DFLAG.bits()),
+                op.with_tag(UpdateTag::CLEAN.bits()),
+                Ordering::Release,
+                Ordering::Relaxed,
+                guard,
+            );
+            false
+        }
+    }
+
+    fn help_marked(
+        &self,
+        op: Shared<Node<K, V>>,
+        record: &Delete<K, V>,
+        guard: &Guard<Node<K, V>>,
+    ) {
+        // Precondition: op points to a DInfo record (i.e., it is not ⊥)
+        // Set other to point to the sibling of the node to which op → l points
+        let p_node = unsafe { record.p.deref() };
+        let right = unsafe { p_node.right.load_unchecked(Ordering::Acquire) };
+        let other = if right.as_raw() == record.l.as_raw() {
+            unsafe { p_node.left.load_unchecked(Ordering::Acquire) }
+        } else {
+            right
+        };
+
+        // Splice the node to which op → p points out of the tree, replacing it by other
+        let spliced = self.cas_child(record.gp, record.p, other, guard);
+        let _ = unsafe { record.gp.deref() }.update.compare_exchange(
+            record.gp,
+            op.with_tag(UpdateTag::DFLAG.bits()),
+            op.with_tag(UpdateTag::CLEAN.bits()),
+            Ordering::Release,
+            Ordering::Relaxed,
+            guard,
+        );
+        // Retire them only after `op` is unflagged, as the helpers of `op` may still read them.
+        if spliced {
+            unsafe {
+                guard.retire(record.p);
+                guard.retire(record.l);
+            }
+        }
+    }
+
+    fn help_insert(
+        &self,
+        op: Shared<Node<K, V>>,
+        record: &Insert<K, V>,
+        guard: &Guard<Node<K, V>>,
+    ) {
+        // Precondition: op points to an IInfo record (i.e., it is not ⊥)
+        let replaced = self.cas_child(record.p, record.l, record.new_internal, guard);
+        let _ = unsafe { record.p.deref() }.update.compare_exchange(
+            record.p,
+            op.with_tag(UpdateTag::IFLAG.bits()),
+            op.with_tag(UpdateTag::CLEAN.bits()),
+            Ordering::Release,
+            Ordering::Relaxed,
+            guard,
+        );
+        // Likewise `help_marked`, retire it only after `op` is unflagged.
+        if replaced {
+            unsafe { guard.retire(record.l) };
+        }
+    }
+
+    #[inline]
+    fn cas_child(
+        &self,
+        parent: Shared<Node<K, V>>,
+        old: Shared<Node<K, V>>,
+        new: Shared<Node<K, V>>,
+        guard: &Guard<Node<K, V>>,
+    ) -> bool {
+        // Precondition: parent points to an Internal node and new points to a Node (i.e., neither is ⊥)
+        // This routine tries to change one of the child fields of the node that parent points to from old to new.
+        let new_node = some_or!(new.as_ref(), return false);
+        let parent_node = unsafe { parent.deref() };
+        let node_to_cas =
+            if unsafe { new_node.key.get_unchecked() < parent_node.key.get_unchecked() } {
+                &parent_node.left
+            } else {
+                &parent_node.right
+            };
+        node_to_cas
+            .compare_exchange(
+                parent,
+                old,
+                new,
+                Ordering::Release,
+                Ordering::Acquire,
+                guard,
+            )
+            .success()
+            .is_ok()
+    }
+}
+
+impl<K, V> ConcurrentMap<K, V> for EFRBTree<K, V>
+where
+    K: 'static + Copy + Ord,
+    V: 'static + Copy,
+{
+    type Global = Global<Node<K, V>>;
+
+    type Local = Local<Node<K, V>>;
+
+    fn global(key_range_hint: usize) -> Self::Global {
+        Global::new(key_range_hint)
+    }
+
+    fn local(global: &Self::Global) -> Self::Local {
+        Local::new(global)
+    }
+
+    fn stats(global: &Self::Global) -> Stats {
+        global.stats()
+    }
+
+    fn pool(global: &Self::Global) -> Pool {
+        global.pool()
+    }
+
+    fn new(local: &Self::Local) -> Self {
+        EFRBTree::new(local)
+    }
+
+    #[inline(always)]
+    fn get(&self, key: &K, local: &Self::Local) -> Option<V> {
+        self.get(key, local)
+    }
+
+    #[inline(always)]
+    fn insert(&self, key: K, value: V, local: &Self::Local) -> bool {
+        self.insert(key, value, local)
+    }
+
+    #[inline(always)]
+    fn remove(&self, key: &K, local: &Self::Local) -> Option<V> {
+        self.delete(key, local)
+    }
+
+    fn validate(&self, local: &Self::Local) -> Result<Vec<K>, String> {
+        self.validate(local)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EFRBTree;
+    use crate::ds_impl::vbr::concurrent_map;
+
+    #[test]
+    fn smoke_efrb_tree() {
+        concurrent_map::tests::smoke::<EFRBTree<i32, i32>>();
+    }
+}
@@ -1,4 +1,5 @@
-use super::concurrent_map::ConcurrentMap;
+use super::concurrent_map::{epoch_advanced, ConcurrentMap};
+use smr_stats::{Pool, Stats};
 use vbr::CompareExchangeError::Success;
 use vbr::{Entry, Global, Guard, ImmAtomic, Local, MutAtomic, Shared};
 
@@ -77,6 +78,38 @@ where
         }
     }
 
+    /// Checks that the nodes are sorted and the unmarked ones have distinct keys, and returns the
+    /// keys of the unmarked nodes. Marked nodes that are not unlinked yet may remain.
+    fn validate(&self, local: &Local<Node<K, V>>) -> Result<Vec<K>, String> {
+        let guard = &local.guard();
+        let mut keys: Vec<K> = Vec::new();
+        let mut last: Option<K> = None;
+        let head = self.head.load(guard).map_err(epoch_advanced)?;
+        let mut curr = unsafe { head.deref() }
+            .next
+            .load(Ordering::Acquire, guard)
+            .map_err(epoch_advanced)?;
+        while let Some(curr_node) = curr.with_tag(0).as_ref() {
+            let key = curr_node.key.get(guard).map_err(epoch_advanced)?;
+            if last.map_or(false, |last| last > key) {
+                return Err(format!("the node #{} is out of order", keys.len()));
+            }
+            let next = curr_node
+                .next
+                .load(Ordering::Acquire, guard)
+                .map_err(epoch_advanced)?;
+            if next.tag() == 0 {
+                if keys.last() == Some(&key) {
+                    return Err(format!("the node #{} has a duplicate key", keys.len()));
+                }
+                keys.push(key);
+            }
+            last = Some(key);
+            curr = next;
+        }
+        Ok(keys)
+    }
+
     /// Clean up a chain of logically removed nodes in each traversal.
     #[inline]
     fn find_harris<'g>(
@@ -439,6 +472,14 @@ where
         Local::new(global)
     }
 
+    fn stats(global: &Self::Global) -> Stats {
+        global.stats()
+    }
+
+    fn pool(global: &Self::Global) -> Pool {
+        global.pool()
+    }
+
     fn new(local: &Self::Local) -> Self {
         Self {
             inner: List::new(local),
@@ -457,6 +498,10 @@ where
     fn remove<'g>(&'g self, key: &K, local: &Self::Local) -> Option<V> {
         self.inner.harris_remove(key, local)
     }
+
+    fn validate(&self, local: &Self::Local) -> Result<Vec<K>, String> {
+        self.inner.validate(local)
+    }
 }
 
 pub struct HMList<K, V>
@@ -483,6 +528,14 @@ where
         Local::new(global)
     }
 
+    fn stats(global: &Self::Global) -> Stats {
+        global.stats()
+    }
+
+    fn pool(global: &Self::Global) -> Pool {
+        global.pool()
+    }
+
     fn new(local: &Self::Local) -> Self {
         Self {
             inner: List::new(local),
@@ -501,6 +554,10 @@ where
     fn remove<'g>(&'g self, key: &K, local: &Self::Local) -> Option<V> {
         self.inner.harris_michael_remove(key, local)
     }
+
+    fn validate(&self, local: &Self::Local) -> Result<Vec<K>, String> {
+        self.inner.validate(local)
+    }
 }
 
 pub struct HHSList<K, V>
@@ -539,6 +596,14 @@ where
         Local::new(global)
     }
 
+    fn stats(global: &Self::Global) -> Stats {
+        global.stats()
+    }
+
+    fn pool(global: &Self::Global) -> Pool {
+        global.pool()
+    }
+
     fn new(local: &Self::Local) -> Self {
         Self {
             inner: List::new(local),
@@ -557,6 +622,10 @@ where
     fn remove<'g>(&'g self, key: &K, local: &Self::Local) -> Option<V> {
         self.inner.harris_remove(key, local)
     }
+
+    fn validate(&self, local: &Self::Local) -> Result<Vec<K>, String> {
+        self.inner.validate(local)
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,7 @@
 use vbr::{Global, Local};
 
 use super::concurrent_map::ConcurrentMap;
+use smr_stats::{Pool, Stats};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
@@ -55,6 +56,24 @@ where
         let i = Self::hash(&k);
         self.get_bucket(i).remove(k, local)
     }
+
+    /// Checks each bucket and that its keys belong to it.
+    pub fn validate(&self, local: &Local<Node<K, V>>) -> Result<Vec<K>, String> {
+        let mut keys = Vec::new();
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            let bucket_keys = bucket
+                .validate(local)
+                .map_err(|e| format!("bucket {i}: {e}"))?;
+            if bucket_keys
+                .iter()
+                .any(|k| Self::hash(k) % self.buckets.len() != i)
+            {
+                return Err(format!("bucket {i} has a key of another bucket"));
+            }
+            keys.extend(bucket_keys);
+        }
+        Ok(keys)
+    }
 }
 
 impl<K, V> ConcurrentMap<K, V> for HashMap<K, V>
@@ -74,6 +93,14 @@ where
         Local::new(global)
     }
 
+    fn stats(global: &Self::Global) -> Stats {
+        global.stats()
+    }
+
+    fn pool(global: &Self::Global) -> Pool {
+        global.pool()
+    }
+
     fn new(local: &Self::Local) -> Self {
         Self::with_capacity(30000, local)
     }
@@ -89,6 +116,10 @@ where
     fn remove(&self, key: &K, local: &Self::Local) -> Option<V> {
         self.remove(key, local)
     }
+
+    fn validate(&self, local: &Self::Local) -> Result<Vec<K>, String> {
+        self.validate(local)
+    }
 }
 
 #[cfg(test)]
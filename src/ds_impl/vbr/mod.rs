@@ -1,5 +1,7 @@
 pub mod concurrent_map;
 
+pub mod bonsai_tree;
+pub mod ellen_tree;
 pub mod list;
 pub mod michael_hash_map;
 pub mod natarajan_mittal_tree;
@@ -7,6 +9,8 @@ pub mod skip_list;
 
 pub use self::concurrent_map::ConcurrentMap;
 
+pub use bonsai_tree::BonsaiTreeMap;
+pub use ellen_tree::EFRBTree;
 pub use list::{HHSList, HList, HMList};
 pub use michael_hash_map::HashMap;
 pub use natarajan_mittal_tree::NMTreeMap;
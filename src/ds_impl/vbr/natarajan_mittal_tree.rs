@@ -2,7 +2,8 @@ use num::Bounded;
 use vbr::CompareExchangeError::*;
 use vbr::{ptr_with_tag, Entry, Global, Guard, ImmAtomic, Local, MutAtomic, Shared};
 
-use super::concurrent_map::ConcurrentMap;
+use super::concurrent_map::{epoch_advanced, ConcurrentMap};
+use smr_stats::{Pool, Stats};
 use std::cmp;
 use std::mem::zeroed;
 use std::sync::atomic::Ordering;
@@ -164,6 +165,47 @@ where
         NMTreeMap { r: Entry::new(r) }
     }
 
+    /// Checks that the tree is a leaf-oriented BST without marked edges, and returns the keys of
+    /// its leaves in order. The leaves with the maximum key are the sentinels.
+    pub fn validate(&self, local: &Local<Node<K, V>>) -> Result<Vec<K>, String> {
+        let guard = &local.guard();
+        let mut keys = Vec::new();
+        // Nodes with the bounds of the keys of their leaves, in the order of a DFS.
+        let r = self.r.load(guard).map_err(epoch_advanced)?;
+        let mut stack = vec![(r, None, None)];
+        while let Some((node, lo, hi)) = stack.pop() {
+            if node.tag() != 0 {
+                return Err("a marked edge remains".to_string());
+            }
+            let node_ref = unsafe { node.deref() };
+            let key = node_ref.key.get(guard).map_err(epoch_advanced)?;
+            let left = node_ref
+                .left
+                .load(Ordering::Acquire, guard)
+                .map_err(epoch_advanced)?;
+            let right = node_ref
+                .right
+                .load(Ordering::Acquire, guard)
+                .map_err(epoch_advanced)?;
+            match (left.is_null(), right.is_null()) {
+                (true, true) => {
+                    if key != K::max_value() {
+                        if lo.map_or(false, |lo| key < lo) || hi.map_or(false, |hi| key >= hi) {
+                            return Err("a leaf is in the wrong subtree".to_string());
+                        }
+                        keys.push(key);
+                    }
+                }
+                (false, false) => {
+                    stack.push((right, Some(key), hi));
+                    stack.push((left, lo, Some(key)));
+                }
+                _ => return Err("an internal node has a single child".to_string()),
+            }
+        }
+        Ok(keys)
+    }
+
     // All `Shared<_>` fields are unmarked.
     fn seek<'g>(
         &'g self,
@@ -513,6 +555,14 @@ where
         Local::new(global)
     }
 
+    fn stats(global: &Self::Global) -> Stats {
+        global.stats()
+    }
+
+    fn pool(global: &Self::Global) -> Pool {
+        global.pool()
+    }
+
     fn new(local: &Self::Local) -> Self {
         NMTreeMap::new(local)
     }
@@ -531,6 +581,10 @@ where
     fn remove(&self, key: &K, local: &Self::Local) -> Option<V> {
         self.remove(key, local)
     }
+
+    fn validate(&self, local: &Self::Local) -> Result<Vec<K>, String> {
+        self.validate(local)
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     mem::zeroed,
     sync::atomic::{AtomicUsize, Ordering},
 };
@@ -6,7 +7,8 @@ use std::{
 use vbr::CompareExchangeError::*;
 use vbr::{Entry, Global, Guard, ImmAtomic, Local, MutAtomic, Shared};
 
-use super::concurrent_map::ConcurrentMap;
+use super::concurrent_map::{epoch_advanced, ConcurrentMap};
+use smr_stats::{Pool, Stats};
 
 const MAX_HEIGHT: usize = 32;
 
@@ -26,12 +28,12 @@ where
 
 fn generate_height() -> usize {
     // returns 1 with probability 3/4
-    if rand::random::<usize>() % 4 < 3 {
+    if crate::utils::random::<usize>() % 4 < 3 {
         return 1;
     }
     // returns h with probability 2^(−(h+1))
     let mut height = 2;
-    while height < MAX_HEIGHT && rand::random::<bool>() {
+    while height < MAX_HEIGHT && crate::utils::random::<bool>() {
         height += 1;
     }
     height
@@ -145,6 +147,55 @@ where
         };
     }
 
+    /// Checks that each level is sorted and only links the unmarked nodes of the lower levels
+    /// that are tall enough, and returns the keys of the unmarked nodes. Marked nodes that are not
+    /// unlinked yet may remain.
+    pub fn validate(&self, local: &Local<Node<K, V>>) -> Result<Vec<K>, String> {
+        let guard = &local.guard();
+        let head = self.head.load(guard).map_err(epoch_advanced)?;
+        let head = unsafe { head.deref() };
+        let mut keys: Vec<K> = Vec::new();
+        // The unmarked nodes of the level below.
+        let mut lower = HashSet::new();
+        for level in 0..MAX_HEIGHT {
+            let mut nodes = HashSet::new();
+            let mut last: Option<K> = None;
+            let mut curr = head.next[level]
+                .load(Ordering::Acquire, guard)
+                .map_err(epoch_advanced)?;
+            while let Some(node) = curr.with_tag(0).as_ref() {
+                let key = node.key.get(guard).map_err(epoch_advanced)?;
+                if last.map_or(false, |last| last > key) {
+                    return Err(format!("level {level} is out of order"));
+                }
+                last = Some(key);
+                let ptr = curr.with_tag(0).as_raw();
+                curr = node.next[level]
+                    .load(Ordering::Acquire, guard)
+                    .map_err(epoch_advanced)?;
+                let next = node.next[0]
+                    .load(Ordering::Acquire, guard)
+                    .map_err(epoch_advanced)?;
+                if next.tag() & 1 != 0 {
+                    continue;
+                }
+                let height = node.height.get(guard).map_err(epoch_advanced)?;
+                if level >= height || (level > 0 && !lower.contains(&ptr)) {
+                    return Err(format!("level {level} links a node not in the lower level"));
+                }
+                if level == 0 {
+                    if keys.last() == Some(&key) {
+                        return Err("duplicate keys".to_string());
+                    }
+                    keys.push(key);
+                }
+                nodes.insert(ptr);
+            }
+            lower = nodes;
+        }
+        Ok(keys)
+    }
+
     fn find_optimistic<'g>(
         &'g self,
         key: &K,
@@ -406,6 +457,14 @@ where
         Local::new(global)
     }
 
+    fn stats(global: &Self::Global) -> Stats {
+        global.stats()
+    }
+
+    fn pool(global: &Self::Global) -> Pool {
+        global.pool()
+    }
+
     fn new(local: &Self::Local) -> Self {
         SkipList::new(local)
     }
@@ -433,6 +492,10 @@ where
     fn remove<'g>(&'g self, key: &'g K, local: &Self::Local) -> Option<V> {
         self.remove(key, local)
     }
+
+    fn validate(&self, local: &Self::Local) -> Result<Vec<K>, String> {
+        self.validate(local)
+    }
 }
 
 #[cfg(test)]
@@ -61,9 +61,9 @@ extern crate crossbeam_utils;
 #[macro_use]
 extern crate bitflags;
 extern crate clap;
-extern crate typenum;
 
 #[macro_use]
 mod utils;
+pub mod bench;
 pub mod config;
 pub mod ds_impl;
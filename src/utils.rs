@@ -1,3 +1,8 @@
+use rand::distributions::{Distribution, Standard};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+
 #[macro_export]
 /// Ok or executing the given expression.
 macro_rules! ok_or {
@@ -19,3 +24,23 @@ macro_rules! some_or {
         }
     }};
 }
+
+thread_local! {
+    /// The random number generator used inside the data structures (e.g., for the heights of
+    /// skip list nodes), which the benchmark driver seeds for reproducible runs (`--seed`).
+    static THREAD_RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// Seeds the random number generator of the current thread used by [`random`].
+pub fn seed_thread_rng(seed: u64) {
+    THREAD_RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+/// Like `rand::random`, but draws from the (seedable) random number generator of the current
+/// thread.
+pub fn random<T>() -> T
+where
+    Standard: Distribution<T>,
+{
+    THREAD_RNG.with(|rng| rng.borrow_mut().gen())
+}
@@ -2,10 +2,10 @@
 
 export RUST_BACKTRACE=1 RUSTFLAGS='-Z sanitizer=address' 
 
-circ_ebr="cargo run --bin circ-ebr --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- "
-circ_hp="cargo run --bin circ-hp --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- "
-cdrc_ebr="cargo run --bin cdrc-ebr --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- "
-cdrc_hp="cargo run --bin cdrc-hp --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- "
+circ_ebr="cargo run --bin smr-bench --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- -mcirc-ebr "
+circ_hp="cargo run --bin smr-bench --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- -mcirc-hp "
+cdrc_ebr="cargo run --bin smr-bench --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- -mcdrc-ebr "
+cdrc_hp="cargo run --bin smr-bench --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- -mcdrc-hp "
 double_link="cargo run --bin double-link --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- "
 
 set -e
@@ -2,7 +2,7 @@
 
 export RUST_BACKTRACE=1 RUSTFLAGS='-Z sanitizer=address' 
 
-hps="cargo run --bin hp-pp --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- "
+hps="cargo run --bin smr-bench --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- -mhp-pp "
 
 set -e
 for i in {1..5000}; do
@@ -2,8 +2,8 @@
 
 export RUST_BACKTRACE=1 RUSTFLAGS='-Z sanitizer=address' 
 
-hps="cargo run --bin hp-brcu --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- "
-hps0="cargo run --bin hp-rcu --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- "
+hps="cargo run --bin smr-bench --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- -mhp-brcu "
+hps0="cargo run --bin smr-bench --profile=release-simple --target x86_64-unknown-linux-gnu --features sanitize -- -mhp-rcu "
 
 set -e
 for i in {1..5000}; do
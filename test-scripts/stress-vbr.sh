@@ -1,6 +1,6 @@
 #!/usr/bin/env bash
 
-vbr="cargo run --bin vbr --release -- "
+vbr="cargo run --bin smr-bench --release -- -mvbr "
 
 set -e
 for i in {1..5000}; do